@@ -0,0 +1,32 @@
+//! Feeds arbitrary input to `via_config_load_from_strings` under the size
+//! limits recommended for semi-trusted sources. The input is split at NUL
+//! bytes into the system, hardware and profile documents.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use std::ffi::CString;
+use std::sync::Once;
+
+static LIMITS: Once = Once::new();
+
+fuzz_target!(|data: &[u8]| {
+    LIMITS.call_once(|| via_config::via_config_set_size_limits(64 * 1024, 4096, 4096));
+
+    let mut parts = data.splitn(3, |&b| b == 0).map(|part| CString::new(part).expect("parts have no NUL bytes"));
+    let system = parts.next().unwrap_or_default();
+    let hardware = parts.next().unwrap_or_default();
+    let profile = parts.next().unwrap_or_default();
+
+    unsafe {
+        let config = via_config::via_config_load_from_strings(system.as_ptr(), hardware.as_ptr(), profile.as_ptr());
+        if config.is_null() {
+            return;
+        }
+        let mut dump = std::ptr::null_mut();
+        if via_config::via_config_dump_yaml(config, c"".as_ptr(), 0, 0, &mut dump) == via_config::ViaConfigStatus::Ok {
+            via_config::via_config_free_string(dump);
+        }
+        via_config::via_config_free(config);
+    }
+});
@@ -19,6 +19,15 @@
  * 2. Check if the returned handle is not NULL.
  * 3. Use the various `via_config_get_*()` functions to retrieve values.
  * 4. When done, call `via_config_free()` with the handle to prevent memory leaks.
+ *
+ * KEYS:
+ * Values are addressed by dot-separated keys, one segment per mapping level
+ * (e.g., "camera.device-id"). A mapping key that itself contains a dot is
+ * written with the dot escaped by a backslash, e.g.
+ * "perception.model-paths.yolo\\.v8" (in C source) names the entry `yolo.v8`;
+ * a literal backslash is written as two. Keys returned by the library (such as
+ * from `via_config_query_prefix`) are escaped the same way, so they can be passed
+ * back to any getter.
  */
 
 #ifndef VIA_CONFIG_H
@@ -33,6 +42,145 @@
 extern "C" {
 #endif // __cplusplus
 
+/**
+ * @brief The version of the C ABI this header describes.
+ *
+ * Compare it with `via_config_abi_version()` at startup to detect a header
+ * that does not match the linked library. It is incremented whenever a
+ * function, type or constant is added to or changed in this header.
+ */
+#define VIA_CONFIG_ABI_VERSION 36
+
+/**
+ * @brief Load flag: fail with a specific error if an alias (`*name`) references
+ * an anchor (`&name`) that is not defined earlier in the same file.
+ */
+#define VIA_CONFIG_FLAG_RESOLVE_ANCHORS_STRICT (1 << 0)
+
+/**
+ * @brief Load flag: make the typed getters report an explicit null value
+ * (`key:` with no value) as `ViaConfigStatus_KeyNotFound` instead of
+ * `ViaConfigStatus_TypeError`, so that it can mean "use the default".
+ */
+#define VIA_CONFIG_FLAG_NULL_AS_MISSING (1 << 1)
+
+/**
+ * @brief Load flag: record how long reading and parsing each source file took,
+ * for `via_config_get_load_timings()`.
+ */
+#define VIA_CONFIG_FLAG_RECORD_TIMINGS (1 << 2)
+
+/**
+ * @brief Load flag: make the numeric getters accept strings that hold a number,
+ * such as a quoted `"4096"`, instead of returning `ViaConfigStatus_TypeError`.
+ * Strings that are not numbers are still a `ViaConfigStatus_TypeError`.
+ */
+#define VIA_CONFIG_FLAG_COERCE_NUMERIC_STRINGS (1 << 3)
+
+/**
+ * @brief Load flag: make `via_config_get_boolean()` also accept `yes`/`no`,
+ * `on`/`off` and `1`/`0` (case-insensitive), which YAML 1.2 reads as strings
+ * or integers rather than booleans.
+ */
+#define VIA_CONFIG_FLAG_LENIENT_BOOLEANS (1 << 4)
+
+/**
+ * @brief Load flag: fail with `ViaConfigStatus_TypeError` if a source changes
+ * the kind of a value an earlier source defined (mapping, sequence or scalar),
+ * e.g. `threads` redefined from a mapping to a string, instead of silently
+ * replacing it. `via_config_last_error_message()` names the conflicting key.
+ */
+#define VIA_CONFIG_FLAG_STRICT_MERGE (1 << 5)
+
+/**
+ * @brief Load flag: after merging, replace `{{ dot.key }}` references in string
+ * values with the referenced value, e.g. `greeting: "Hello {{ profile.user-name }}"`.
+ * A reference to an unknown key or to a collection, or a reference cycle,
+ * fails the load with `ViaConfigStatus_ParseError`.
+ */
+#define VIA_CONFIG_FLAG_RESOLVE_TEMPLATES (1 << 6)
+
+/**
+ * @brief Load flag: load only the system and hardware files and ignore the
+ * user profile, e.g. to reproduce factory defaults in a diagnostic mode.
+ * `via_config_load_with_flags()` then also accepts a `NULL` profile path.
+ */
+#define VIA_CONFIG_FLAG_SKIP_PROFILE (1 << 7)
+
+/**
+ * @brief Load flag: treat a profile file that does not exist as an empty
+ * profile instead of failing with `ViaConfigStatus_FileNotFound`, e.g. in
+ * early provisioning. The system and hardware files remain mandatory, and a
+ * profile that exists but cannot be read or parsed still fails the load.
+ */
+#define VIA_CONFIG_FLAG_PROFILE_OPTIONAL (1 << 8)
+
+/**
+ * @brief Load flag: record every value that a later source replaces with a
+ * different one during the merge, for `via_config_get_override_log()`.
+ */
+#define VIA_CONFIG_FLAG_RECORD_OVERRIDES (1 << 9)
+
+/**
+ * @brief Load flag: accept files that hold several YAML documents separated
+ * by `---` and merge them in order, later documents winning. Without it, such
+ * a file fails the load with `ViaConfigStatus_ParseError` instead of silently
+ * using only one of the documents.
+ */
+#define VIA_CONFIG_FLAG_MERGE_DOCUMENTS (1 << 10)
+
+/**
+ * @brief Load flag: fail the load with `ViaConfigStatus_ValidationFailed` if
+ * the merged configuration does not match the schema exported by
+ * `via_config_export_schema()`, e.g. a required setting is missing or has the
+ * wrong type. The files merge side by side, so the schema's root holds the
+ * settings of all three files. `via_config_last_error_message()` names the
+ * problem. Without it, partial configurations load normally.
+ */
+#define VIA_CONFIG_FLAG_STRICT_SCHEMA (1 << 11)
+
+/**
+ * @brief Load flag: count how often each key is looked up by the typed
+ * getters, and how often it was not found, for `via_config_dump_metrics()`.
+ */
+#define VIA_CONFIG_FLAG_RECORD_METRICS (1 << 12)
+
+/**
+ * @brief Load flag: fail the load with `ViaConfigStatus_ValidationFailed` if
+ * the merged configuration has a top-level key that the schema exported by
+ * `via_config_export_schema()` does not define (other than `schema-version`),
+ * e.g. a misspelled `threds:`. The files merge side by side, so the allowed
+ * keys are the settings of all three files, such as `log-level`, `camera` and
+ * `user-name`.
+ * `via_config_last_error_message()` names each such key and the first source
+ * that contains it. This catches the most common typos at a fraction of the
+ * cost of `VIA_CONFIG_FLAG_STRICT_SCHEMA`.
+ */
+#define VIA_CONFIG_FLAG_STRICT_TOP_LEVEL (1 << 13)
+
+/**
+ * @brief Log levels passed to a `ViaConfigLogCallback`, in increasing severity.
+ */
+#define VIA_CONFIG_LOG_LEVEL_DEBUG 0
+#define VIA_CONFIG_LOG_LEVEL_INFO 1
+#define VIA_CONFIG_LOG_LEVEL_WARN 2
+#define VIA_CONFIG_LOG_LEVEL_ERROR 3
+
+/**
+ * @brief Dump flag: round floating-point values to the requested number of
+ * decimal places in the output of `via_config_dump_yaml()`, so that snapshots
+ * are stable across merge round-trips.
+ */
+#define VIA_CONFIG_DUMP_FLAG_ROUND_FLOATS (1 << 0)
+
+/**
+ * @brief Dump flag: sort the keys of every mapping in the output of
+ * `via_config_dump_yaml()`, so that equal configurations produce
+ * byte-identical dumps (and hashes) regardless of the key order in their
+ * source files.
+ */
+#define VIA_CONFIG_DUMP_FLAG_SORT_KEYS (1 << 1)
+
 /**
  * @brief An opaque handle to the internal Rust configuration structure.
  *
@@ -42,6 +190,13 @@ extern "C" {
  */
 typedef struct ViaConfig ViaConfig;
 
+/**
+ * @brief An opaque iterator over the entries of one mapping.
+ *
+ * Created with `via_config_iter_create()` and freed with `via_config_iter_free()`.
+ */
+typedef struct ViaConfigIter ViaConfigIter;
+
 /**
  * @brief Represents the status of a configuration operation.
  *
@@ -67,7 +222,10 @@ typedef enum ViaConfigStatus {
   ViaConfigStatus_KeyNotFound = 3,
   /**
    * The value for the requested key has a different type than expected
-   * (e.g., asking for an integer but the value is a string).
+   * (e.g., asking for an integer but the value is a string). The typed
+   * getters describe the expected and found types through
+   * `via_config_last_error_message()`, e.g. "Expected integer for key
+   * 'camera.device-id' but found string".
    */
   ViaConfigStatus_TypeError = 4,
   /**
@@ -78,8 +236,94 @@ typedef enum ViaConfigStatus {
    * An unknown or internal error occurred in the Rust library.
    */
   ViaConfigStatus_InternalError = 6,
+  /**
+   * One or more validation checks rejected the configuration. A report
+   * describing the failures is returned alongside this status.
+   */
+  ViaConfigStatus_ValidationFailed = 7,
+  /**
+   * The configuration has been frozen with `via_config_freeze()` and cannot
+   * be modified.
+   */
+  ViaConfigStatus_Frozen = 8,
 } ViaConfigStatus;
 
+/**
+ * @brief The C type of a struct field filled by `via_config_fill_struct()`.
+ */
+typedef enum ViaConfigFieldType {
+  ViaConfigFieldType_Bool = 0,
+  ViaConfigFieldType_I32 = 1,
+  ViaConfigFieldType_U32 = 2,
+  ViaConfigFieldType_U16 = 3,
+  ViaConfigFieldType_I64 = 4,
+  ViaConfigFieldType_F32 = 5,
+  ViaConfigFieldType_F64 = 6,
+  /**
+   * An owned `char*` that the caller must free with `via_config_free_string()`.
+   */
+  ViaConfigFieldType_String = 7,
+} ViaConfigFieldType;
+
+/**
+ * @brief Describes one field of a C struct to be filled from the configuration.
+ *
+ * Typically built with `offsetof()`, e.g.
+ * `{ "camera.device-id", offsetof(CameraSettings, device_id), ViaConfigFieldType_I32 }`.
+ */
+typedef struct ViaConfigField {
+  const char *key;
+  size_t offset;
+  ViaConfigFieldType field_type;
+} ViaConfigField;
+
+/**
+ * @brief The YAML type of a configuration value, as reported by
+ * `via_config_list_all()`, `via_config_get_any()` and `via_config_visit()`.
+ */
+typedef enum ViaConfigValueType {
+  ViaConfigValueType_Null = 0,
+  ViaConfigValueType_Bool = 1,
+  ViaConfigValueType_Integer = 2,
+  ViaConfigValueType_Float = 3,
+  ViaConfigValueType_String = 4,
+  ViaConfigValueType_Sequence = 5,
+  /**
+   * Only reported by `via_config_list_all()` for an empty mapping, since it
+   * flattens non-empty ones. `via_config_visit()` reports every mapping.
+   */
+  ViaConfigValueType_Mapping = 6,
+} ViaConfigValueType;
+
+/**
+ * @brief A custom check for a configuration value.
+ *
+ * Receives the string form of the value (valid only for the duration of the
+ * call) and returns `true` if the value is acceptable.
+ */
+typedef bool (*ViaConfigValidator)(const char *value_as_cstr);
+
+/**
+ * @brief Receives the library's log messages.
+ *
+ * `level` is one of the `VIA_CONFIG_LOG_LEVEL_*` values. `msg` is only valid
+ * for the duration of the call.
+ */
+typedef void (*ViaConfigLogCallback)(int32_t level, const char *msg);
+
+/**
+ * @brief Receives each node of the tree from `via_config_visit()`.
+ *
+ * `path` is the node's dot-separated key, `value_yaml` its value as YAML, or
+ * `NULL` for a mapping, and `user_data` the pointer passed to
+ * `via_config_visit()`. The strings are only valid for the duration of the
+ * call.
+ */
+typedef void (*ViaConfigVisitor)(const char *path,
+                                 ViaConfigValueType value_type,
+                                 const char *value_yaml,
+                                 void *user_data);
+
 /**
  * @brief Loads and parses configuration from specified YAML files.
  *
@@ -92,14 +336,186 @@ typedef enum ViaConfigStatus {
  * @param profile_path  A UTF-8 encoded, null-terminated string for the user profile path.
  *
  * @return A pointer to a `ViaConfig` handle on success.
- * @return `NULL` on failure (e.g., file not found, parse error). Check logs for details.
+ * @return `NULL` on failure (e.g., file not found, parse error). Use
+ *         `via_config_last_error_status()` and `via_config_last_error_message()`
+ *         for details.
  *
+ * @note Files may start with a UTF-8 byte order mark and may use CRLF line endings.
+ * @note An empty or comment-only file contributes no settings. Any other file
+ *       whose top level is not a mapping fails with `ViaConfigStatus_ParseError`.
+ * @note A profile may inherit from a parent profile with a top-level
+ *       `extends: <path>` key, resolved relative to the profile's directory.
+ *       The parent is loaded first and the profile merged on top of it. A
+ *       parent may extend another profile in turn, each profile overriding
+ *       the ones it extends; a chain that leads back to a profile already in
+ *       it fails with `ViaConfigStatus_ParseError`, naming the cycle.
+ * @note A value written as `{ $secret_file: <path> }` is replaced with the
+ *       contents of that file, trimmed of surrounding whitespace, e.g.
+ *       `api-key: { $secret_file: /run/secrets/api-key }`. A relative path is
+ *       resolved against the directory of the file containing it, and the
+ *       root jail applies. An unreadable secret file fails with
+ *       `ViaConfigStatus_FileNotFound`. Keys read this way are redacted in
+ *       dumps, as if registered with `via_config_add_secret_key()`.
  * @note The returned pointer MUST be freed using `via_config_free()` to avoid memory leaks.
  */
 ViaConfig *via_config_load(const char *system_path,
                            const char *hardware_path,
                            const char *profile_path);
 
+/**
+ * @brief Loads configuration like `via_config_load()`, with optional behaviour flags.
+ *
+ * YAML anchors and aliases are always expanded before the files are merged.
+ *
+ * @param system_path   A UTF-8 encoded, null-terminated string for the system config path.
+ * @param hardware_path A UTF-8 encoded, null-terminated string for the hardware config path.
+ * @param profile_path  A UTF-8 encoded, null-terminated string for the user profile path.
+ *                      May be `NULL` with `VIA_CONFIG_FLAG_SKIP_PROFILE`.
+ * @param flags         A bitwise OR of `VIA_CONFIG_FLAG_*` constants, or 0.
+ *
+ * @return A pointer to a `ViaConfig` handle on success.
+ * @return `NULL` on failure. Use `via_config_last_error_message()` for details.
+ *
+ * @note The returned pointer MUST be freed using `via_config_free()` to avoid memory leaks.
+ */
+ViaConfig *via_config_load_with_flags(const char *system_path,
+                                      const char *hardware_path,
+                                      const char *profile_path,
+                                      uint32_t flags);
+
+/**
+ * @brief Loads configuration with a user profile selected by name.
+ *
+ * The profile is resolved as `<profiles_dir>/<profile_name>.yml`, following
+ * the layout of the `config/profiles/` directory.
+ *
+ * @param system_path   A UTF-8 encoded, null-terminated string for the system config path.
+ * @param hardware_path A UTF-8 encoded, null-terminated string for the hardware config path.
+ * @param profiles_dir  A UTF-8 encoded, null-terminated path of the profiles directory.
+ * @param profile_name  The profile name without extension (e.g., "joao").
+ *
+ * @return A pointer to a `ViaConfig` handle on success.
+ * @return `NULL` on failure. If the profile does not exist,
+ *         `via_config_last_error_status()` returns `ViaConfigStatus_FileNotFound`.
+ *
+ * @note The returned pointer MUST be freed using `via_config_free()` to avoid memory leaks.
+ */
+ViaConfig *via_config_load_profile(const char *system_path,
+                                   const char *hardware_path,
+                                   const char *profiles_dir,
+                                   const char *profile_name);
+
+/**
+ * @brief Loads configuration from a single file with per-environment sections.
+ *
+ * The file has top-level sections such as `default:`, `dev:` and `prod:`. The
+ * `default` section is merged with the section named `env_name`, with the
+ * environment winning. `via_config_get_source()` reports 0 for values from
+ * `default` and 1 for values from the environment section.
+ *
+ * @param path     A UTF-8 encoded, null-terminated path of the config file.
+ * @param env_name The environment section to apply (e.g., "prod").
+ *
+ * @return A pointer to a `ViaConfig` handle on success.
+ * @return `NULL` on failure. If the section does not exist,
+ *         `via_config_last_error_status()` returns `ViaConfigStatus_KeyNotFound`.
+ *
+ * @note The returned pointer MUST be freed using `via_config_free()` to avoid memory leaks.
+ */
+ViaConfig *via_config_load_environment(const char *path, const char *env_name);
+
+/**
+ * @brief Loads a configuration file written in the legacy flat-key format.
+ *
+ * Older TrackieLLM versions joined nested keys with underscores, e.g.
+ * `camera_device_id: 0` for what is now `camera: { device-id: 0 }`. Flat keys
+ * are matched against the current configuration layout and expanded into
+ * nested mappings, so old files keep working. Keys that are already nested,
+ * and flat keys that match no known setting, are kept unchanged.
+ *
+ * @param path A UTF-8 encoded, null-terminated path of the legacy file.
+ *
+ * @return A pointer to a `ViaConfig` handle on success.
+ * @return `NULL` on failure. Use `via_config_last_error_status()` and
+ *         `via_config_last_error_message()` for details.
+ *
+ * @note The returned pointer MUST be freed using `via_config_free()`.
+ */
+ViaConfig *via_config_load_legacy(const char *path);
+
+/**
+ * @brief Loads configuration with several user profiles applied in order.
+ *
+ * The system and hardware files are merged first, then each profile in turn,
+ * so later profiles override earlier ones (e.g., base, then accessibility,
+ * then language). `via_config_get_source()` reports 0 for system, 1 for
+ * hardware and `2 + i` for `profile_paths[i]`.
+ *
+ * @param system_path   A UTF-8 encoded, null-terminated path to the system config.
+ * @param hardware_path A UTF-8 encoded, null-terminated path to the hardware config.
+ * @param profile_paths An array of `profile_count` UTF-8 encoded, null-terminated profile paths.
+ *                      May be `NULL` if `profile_count` is 0.
+ * @param profile_count The number of entries in `profile_paths`.
+ *
+ * @return A pointer to a `ViaConfig` handle on success.
+ * @return `NULL` on failure. Use `via_config_last_error_message()` for details.
+ *
+ * @note The returned pointer MUST be freed using `via_config_free()` to avoid memory leaks.
+ */
+ViaConfig *via_config_load_with_profiles(const char *system_path,
+                                         const char *hardware_path,
+                                         const char *const *profile_paths,
+                                         size_t profile_count);
+
+/**
+ * @brief Loads configuration through an on-disk cache of the merged result.
+ *
+ * Behaves like `via_config_load_with_flags()`, but first checks the cache file
+ * at `cache_path`. If it was written for the current paths, sizes and
+ * modification times of the three files (and the same flags, facts and
+ * limits), the merged configuration is restored from it without parsing any
+ * YAML. Otherwise the files are parsed and the cache is rewritten.
+ *
+ * @param system_path   A UTF-8 encoded, null-terminated path to the system config.
+ * @param hardware_path A UTF-8 encoded, null-terminated path to the hardware config.
+ * @param profile_path  A UTF-8 encoded, null-terminated path to the user profile.
+ * @param cache_path    A UTF-8 encoded, null-terminated path of the cache file.
+ * @param flags         A bitwise OR of `VIA_CONFIG_FLAG_*` values, or 0.
+ *
+ * @return A pointer to a `ViaConfig` handle on success.
+ * @return `NULL` on failure. Use `via_config_last_error_message()` for details.
+ *
+ * @note Failing to read or write the cache is not an error; the files are parsed instead.
+ * @note A profile that uses `extends` is never cached, since changes to its
+ *       parent would not invalidate the cache. Neither is a configuration
+ *       that reads a `$secret_file`, so that secrets are not written to disk.
+ * @note The returned pointer MUST be freed using `via_config_free()` to avoid memory leaks.
+ */
+ViaConfig *via_config_load_cached(const char *system_path,
+                                  const char *hardware_path,
+                                  const char *profile_path,
+                                  const char *cache_path,
+                                  uint32_t flags);
+
+/**
+ * @brief Loads configuration from in-memory YAML documents instead of files.
+ *
+ * The documents are parsed and merged exactly as in `via_config_load()`
+ * (profile > hardware > system).
+ *
+ * @param system_yaml   A UTF-8 encoded, null-terminated string holding the system config.
+ * @param hardware_yaml A UTF-8 encoded, null-terminated string holding the hardware config.
+ * @param profile_yaml  A UTF-8 encoded, null-terminated string holding the user profile.
+ *
+ * @return A pointer to a `ViaConfig` handle on success.
+ * @return `NULL` on failure (e.g., parse error). Use `via_config_last_error_message()` for details.
+ *
+ * @note The returned pointer MUST be freed using `via_config_free()` to avoid memory leaks.
+ */
+ViaConfig *via_config_load_from_strings(const char *system_yaml,
+                                        const char *hardware_yaml,
+                                        const char *profile_yaml);
+
 /**
  * @brief Frees all memory associated with a `ViaConfig` handle.
  *
@@ -129,6 +545,40 @@ void via_config_free(ViaConfig *config);
  */
 ViaConfigStatus via_config_get_string(const ViaConfig *config, const char *key, const char **out_value);
 
+/**
+ * @brief Copies a string value into a caller-allocated buffer, without allocating.
+ *
+ * Follows the usual two-call convention: `out_needed` always receives the
+ * buffer size the whole value needs, including the terminating NUL. A value
+ * that does not fit is truncated at a UTF-8 character boundary and still
+ * NUL-terminated.
+ *
+ * @code{.c}
+ * char level[16];
+ * size_t needed;
+ * if (via_config_copy_string(config, "log.level", level, sizeof level, &needed) == ViaConfigStatus_Ok
+ *     && needed > sizeof level) {
+ *   // Truncated: retry with a buffer of `needed` bytes.
+ * }
+ * @endcode
+ *
+ * @param config     A valid `ViaConfig` handle.
+ * @param key        A null-terminated string representing the key (e.g., "log.level").
+ * @param out_buf    A buffer of `buf_len` bytes. May be `NULL` if `buf_len` is 0,
+ *                   to query the size only.
+ * @param buf_len    The size of `out_buf` in bytes.
+ * @param out_needed A pointer to a `size_t` that receives the size needed for the whole value.
+ *
+ * @return `ViaConfigStatus_Ok` on success, including when the value was truncated.
+ * @return `ViaConfigStatus_TypeError` if the value is not a string.
+ * @return An error status code on failure. `out_buf` will be untouched.
+ */
+ViaConfigStatus via_config_copy_string(const ViaConfig *config,
+                                       const char *key,
+                                       char *out_buf,
+                                       size_t buf_len,
+                                       size_t *out_needed);
+
 /**
  * @brief Retrieves an integer value from the configuration.
  *
@@ -137,25 +587,172 @@ ViaConfigStatus via_config_get_string(const ViaConfig *config, const char *key,
  * @param out_value A pointer to an `int64_t` where the result will be stored.
  *
  * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_TypeError` if the value is not an integer, or is
+ *         above `INT64_MAX`; the last error message then says it is out of
+ *         range. Use `via_config_get_u64()` for such values.
  * @return An error status code on failure. `out_value` will be untouched.
  */
 ViaConfigStatus via_config_get_integer(const ViaConfig *config, const char *key, int64_t *out_value);
 
+/**
+ * @brief Retrieves a non-negative integer value, up to `UINT64_MAX`.
+ *
+ * YAML integers above `UINT64_MAX` cannot be loaded at all; quote such a value
+ * to keep it as a string.
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param key A null-terminated string representing the key (e.g., "storage.quota-bytes").
+ * @param out_value A pointer to a `uint64_t` where the result will be stored.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_TypeError` if the value is not an integer or is negative.
+ * @return An error status code on failure. `out_value` will be untouched.
+ */
+ViaConfigStatus via_config_get_u64(const ViaConfig *config, const char *key, uint64_t *out_value);
+
+/**
+ * @brief Retrieves an integer value that must fit in a `uint32_t`.
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param key A null-terminated string representing the key (e.g., "camera.resolution.width").
+ * @param out_value A pointer to a `uint32_t` where the result will be stored.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_TypeError` if the value is not an integer or is negative or larger than `UINT32_MAX`.
+ * @return An error status code on failure. `out_value` will be untouched.
+ */
+ViaConfigStatus via_config_get_u32(const ViaConfig *config, const char *key, uint32_t *out_value);
+
+/**
+ * @brief Retrieves an integer value that must fit in a `int32_t`.
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param key A null-terminated string representing the key (e.g., "camera.resolution.width").
+ * @param out_value A pointer to a `int32_t` where the result will be stored.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_TypeError` if the value is not an integer or is outside the `int32_t` range.
+ * @return An error status code on failure. `out_value` will be untouched.
+ */
+ViaConfigStatus via_config_get_i32(const ViaConfig *config, const char *key, int32_t *out_value);
+
+/**
+ * @brief Retrieves an integer value that must fit in a `uint16_t`.
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param key A null-terminated string representing the key (e.g., "camera.resolution.width").
+ * @param out_value A pointer to a `uint16_t` where the result will be stored.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_TypeError` if the value is not an integer or is negative or larger than `UINT16_MAX`.
+ * @return An error status code on failure. `out_value` will be untouched.
+ */
+ViaConfigStatus via_config_get_u16(const ViaConfig *config, const char *key, uint16_t *out_value);
+
+/**
+ * @brief Retrieves an integer value clamped into `[min, max]`.
+ *
+ * A fail-soft alternative to range validation for devices that must keep
+ * running: a misconfigured `threads.perception: 9999` read with a maximum of
+ * 16 yields 16 instead of an error. Clamping is logged as a warning.
+ *
+ * @param config      A valid `ViaConfig` handle.
+ * @param key         A null-terminated string representing the key (e.g., "threads.perception").
+ * @param min         The smallest acceptable value.
+ * @param max         The largest acceptable value.
+ * @param out_value   A pointer to an `int64_t` where the clamped result will be stored.
+ * @param out_clamped A pointer to a `bool` that receives `true` if the value was outside the range.
+ *
+ * @return `ViaConfigStatus_Ok` on success, whether or not the value was clamped.
+ * @return `ViaConfigStatus_TypeError` if the value is not an integer.
+ * @return `ViaConfigStatus_InternalError` if `min` is greater than `max`.
+ * @return An error status code on failure. `out_value` will be untouched.
+ */
+ViaConfigStatus via_config_get_integer_clamped(const ViaConfig *config,
+                                               const char *key,
+                                               int64_t min,
+                                               int64_t max,
+                                               int64_t *out_value,
+                                               bool *out_clamped);
+
 /**
  * @brief Retrieves a floating-point value from the configuration.
  *
+ * Integer values (e.g., `5` or `-1`) are promoted to `double`. Scientific
+ * notation such as `1e-3` is accepted.
+ *
  * @param config A valid `ViaConfig` handle.
  * @param key A null-terminated string representing the key (e.g., "perception.threshold.detection").
  * @param out_value A pointer to a `double` where the result will be stored.
  *
  * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_TypeError` if the value is not a number.
  * @return An error status code on failure. `out_value` will be untouched.
  */
 ViaConfigStatus via_config_get_float(const ViaConfig *config, const char *key, double *out_value);
 
+/**
+ * @brief Retrieves a numeric value converted to another unit.
+ *
+ * Reads the value like `via_config_get_float()`, promoting integers, and
+ * multiplies it by `scale`. For example, a `hardware.microphone.sample-rate`
+ * of `44100` (Hz) read with a scale of `0.001` yields `44.1` (kHz).
+ *
+ * @param config    A valid `ViaConfig` handle.
+ * @param key       A null-terminated string representing the key (e.g., "hardware.microphone.sample-rate").
+ * @param scale     The factor to multiply the value by.
+ * @param out_value A pointer to a `double` where the scaled result will be stored.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_TypeError` if the value is not a number.
+ * @return An error status code on failure. `out_value` will be untouched.
+ */
+ViaConfigStatus via_config_get_scaled_float(const ViaConfig *config,
+                                            const char *key,
+                                            double scale,
+                                            double *out_value);
+
+/**
+ * @brief Copies a numeric sequence into a fixed-size buffer.
+ *
+ * Avoids allocating for small lists of known size, e.g. a 3-element
+ * calibration offset. Integer elements are promoted to `double`, as in
+ * `via_config_get_float()`.
+ *
+ * @code{.c}
+ * double offset[3];
+ * size_t written;
+ * via_config_get_float_into(config, "camera.calibration-offset", offset, 3, false, &written);
+ * @endcode
+ *
+ * @param config      A valid `ViaConfig` handle.
+ * @param key         A null-terminated string representing the key of a sequence.
+ * @param out_buf     A buffer with room for `capacity` values. May be `NULL` if `capacity` is 0.
+ * @param capacity    The number of values `out_buf` can hold.
+ * @param truncate    If `true`, a longer sequence is cut to `capacity` values
+ *                    instead of failing.
+ * @param out_written A pointer to a `size_t` that receives the number of values written.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_TypeError` if the value is not a sequence of numbers,
+ *         or if it is longer than `capacity` and `truncate` is `false`. In the
+ *         latter case `out_written` receives the sequence's length, so the
+ *         caller can retry with a larger buffer.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_get_float_into(const ViaConfig *config,
+                                          const char *key,
+                                          double *out_buf,
+                                          size_t capacity,
+                                          bool truncate,
+                                          size_t *out_written);
+
 /**
  * @brief Retrieves a boolean value from the configuration.
  *
+ * Only `true` and `false` are accepted, unless the handle was loaded with
+ * `VIA_CONFIG_FLAG_LENIENT_BOOLEANS`.
+ *
  * @param config A valid `ViaConfig` handle.
  * @param key A null-terminated string representing the key (e.g., "audio.noise_filter.enabled").
  * @param out_value A pointer to a `bool` where the result will be stored.
@@ -168,7 +765,9 @@ ViaConfigStatus via_config_get_boolean(const ViaConfig *config, const char *key,
 /**
  * @brief Converts a `ViaConfigStatus` enum to a human-readable string.
  *
- * This is useful for logging errors returned by the API.
+ * This is useful for logging errors returned by the API. The message is
+ * returned in the locale selected with `via_config_set_locale()` on the
+ * calling thread, or in English if none was set.
  *
  * @param status The status enum to convert.
  * @return A static, null-terminated string describing the status.
@@ -177,6 +776,1705 @@ ViaConfigStatus via_config_get_boolean(const ViaConfig *config, const char *key,
  */
 const char *via_config_status_to_string(ViaConfigStatus status);
 
+/**
+ * @brief Returns the version of the C ABI implemented by the linked library.
+ *
+ * A mismatch with `VIA_CONFIG_ABI_VERSION` means the header and the binary
+ * come from different releases, e.g.:
+ * `if (via_config_abi_version() != VIA_CONFIG_ABI_VERSION) { ... }`
+ *
+ * @return The library's ABI version.
+ */
+uint32_t via_config_abi_version(void);
+
+/**
+ * @brief Returns the library's semantic version (e.g., "0.1.0").
+ *
+ * @return A static, null-terminated string.
+ *
+ * @note The returned pointer is to a static string literal and must not be freed.
+ */
+const char *via_config_library_version(void);
+
+/**
+ * @brief Sets the language used by `via_config_status_to_string()` on the calling thread.
+ *
+ * Supported languages are English ("en", "en-US") and Brazilian Portuguese
+ * ("pt-BR", "pt_BR"). Unsupported languages fall back to English.
+ *
+ * @param locale A null-terminated locale tag.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_set_locale(const char *locale);
+
+/**
+ * @brief Merges one configuration handle into another.
+ *
+ * Mappings are merged recursively; any other value from `overlay` replaces the
+ * corresponding value in `base`. This is the same merge used between the
+ * system, hardware and profile layers of `via_config_load()`.
+ *
+ * @param base A valid `ViaConfig` handle, modified in place.
+ * @param overlay A valid `ViaConfig` handle whose values win. It is not modified.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_merge_into(ViaConfig *base, const ViaConfig *overlay);
+
+/**
+ * @brief Frees a string that was allocated by the library and returned to the caller.
+ *
+ * @param s A string previously returned as an owned `char*` (e.g. by
+ *          `via_config_export_schema()`). If `NULL` is passed, the function does nothing.
+ */
+void via_config_free_string(char *s);
+
+/**
+ * @brief Exports a JSON Schema describing the expected configuration structure.
+ *
+ * The schema is derived from the Rust configuration models and uses the same
+ * kebab-case field names as the YAML files. It can be fed to editors for
+ * autocompletion and validation.
+ *
+ * @param out_json A pointer to a `char*` where the schema will be stored.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return An error status code on failure. `out_json` will be untouched.
+ *
+ * @note The returned string MUST be freed using `via_config_free_string()`.
+ */
+ViaConfigStatus via_config_export_schema(char **out_json);
+
+/**
+ * @brief Updates a single key in a YAML file on disk, preserving comments and formatting.
+ *
+ * Only the value portion of the key's line is rewritten; indentation, trailing
+ * comments and every other byte of the file stay identical. The file is
+ * written under a temporary name and renamed into place, so a crash while
+ * saving never leaves it truncated. The same value is also applied to the
+ * loaded configuration handle.
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param path A null-terminated path to the YAML file to edit.
+ * @param key A null-terminated dot-separated key (e.g., "reasoning.llm.context-size").
+ * @param value The new value as raw YAML scalar text (e.g., "4096", "true", "\"text\"").
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_KeyNotFound` if the key is not present in the file.
+ * @return `ViaConfigStatus_TypeError` if the key does not hold a single-line scalar,
+ *         or if `value` is not a single-line YAML scalar.
+ * @return An error status code on failure. The file is untouched on failure.
+ */
+ViaConfigStatus via_config_save_file(ViaConfig *config, const char *path, const char *key, const char *value);
+
+/**
+ * @brief Reads the comment that documents a key, for a self-documenting settings UI.
+ *
+ * The description is taken from the YAML files the handle was loaded from:
+ * the comment lines directly above the key, followed by the trailing comment
+ * on its line, without the `#` markers and separated by newlines. Files are
+ * searched from the profile down to the system file, and the first comment
+ * found is returned.
+ *
+ * @code{.yaml}
+ * camera:
+ *   # Index of the capture device.
+ *   device-id: 0  # 0 is the built-in camera
+ * @endcode
+ *
+ * Here the description of "camera.device-id" is
+ * "Index of the capture device.\n0 is the built-in camera".
+ *
+ * @param config   A valid `ViaConfig` handle.
+ * @param key      A null-terminated dot-separated key (e.g., "camera.device-id").
+ * @param out_text A pointer to a `char*` that will receive the description,
+ *                 or `NULL` if the key has no comment or the handle was not
+ *                 loaded from files.
+ *
+ * @return `ViaConfigStatus_Ok` on success, whether or not a comment was found.
+ * @return `ViaConfigStatus_KeyNotFound` if the key does not exist.
+ * @return An error status code on failure.
+ *
+ * @note The files are re-read on each call. A non-null string MUST be freed
+ *       using `via_config_free_string()`.
+ */
+ViaConfigStatus via_config_get_description(const ViaConfig *config, const char *key, char **out_text);
+
+/**
+ * @brief Retrieves the text form of any scalar value.
+ *
+ * Numbers and booleans are returned in their canonical form (e.g., "4096",
+ * "true", "0.75"), strings verbatim and null as "null". Meant for consumers
+ * that only interpolate values into text and do not care how they were authored.
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param key A null-terminated string representing the key (e.g., "reasoning.llm.context-size").
+ * @param out_value A pointer to a `char*` where the result will be stored.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_TypeError` if the value is a mapping or a sequence.
+ * @return An error status code on failure. `out_value` will be untouched.
+ *
+ * @note The returned string MUST be freed using `via_config_free_string()`.
+ */
+ViaConfigStatus via_config_get_string_or_number_as_string(const ViaConfig *config, const char *key, char **out_value);
+
+/**
+ * @brief Retrieves a string value, letting an environment variable override the configuration.
+ *
+ * If the environment variable `env_name` is set, its value is returned.
+ * Otherwise the string value at `key` is returned.
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param key A null-terminated string representing the key (e.g., "reasoning.endpoint").
+ * @param env_name A null-terminated environment variable name (e.g., "TRACKIE_ENDPOINT").
+ * @param out_value A pointer to a `char*` where the result will be stored.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_KeyNotFound` if neither the variable nor the key is set.
+ * @return An error status code on failure. `out_value` will be untouched.
+ *
+ * @note The returned string MUST be freed using `via_config_free_string()`.
+ */
+ViaConfigStatus via_config_get_string_or_env(const ViaConfig *config,
+                                             const char *key,
+                                             const char *env_name,
+                                             char **out_value);
+
+/**
+ * @brief Flattens the configuration into environment variables for a child process.
+ *
+ * Every leaf becomes one variable named `PREFIX_SECTION__KEY`: sections are
+ * upper-cased and joined with `__`, and other non-alphanumeric characters
+ * become `_`. For example, with the prefix "TRACKIE", `camera.device-id: 0`
+ * is exported as `TRACKIE_CAMERA__DEVICE_ID=0`. Null values are exported as
+ * empty strings and sequences as compact JSON. Values of secret keys are
+ * exported as they are.
+ *
+ * @param config     A valid `ViaConfig` handle.
+ * @param prefix     A null-terminated prefix for every name, or "" for none.
+ * @param out_names  A pointer to a `char**` that will receive the variable names.
+ * @param out_values A pointer to a `char**` that will receive the values, parallel to `out_names`.
+ * @param out_len    A pointer to a `size_t` that will receive the number of variables.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return An error status code on failure.
+ *
+ * @note Both arrays MUST be freed using `via_config_free_string_array()`.
+ */
+ViaConfigStatus via_config_export_env(const ViaConfig *config,
+                                      const char *prefix,
+                                      char ***out_names,
+                                      char ***out_values,
+                                      size_t *out_len);
+
+/**
+ * @brief Registers a custom check for the value at `key`.
+ *
+ * Registered checks are run by `via_config_run_validators()`. Several checks
+ * may be registered for the same key.
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param key A null-terminated string representing the key (e.g., "camera.resolution.width").
+ * @param validator The callback to invoke with the string form of the value.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_register_validator(ViaConfig *config,
+                                              const char *key,
+                                              ViaConfigValidator validator);
+
+/**
+ * @brief Runs every registered validator against the current configuration.
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param out_failures A pointer to a `char*` that receives a newline-separated
+ *                     report of failed checks, or `NULL` if all checks passed.
+ *                     Values of secret keys are shown as `***`.
+ *
+ * @return `ViaConfigStatus_Ok` if every check passed.
+ * @return `ViaConfigStatus_ValidationFailed` if a check failed or its key is missing.
+ * @return An error status code on failure.
+ *
+ * @note A non-NULL report MUST be freed using `via_config_free_string()`.
+ */
+ViaConfigStatus via_config_run_validators(const ViaConfig *config, char **out_failures);
+
+/**
+ * @brief Registers a rule that one key must be set whenever another key has a given value.
+ *
+ * For example, `via_config_add_dependency_rule(config,
+ * "microphone.noise-filter.enabled", "true",
+ * "microphone.noise-filter.window-size")` catches a noise filter that
+ * is enabled but not configured. The value is compared in its plain-text
+ * form, so "true" matches the boolean and "16000" the integer. Rules are
+ * checked by `via_config_check_dependencies()`.
+ *
+ * @param config      A valid `ViaConfig` handle.
+ * @param if_key      A null-terminated key whose value triggers the rule.
+ * @param if_equals   A null-terminated value of `if_key` for which the rule applies.
+ * @param require_key A null-terminated key that must then be set.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_add_dependency_rule(ViaConfig *config,
+                                               const char *if_key,
+                                               const char *if_equals,
+                                               const char *require_key);
+
+/**
+ * @brief Registers a dependency rule whose required key must also be a number within bounds.
+ *
+ * Like `via_config_add_dependency_rule()`, but the required key must also hold
+ * a number in `[min, max]`, with either bound excluded when its `*_exclusive`
+ * flag is set. An infinite bound leaves that side open, so
+ * `via_config_add_dependency_range(config,
+ * "microphone.noise-filter.enabled", "true",
+ * "microphone.noise-filter.window-size", 0, true, INFINITY, false)` requires
+ * `window-size > 0` whenever the filter is enabled.
+ *
+ * @param config        A valid `ViaConfig` handle.
+ * @param if_key        A null-terminated key whose value triggers the rule.
+ * @param if_equals     A null-terminated value of `if_key` for which the rule applies.
+ * @param require_key   A null-terminated key that must then hold a number in range.
+ * @param min           The lower bound, or `-INFINITY` for none.
+ * @param min_exclusive Whether `min` itself is outside the range.
+ * @param max           The upper bound, or `INFINITY` for none.
+ * @param max_exclusive Whether `max` itself is outside the range.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_InternalError` if a bound is NaN or `min > max`.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_add_dependency_range(ViaConfig *config,
+                                                const char *if_key,
+                                                const char *if_equals,
+                                                const char *require_key,
+                                                double min,
+                                                bool min_exclusive,
+                                                double max,
+                                                bool max_exclusive);
+
+/**
+ * @brief Checks every rule registered with `via_config_add_dependency_rule()`
+ *        or `via_config_add_dependency_range()`.
+ *
+ * A rule applies when its `if_key` has the `if_equals` value. Its required
+ * key counts as set unless it is missing, null, or an empty string, sequence
+ * or mapping. A rule with a range also fails if the value is not a number
+ * within it, e.g. `a.window-size: required because a.enabled is true, but it
+ * is 0, which is not > 0`.
+ *
+ * @param config     A valid `ViaConfig` handle.
+ * @param out_report A pointer to a `char*` that receives one line per broken
+ *                   rule, e.g. `a.window-size: required because a.enabled is
+ *                   true, but it is not set`, or `NULL` if every rule holds.
+ *
+ * @return `ViaConfigStatus_Ok` if every rule holds.
+ * @return `ViaConfigStatus_ValidationFailed` if a rule is broken.
+ * @return An error status code on failure.
+ *
+ * @note A non-NULL report MUST be freed using `via_config_free_string()`.
+ */
+ViaConfigStatus via_config_check_dependencies(const ViaConfig *config, char **out_report);
+
+/**
+ * @brief Frees an array of strings returned by the library, including every string in it.
+ *
+ * @param array An array previously returned by the library (e.g. by `via_config_get_keys()`).
+ *              If `NULL` is passed, the function does nothing.
+ * @param len The length reported alongside the array.
+ */
+void via_config_free_string_array(char **array, size_t len);
+
+/**
+ * @brief Lists the keys of a mapping in the configuration.
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param key A null-terminated key of a mapping (e.g., "hardware.perception.model-paths"),
+ *            or an empty string for the root.
+ * @param sorted If `true`, keys are sorted lexicographically so that the order is
+ *               reproducible. Otherwise they are in document/merge order.
+ * @param out_keys A pointer to a `char**` where the array of keys will be stored.
+ * @param out_len A pointer to a `size_t` where the number of keys will be stored.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_TypeError` if the value at `key` is not a mapping.
+ * @return An error status code on failure. The output pointers will be untouched.
+ *
+ * @note The returned array MUST be freed using `via_config_free_string_array()`.
+ */
+ViaConfigStatus via_config_get_keys(const ViaConfig *config,
+                                    const char *key,
+                                    bool sorted,
+                                    char ***out_keys,
+                                    size_t *out_len);
+
+/**
+ * @brief Reports whether a key holds an explicit null value.
+ *
+ * A key written as `key:` with no value is null. This distinguishes it from a
+ * missing key (`ViaConfigStatus_KeyNotFound`) and from a value of another type.
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param key A null-terminated string representing the key (e.g., "microphone.noise-filter.enabled").
+ * @param out_is_null A pointer to a `bool` that receives `true` if the value is null.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_KeyNotFound` if the key does not exist.
+ * @return An error status code on failure. `out_is_null` will be untouched.
+ */
+ViaConfigStatus via_config_is_null(const ViaConfig *config, const char *key, bool *out_is_null);
+
+/**
+ * @brief Reports which source file supplied the value of a key.
+ *
+ * Useful for troubleshooting layered configuration, e.g. to find out which
+ * file set `log-level`.
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param key A null-terminated string representing a leaf key (e.g., "log-level").
+ * @param out_source_index A pointer to an `int32_t` that receives 0 for the system
+ *                         file, 1 for the hardware file, 2 for the profile, or -1
+ *                         if the value was changed after load.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_TypeError` if the key refers to a mapping rather than a leaf value.
+ * @return An error status code on failure. `out_source_index` will be untouched.
+ */
+ViaConfigStatus via_config_get_source(const ViaConfig *config, const char *key, int32_t *out_source_index);
+
+/**
+ * @brief Checks that a list of required keys exists in the configuration.
+ *
+ * Intended for startup, so that a missing mandatory setting aborts with a
+ * clear message listing everything that needs to be fixed.
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param keys An array of `count` null-terminated keys (e.g., "reasoning.llm.model-path").
+ * @param count The number of keys in `keys`.
+ * @param out_missing A pointer to a `char*` that receives a newline-separated report
+ *                    of missing keys, or `NULL` if all keys are present.
+ *
+ * @return `ViaConfigStatus_Ok` if every key is present.
+ * @return `ViaConfigStatus_ValidationFailed` if one or more keys are missing.
+ * @return An error status code on failure.
+ *
+ * @note A non-NULL report MUST be freed using `via_config_free_string()`.
+ */
+ViaConfigStatus via_config_require_keys(const ViaConfig *config,
+                                        const char *const *keys,
+                                        size_t count,
+                                        char **out_missing);
+
+/**
+ * @brief Fills a C struct directly from configuration keys, following a field layout.
+ *
+ * Each field is read with the typed getter matching its `field_type`, so range
+ * and type checks are the same as for the individual getters. All fields are
+ * read and bounds-checked against `struct_size` before anything is written.
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param layout An array of `count` field descriptions.
+ * @param count The number of fields in `layout`.
+ * @param out_struct A pointer to the struct to fill.
+ * @param struct_size The size of the struct in bytes (`sizeof`), used for bounds checks.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return The status of the first field that could not be read, or
+ *         `ViaConfigStatus_InternalError` if a field lies outside the struct.
+ *         On failure the struct is left untouched.
+ *
+ * @note String fields receive owned strings that MUST be freed using `via_config_free_string()`.
+ */
+ViaConfigStatus via_config_fill_struct(const ViaConfig *config,
+                                       const ViaConfigField *layout,
+                                       size_t count,
+                                       void *out_struct,
+                                       size_t struct_size);
+
+/**
+ * @brief Returns the status of the last error reported on the calling thread.
+ *
+ * Loaders return `NULL` on failure; this tells the caller why. The value is
+ * reset at the start of each load.
+ *
+ * @return The status of the last error, or `ViaConfigStatus_Ok` if there is none.
+ */
+ViaConfigStatus via_config_last_error_status(void);
+
+/**
+ * @brief Returns a description of the last error reported on the calling thread.
+ *
+ * @return A null-terminated message, or `NULL` if there is no error.
+ *
+ * @warning LIFETIME: The string is owned by the library and is valid only until
+ *          the next call on the same thread that reports or clears an error.
+ *          Copy it if you need to keep it.
+ */
+const char *via_config_last_error_message(void);
+
+/**
+ * @brief Freezes a configuration so that it can no longer be modified.
+ *
+ * Intended to be called once initialization is done. Every later call that
+ * would change the configuration (e.g. `via_config_merge_into()`,
+ * `via_config_save_file()`) fails with `ViaConfigStatus_Frozen`. Reading is
+ * unaffected. Freezing cannot be undone.
+ *
+ * @param config A valid `ViaConfig` handle.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_freeze(ViaConfig *config);
+
+/**
+ * @brief Retrieves a ratio normalized to the range [0, 1].
+ *
+ * The value may be written either as a number in [0, 1] (e.g. `0.75`) or as a
+ * percentage string (e.g. `"75%"`); both yield the same result.
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param key A null-terminated string representing the key (e.g., "perception.thresholds.detection").
+ * @param out_value A pointer to a `double` where the normalized ratio will be stored.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_TypeError` if the value is unparseable or out of range.
+ * @return An error status code on failure. `out_value` will be untouched.
+ */
+ViaConfigStatus via_config_get_ratio(const ViaConfig *config, const char *key, double *out_value);
+
+/**
+ * @brief Serializes a configuration into a compact binary buffer.
+ *
+ * The buffer can be passed to another process (e.g. over IPC) and restored with
+ * `via_config_from_bytes()`, avoiding re-reading and re-parsing the YAML files.
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param out_buf A pointer to a `uint8_t*` where the buffer will be stored.
+ * @param out_len A pointer to a `size_t` where the buffer length will be stored.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return An error status code on failure. The output pointers will be untouched.
+ *
+ * @note The returned buffer MUST be freed using `via_config_free_bytes()`.
+ */
+ViaConfigStatus via_config_to_bytes(const ViaConfig *config, uint8_t **out_buf, size_t *out_len);
+
+/**
+ * @brief Restores a configuration from a buffer produced by `via_config_to_bytes()`.
+ *
+ * Queries on the restored handle return exactly the same results as on the
+ * original. Registered validators are not carried over.
+ *
+ * @param buf A pointer to the buffer.
+ * @param len The length of the buffer in bytes.
+ *
+ * @return A pointer to a `ViaConfig` handle on success.
+ * @return `NULL` if the buffer is not a valid encoding. Use
+ *         `via_config_last_error_message()` for details.
+ *
+ * @note The returned pointer MUST be freed using `via_config_free()` to avoid memory leaks.
+ */
+ViaConfig *via_config_from_bytes(const uint8_t *buf, size_t len);
+
+/**
+ * @brief Frees a buffer returned by `via_config_to_bytes()`.
+ *
+ * @param buf The buffer. If `NULL` is passed, the function does nothing.
+ * @param len The length reported alongside the buffer.
+ */
+void via_config_free_bytes(uint8_t *buf, size_t len);
+
+/**
+ * @brief Retrieves every leaf key under a prefix, with its value serialized as YAML.
+ *
+ * `"hardware"` or `"hardware.*"` matches every key nested under `hardware`.
+ * A trailing `*` after other text (e.g. `"camera.res*"`) matches by plain string
+ * prefix. An empty prefix matches every key.
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param prefix A null-terminated prefix pattern.
+ * @param out_keys A pointer to a `char**` that receives the matching dot-keys.
+ * @param out_values_yaml A pointer to a `char**` that receives the corresponding values as YAML.
+ * @param out_len A pointer to a `size_t` that receives the length of both arrays.
+ *
+ * @return `ViaConfigStatus_Ok` on success, even if nothing matched.
+ * @return An error status code on failure. The output pointers will be untouched.
+ *
+ * @note Both arrays MUST be freed using `via_config_free_string_array()`.
+ */
+ViaConfigStatus via_config_query_prefix(const ViaConfig *config,
+                                        const char *prefix,
+                                        char ***out_keys,
+                                        char ***out_values_yaml,
+                                        size_t *out_len);
+
+/**
+ * @brief Checks the configured thread counts against the available CPUs.
+ *
+ * Sums `threads.perception`, `threads.reasoning` and `threads.audio`, as set
+ * by the system file, and compares the total with the number of logical CPUs
+ * available to the process.
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param out_warning A pointer to a `char*` that receives a warning naming each
+ *                    count and the CPU total, or `NULL` if within budget.
+ *
+ * @return `ViaConfigStatus_Ok` if the total fits in the available CPUs.
+ * @return `ViaConfigStatus_ValidationFailed` if the budget is exceeded.
+ * @return An error status code on failure (e.g. a thread count is missing or not
+ *         a non-negative integer).
+ *
+ * @note A non-NULL warning MUST be freed using `via_config_free_string()`.
+ */
+ViaConfigStatus via_config_check_thread_budget(const ViaConfig *config, char **out_warning);
+
+/**
+ * @brief Checks that a width and a height form one of the accepted aspect ratios.
+ *
+ * The ratio of the two values must match one of `ratios` within a relative
+ * tolerance of 1%, which absorbs rounding in resolutions such as 854x480.
+ *
+ * @param config     A valid `ViaConfig` handle.
+ * @param width_key  The key of the width (e.g., "hardware.camera.resolution.width").
+ * @param height_key The key of the height (e.g., "hardware.camera.resolution.height").
+ * @param ratios     An array of `count` accepted ratios, written as "W:H"
+ *                   (e.g., "16:9") or as a decimal number (e.g., "1.85").
+ * @param count      The number of entries in `ratios`.
+ * @param out_ok     A pointer to a `bool` that receives `true` if the ratio is accepted.
+ *
+ * @return `ViaConfigStatus_Ok` on success, whether or not the ratio is accepted.
+ * @return `ViaConfigStatus_TypeError` if either dimension is not a positive number.
+ * @return `ViaConfigStatus_ParseError` if one of `ratios` is malformed.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_check_aspect_ratio(const ViaConfig *config,
+                                              const char *width_key,
+                                              const char *height_key,
+                                              const char *const *ratios,
+                                              size_t count,
+                                              bool *out_ok);
+
+/**
+ * @brief Sets a string value in the configuration.
+ *
+ * Intermediate mappings are created as needed. Inside an update opened with
+ * `via_config_begin_update()`, the change is staged until
+ * `via_config_commit_update()`.
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param key A null-terminated string representing the key (e.g., "profile.user-name").
+ * @param value The null-terminated value to store.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_Frozen` if the configuration has been frozen.
+ * @return An error status code on failure.
+ *
+ * @warning Strings previously returned by `via_config_get_string()` for this key
+ *          become invalid once the change is applied.
+ */
+ViaConfigStatus via_config_set_string(ViaConfig *config, const char *key, const char *value);
+
+/**
+ * @brief Sets an integer value in the configuration. See `via_config_set_string()`.
+ */
+ViaConfigStatus via_config_set_integer(ViaConfig *config, const char *key, int64_t value);
+
+/**
+ * @brief Sets a floating-point value in the configuration. See `via_config_set_string()`.
+ */
+ViaConfigStatus via_config_set_float(ViaConfig *config, const char *key, double value);
+
+/**
+ * @brief Sets a boolean value in the configuration. See `via_config_set_string()`.
+ */
+ViaConfigStatus via_config_set_boolean(ViaConfig *config, const char *key, bool value);
+
+/**
+ * @brief Removes a key, and any subtree below it, from the configuration.
+ *
+ * Modules reading the key then fall back to their built-in defaults. Parent
+ * mappings left empty by the removal are removed as well. Inside an update
+ * opened with `via_config_begin_update()`, the removal is staged until
+ * `via_config_commit_update()`.
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param key A null-terminated string representing the key (e.g., "profile.alert-preferences").
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_KeyNotFound` if the key does not exist.
+ * @return `ViaConfigStatus_Frozen` if the configuration has been frozen.
+ * @return An error status code on failure.
+ *
+ * @warning Strings previously returned by `via_config_get_string()` for this key
+ *          become invalid once the change is applied.
+ */
+ViaConfigStatus via_config_remove_key(ViaConfig *config, const char *key);
+
+/**
+ * @brief Opens a multi-key update.
+ *
+ * Until `via_config_commit_update()` or `via_config_abort_update()` is called,
+ * the `via_config_set_*()` functions and `via_config_remove_key()` only stage
+ * their changes and readers keep seeing the previous values. The commit applies
+ * every staged change in one step. `via_config_remove_key()` checks the key
+ * against the configuration with the earlier staged changes applied, so a key
+ * set earlier in the same update can be removed again.
+ *
+ * @param config A valid `ViaConfig` handle.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_InternalError` if an update is already open.
+ * @return An error status code on failure.
+ *
+ * @warning A handle is single-threaded and has no internal lock, so an update
+ *          is not atomic with respect to other threads. Callers sharing a
+ *          handle between threads must serialize every call, including the
+ *          commit, themselves.
+ */
+ViaConfigStatus via_config_begin_update(ViaConfig *config);
+
+/**
+ * @brief Applies every change staged since `via_config_begin_update()` and closes the update.
+ *
+ * @param config A valid `ViaConfig` handle.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_InternalError` if no update is open.
+ * @return `ViaConfigStatus_Frozen` if the configuration was frozen after the
+ *         update began. Nothing is applied and the update stays open, so
+ *         that `via_config_abort_update()` can discard it.
+ */
+ViaConfigStatus via_config_commit_update(ViaConfig *config);
+
+/**
+ * @brief Discards every change staged since `via_config_begin_update()` and closes the update.
+ *
+ * @param config A valid `ViaConfig` handle.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_InternalError` if no update is open.
+ */
+ViaConfigStatus via_config_abort_update(ViaConfig *config);
+
+/**
+ * @brief Retrieves a file path, resolved against a base directory.
+ *
+ * Relative values (e.g., `model_path: models/llm.gguf`) are joined with
+ * `base_dir`, typically the directory of the configuration file. Absolute
+ * values are used as-is. The result is canonicalized, so the path must exist.
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param key A null-terminated string representing the key.
+ * @param base_dir The null-terminated directory that relative paths are resolved against.
+ * @param out_value A pointer to a `char*` that will receive the absolute path.
+ *                  The string must be released with `via_config_free_string()`.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_TypeError` if the value is not a non-empty string.
+ * @return `ViaConfigStatus_FileNotFound` if the resolved path does not exist.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_get_path(const ViaConfig *config, const char *key, const char *base_dir, char **out_value);
+
+/**
+ * @brief Returns every perception model with the absolute path of its file.
+ *
+ * Reads the `perception.model-paths` table (model name to file path) and
+ * resolves each relative path against `base_dir`, combining the table
+ * accessor, `via_config_get_path()` and an existence check in one call.
+ *
+ * @param config       A valid `ViaConfig` handle.
+ * @param base_dir     A null-terminated directory that relative paths are resolved against.
+ * @param check_exists If `true`, each path is canonicalized and must exist,
+ *                     exactly as in `via_config_get_path()`. If `false`, paths
+ *                     are only made absolute.
+ * @param out_names    A pointer to a `char**` that will receive the model names, in document order.
+ * @param out_paths    A pointer to a `char**` that will receive the paths, parallel to `out_names`.
+ * @param out_len      A pointer to a `size_t` that will receive the number of models.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_KeyNotFound` if `perception.model-paths` is not set.
+ * @return `ViaConfigStatus_TypeError` if it is not a mapping of non-empty strings.
+ * @return `ViaConfigStatus_FileNotFound` if `check_exists` is set and a file does
+ *         not exist; `via_config_last_error_message()` names the model.
+ * @return An error status code on failure.
+ *
+ * @note Both arrays MUST be freed using `via_config_free_string_array()`.
+ */
+ViaConfigStatus via_config_resolve_model_paths(const ViaConfig *config,
+                                               const char *base_dir,
+                                               bool check_exists,
+                                               char ***out_names,
+                                               char ***out_paths,
+                                               size_t *out_len);
+
+/**
+ * @brief Serializes the configuration, or a subtree of it, as YAML.
+ *
+ * Dump flags only affect the output. For example, with
+ * `VIA_CONFIG_DUMP_FLAG_ROUND_FLOATS` a value stored as `0.7000000001` is
+ * dumped as `0.7`, while `via_config_get_float()` still returns the original.
+ * Values of keys registered with `via_config_add_secret_key()` are always
+ * replaced with `***`.
+ *
+ * @param config          A valid `ViaConfig` handle.
+ * @param key             A null-terminated key of the subtree to dump, or "" for the whole configuration.
+ * @param dump_flags      A combination of `VIA_CONFIG_DUMP_FLAG_*` values, or 0.
+ * @param float_precision The number of decimal places kept by `VIA_CONFIG_DUMP_FLAG_ROUND_FLOATS`.
+ * @param out_yaml        A pointer to a `char*` that will receive the YAML text.
+ *                        The string must be released with `via_config_free_string()`.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_dump_yaml(const ViaConfig *config,
+                                     const char *key,
+                                     uint32_t dump_flags,
+                                     uint32_t float_precision,
+                                     char **out_yaml);
+
+/**
+ * @brief Serializes the configuration, or a subtree of it, as compact JSON.
+ *
+ * Values of keys registered with `via_config_add_secret_key()` are replaced
+ * with `***`, as in `via_config_dump_yaml()`.
+ *
+ * @param config   A valid `ViaConfig` handle.
+ * @param key      A null-terminated key of the subtree, or "" for the whole configuration.
+ * @param out_json A pointer to a `char*` that will receive the JSON text.
+ *                 The string must be released with `via_config_free_string()`.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_KeyNotFound` if the key does not exist.
+ * @return `ViaConfigStatus_TypeError` if the subtree has no JSON equivalent
+ *         (e.g., a mapping key that is itself a collection).
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_get_subtree_json(const ViaConfig *config, const char *key, char **out_json);
+
+/**
+ * @brief Retrieves the type and the YAML-serialized value of any key in one call.
+ *
+ * Meant for language bindings, which can parse the YAML on their side instead
+ * of probing the key with each typed getter. Unlike `via_config_dump_yaml()`,
+ * values of secret keys are returned as they are.
+ *
+ * @param config         A valid `ViaConfig` handle.
+ * @param key            A null-terminated key, e.g. "camera.resolution".
+ * @param out_type       A pointer to a `ViaConfigValueType` that will receive the value's type.
+ * @param out_value_yaml A pointer to a `char*` that will receive the value as YAML.
+ *                       The string must be released with `via_config_free_string()`.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_KeyNotFound` if the key does not exist.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_get_any(const ViaConfig *config,
+                                   const char *key,
+                                   ViaConfigValueType *out_type,
+                                   char **out_value_yaml);
+
+/**
+ * @brief Retrieves the YAML-serialized values of many keys in one call.
+ *
+ * Lets a module read all of its settings with a single call instead of one
+ * getter call per key. Each value is serialized as by `via_config_get_any()`;
+ * the lookup of each key succeeds or fails on its own.
+ *
+ * @param config          A valid `ViaConfig` handle.
+ * @param keys            An array of `count` null-terminated keys.
+ * @param count           The number of keys.
+ * @param out_values_yaml A pointer to a `char**` that will receive an array of
+ *                        `count` values as YAML. The entry of a key that could
+ *                        not be read is `NULL`.
+ * @param out_statuses    A caller-allocated array of `count` statuses, which
+ *                        receives the status of each lookup (e.g.
+ *                        `ViaConfigStatus_KeyNotFound`).
+ *
+ * @return `ViaConfigStatus_Ok` if the lookups were made, even if some failed.
+ * @return An error status code if an argument is invalid. The outputs are
+ *         then untouched.
+ *
+ * @note The value array MUST be freed using `via_config_free_string_array()`
+ *       with `count`, which also frees every value in it.
+ */
+ViaConfigStatus via_config_get_batch(const ViaConfig *config,
+                                     const char *const *keys,
+                                     size_t count,
+                                     char ***out_values_yaml,
+                                     ViaConfigStatus *out_statuses);
+
+/**
+ * @brief Reports whether the configuration was restored from the cache.
+ *
+ * @param config     A valid `ViaConfig` handle.
+ * @param out_cached A pointer to a `bool` that receives `true` if
+ *                   `via_config_load_cached()` used the cache, `false` if the
+ *                   YAML files were parsed.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_was_cached(const ViaConfig *config, bool *out_cached);
+
+/**
+ * @brief Reports every environment variable referenced but not set.
+ *
+ * String values may reference environment variables as `${NAME}`, or as
+ * `${NAME:-fallback}` (never missing); `$$` is a literal `$`. This function
+ * scans the whole configuration without substituting anything, so that all
+ * missing variables can be fixed in one go.
+ *
+ * @param config      A valid `ViaConfig` handle.
+ * @param out_missing A pointer to a `char*` that receives a newline-separated
+ *                    report, one line per missing variable with the keys that
+ *                    reference it, or `NULL` if none are missing. A non-null
+ *                    report must be released with `via_config_free_string()`.
+ *
+ * @return `ViaConfigStatus_Ok` if every referenced variable is set.
+ * @return `ViaConfigStatus_ValidationFailed` if any variable is missing.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_check_interpolation(const ViaConfig *config, char **out_missing);
+
+/**
+ * @brief Lists every leaf of the configuration with its type.
+ *
+ * The whole merged tree is flattened into dot-separated keys (e.g.,
+ * "camera.resolution.width"), returned in document/merge order.
+ *
+ * @param config    A valid `ViaConfig` handle.
+ * @param out_keys  A pointer to a `char**` that will receive the array of keys.
+ *                  The array must be released with `via_config_free_string_array()`.
+ * @param out_types A pointer to a `ViaConfigValueType*` that will receive the
+ *                  parallel array of types. The array must be released with
+ *                  `via_config_free_type_array()`.
+ * @param out_len   A pointer to a `size_t` that will receive the number of leaves.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_list_all(const ViaConfig *config,
+                                    char ***out_keys,
+                                    ViaConfigValueType **out_types,
+                                    size_t *out_len);
+
+/**
+ * @brief Frees an array of types returned by `via_config_list_all()`.
+ *
+ * @param array The array to free. Passing `NULL` is safe.
+ * @param len   The length returned alongside the array.
+ */
+void via_config_free_type_array(ViaConfigValueType *array, size_t len);
+
+/**
+ * @brief Calls a visitor for every node of the configuration.
+ *
+ * The tree is walked depth-first in document order, and every mapping entry
+ * is passed with its full dot-separated key, a mapping before its children.
+ * Sequences and scalars are passed as YAML and not descended into, as in
+ * `via_config_list_all()`. This is the general-purpose traversal for
+ * exports, checks or transformations that have no dedicated accessor.
+ *
+ * @param config    A valid `ViaConfig` handle.
+ * @param visitor   The callback to invoke for each node.
+ * @param user_data A pointer passed to every call of `visitor` unchanged.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_InternalError` if a key or value contains a null
+ *         byte; later nodes are not visited.
+ * @return An error status code on failure.
+ *
+ * @note Values of keys registered with `via_config_add_secret_key()` are
+ *       passed as they are. The visitor must not free or modify the handle.
+ */
+ViaConfigStatus via_config_visit(const ViaConfig *config, ViaConfigVisitor visitor, void *user_data);
+
+/**
+ * @brief Counts the leaf keys of the configuration.
+ *
+ * The count equals the number of keys `via_config_list_all()` returns, but no
+ * list is built, so it is cheap enough for telemetry.
+ *
+ * @param config    A valid `ViaConfig` handle.
+ * @param out_count A pointer to a `size_t` that will receive the number of leaf keys.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_count_keys(const ViaConfig *config, size_t *out_count);
+
+/**
+ * @brief Registers a key whose value is redacted in dumps.
+ *
+ * The value, including any subtree below the key, is replaced with `***` in
+ * the output of `via_config_dump_yaml()`. The key may be a glob pattern where
+ * `*` matches within one key segment and `**` across segments
+ * (e.g., "profile.known_faces_db_path" or "**.api_key").
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param key A null-terminated key or glob pattern.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_add_secret_key(ViaConfig *config, const char *key);
+
+/**
+ * @brief Returns the read+parse duration of each source file.
+ *
+ * Durations are only recorded when the handle was loaded with
+ * `VIA_CONFIG_FLAG_RECORD_TIMINGS`; otherwise, or if the configuration was
+ * restored from a cache, the arrays are empty.
+ *
+ * @param config     A valid `ViaConfig` handle.
+ * @param out_files  A pointer to a `char**` that will receive the file paths, in load order.
+ *                   The array must be released with `via_config_free_string_array()`.
+ * @param out_micros A pointer to a `uint64_t*` that will receive the parallel
+ *                   array of durations in microseconds. The array must be
+ *                   released with `via_config_free_u64_array()`.
+ * @param out_len    A pointer to a `size_t` that will receive the number of files.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_get_load_timings(const ViaConfig *config,
+                                            char ***out_files,
+                                            uint64_t **out_micros,
+                                            size_t *out_len);
+
+/**
+ * @brief Frees an array of integers returned by `via_config_get_load_timings()`.
+ *
+ * @param array The array to free. Passing `NULL` is safe.
+ * @param len   The length returned alongside the array.
+ */
+void via_config_free_u64_array(uint64_t *array, size_t len);
+
+/**
+ * @brief Returns every value a later source overrode while merging.
+ *
+ * Overrides are only recorded when the handle was loaded with
+ * `VIA_CONFIG_FLAG_RECORD_OVERRIDES`. Unlike `via_config_get_source()`, which
+ * tells which source won, the log lists every replacement with its before and
+ * after values, one per line in merge order, e.g.
+ * `camera.fps: 30 -> 15 (source 2)`. Source numbers are as reported by
+ * `via_config_get_source()`. Values of keys registered with
+ * `via_config_add_secret_key()` are shown as `***`.
+ *
+ * @param config     A valid `ViaConfig` handle.
+ * @param out_report A pointer to a `char*` that will receive the log, or
+ *                   `NULL` if no value was overridden or the flag was not set.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return An error status code on failure.
+ *
+ * @note A non-null log MUST be freed using `via_config_free_string()`.
+ */
+ViaConfigStatus via_config_get_override_log(const ViaConfig *config, char **out_report);
+
+/**
+ * @brief Reports how often each key was read.
+ *
+ * Lookups are only counted when the handle was loaded with
+ * `VIA_CONFIG_FLAG_RECORD_METRICS`. Each lookup by a typed getter such as
+ * `via_config_get_integer()` counts as a read of its key, whatever the
+ * status; a lookup of a key that does not exist also counts as not found.
+ * The report has one line per key, most read first, e.g.
+ * `camera.fps: 120 reads, 0 not found`. Every leaf of the configuration that
+ * was never read is listed with `0 reads`, to find settings nothing uses, and
+ * the most read keys are candidates for caching on the caller's side.
+ *
+ * @param config     A valid `ViaConfig` handle.
+ * @param out_report A pointer to a `char*` that will receive the report, or
+ *                   `NULL` if the flag was not set.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return An error status code on failure.
+ *
+ * @note A non-null report MUST be freed using `via_config_free_string()`.
+ * @note A leaf read only through a getter of an enclosing section is not
+ *       counted as read.
+ */
+ViaConfigStatus via_config_dump_metrics(const ViaConfig *config, char **out_report);
+
+/**
+ * @brief Explains step by step how a key got its final value.
+ *
+ * Combines provenance, the sources' own values and the changes made after
+ * load into one line, e.g.
+ * `source 0 (system.yaml)=info, overridden by source 2 (profile.yaml)=debug, overridden by command-line override=trace`.
+ * Every source that sets the key is listed, whether or not the handle was
+ * loaded with `VIA_CONFIG_FLAG_RECORD_OVERRIDES`. A change made after load is
+ * named as a `command-line override` (`via_config_apply_overrides()`), a
+ * `JSON patch` (`via_config_apply_json_patch()`), a `merged handle`
+ * (`via_config_merge_into()`) or a `runtime change`. If the
+ * final value differs from the last step, e.g. after template resolution, it
+ * is appended as `resolved to ...`. Values of keys registered with
+ * `via_config_add_secret_key()` are shown as `***`.
+ *
+ * @param config          A valid `ViaConfig` handle.
+ * @param key             A null-terminated dot-separated key of a leaf value.
+ * @param out_explanation A pointer to a `char*` that will receive the explanation.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_KeyNotFound` if the key does not exist.
+ * @return `ViaConfigStatus_TypeError` if the key is a mapping.
+ *
+ * @note The explanation MUST be freed using `via_config_free_string()`.
+ */
+ViaConfigStatus via_config_explain_key(const ViaConfig *config, const char *key, char **out_explanation);
+
+/**
+ * @brief Retrieves a mapping of numbers as parallel key and value arrays.
+ *
+ * Intended for tables such as `hardware.perception.thresholds` that a UI
+ * presents as sliders. Integer and float values are both promoted to `double`.
+ * Entries are returned in document/merge order.
+ *
+ * @param config     A valid `ViaConfig` handle.
+ * @param key        The dot-separated key of the mapping.
+ * @param out_keys   A pointer to a `char**` that will receive the entry keys.
+ *                   The array must be released with `via_config_free_string_array()`.
+ * @param out_values A pointer to a `double*` that will receive the parallel
+ *                   array of values. The array must be released with
+ *                   `via_config_free_f64_array()`.
+ * @param out_len    A pointer to a `size_t` that will receive the number of entries.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_TypeError` if the value is not a mapping of string
+ *         keys to numbers.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_get_numeric_map(const ViaConfig *config,
+                                           const char *key,
+                                           char ***out_keys,
+                                           double **out_values,
+                                           size_t *out_len);
+
+/**
+ * @brief Frees an array of numbers returned by `via_config_get_numeric_map()`.
+ *
+ * @param array The array to free. Passing `NULL` is safe.
+ * @param len   The length returned alongside the array.
+ */
+void via_config_free_f64_array(double *array, size_t len);
+
+/**
+ * @brief Applies command-line style `key=value` overrides.
+ *
+ * Each value is read as a YAML scalar, so `reasoning.llm.context-size=4096`
+ * sets an integer and `audio.enabled=false` a boolean; other values are kept
+ * as strings. Paths are created as needed. Inside an update opened with
+ * `via_config_begin_update()`, the changes are staged.
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param args   An array of `count` null-terminated `key=value` strings.
+ * @param count  The number of entries in `args`.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_ParseError` if an entry is malformed. No override is
+ *         applied, and `via_config_last_error_message()` names the entry.
+ * @return `ViaConfigStatus_InternalError` if a key is nested deeper than the
+ *         depth limit. No override is applied.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_apply_overrides(ViaConfig *config, const char *const *args, size_t count);
+
+/**
+ * @brief Applies a JSON Patch (RFC 6902) to the merged configuration.
+ *
+ * The `add`, `remove`, `replace`, `move` and `test` operations are
+ * supported, e.g. `[{"op": "test", "path": "/camera/fps", "value": 30},
+ *   {"op": "replace", "path": "/camera/fps", "value": 15},
+ *   {"op": "move", "from": "/camera/fps", "path": "/camera/max-fps"}]`.
+ * Paths are JSON Pointers: `/` separates keys and sequence indices, and `~1`
+ * and `~0` stand for a `/` and a `~` within a key. `-` appends to a sequence.
+ * The operations are applied in order, and the configuration is only changed
+ * if all of them succeed. Inside an update opened with
+ * `via_config_begin_update()`, the patch is applied to the committed values
+ * and the resulting changes are staged.
+ *
+ * @param config     A valid `ViaConfig` handle.
+ * @param patch_json A null-terminated JSON Patch document.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_ParseError` if the patch is malformed or uses
+ *         another operation.
+ * @return `ViaConfigStatus_KeyNotFound` if an operation's path, or the `from`
+ *         path of a `move`, does not exist.
+ * @return `ViaConfigStatus_ValidationFailed` if a `test` operation fails.
+ *         `via_config_last_error_message()` names the operation and the
+ *         actual value, unless the key is secret.
+ * @return `ViaConfigStatus_Frozen` if the handle is frozen.
+ */
+ViaConfigStatus via_config_apply_json_patch(ViaConfig *config, const char *patch_json);
+
+/**
+ * @brief Routes the library's log messages to a callback instead of stderr.
+ *
+ * The callback is process-wide and may be called from any thread that uses
+ * the library, so it must be thread-safe.
+ *
+ * @param callback The callback to install, or `NULL` to restore logging to stderr.
+ */
+void via_config_set_log_callback(ViaConfigLogCallback callback);
+
+/**
+ * @brief Retrieves a string value restricted to a set of allowed variants.
+ *
+ * For example, with `allowed = { "trace", "debug", "info", "warn", "error" }`,
+ * a `system.log-level` of "info" yields index 2. Matching is exact.
+ *
+ * @param config    A valid `ViaConfig` handle.
+ * @param key       A null-terminated string representing the key (e.g., "system.log-level").
+ * @param allowed   An array of `count` null-terminated variant names.
+ * @param count     The number of entries in `allowed`.
+ * @param out_index A pointer to an `int32_t` that receives the index of the matching variant.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_TypeError` if the value is not a string or not one of the variants.
+ * @return An error status code on failure. `out_index` will be untouched.
+ */
+ViaConfigStatus via_config_get_enum(const ViaConfig *config,
+                                    const char *key,
+                                    const char *const *allowed,
+                                    size_t count,
+                                    int32_t *out_index);
+
+/**
+ * @brief Retrieves a string value restricted to a set of variants, mapped to
+ * an integer such as a C enum constant.
+ *
+ * Matches the value like `via_config_get_enum()` and returns the entry of
+ * `values` at the position of the matching name. For example, with
+ * `names = { "error", "warn", "info", "debug" }` and
+ * `values = { LOG_ERROR, LOG_WARN, LOG_INFO, LOG_DEBUG }`, a
+ * `system.log-level` of "debug" yields `LOG_DEBUG`.
+ *
+ * @param config    A valid `ViaConfig` handle.
+ * @param key       A null-terminated string representing the key (e.g., "system.log-level").
+ * @param names     An array of `count` null-terminated variant names.
+ * @param values    An array of `count` values, one for each name.
+ * @param count     The number of entries in `names` and `values`.
+ * @param out_value A pointer to an `int32_t` that receives the value of the matching variant.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_TypeError` if the value is not a string or not one of the variants.
+ * @return An error status code on failure. `out_value` will be untouched.
+ */
+ViaConfigStatus via_config_get_enum_mapped(const ViaConfig *config,
+                                           const char *key,
+                                           const char *const *names,
+                                           const int32_t *values,
+                                           size_t count,
+                                           int32_t *out_value);
+
+/**
+ * @brief Validates the configuration files without keeping a handle.
+ *
+ * This is the entry point for `trackie config check` and CI. Each file is read,
+ * parsed and checked against the expected structure of its kind, and every
+ * problem is reported, not just the first. An empty profile is valid. When
+ * this returns `ViaConfigStatus_Ok`, `via_config_load()` succeeds with the same paths.
+ *
+ * @param system_path   A UTF-8 encoded, null-terminated path to the system config.
+ * @param hardware_path A UTF-8 encoded, null-terminated path to the hardware config.
+ * @param profile_path  A UTF-8 encoded, null-terminated path to the user profile.
+ * @param out_report    A pointer to a `char*` that receives a newline-separated
+ *                      report, one line per problem, or `NULL` if there are none.
+ *                      A non-null report must be released with `via_config_free_string()`.
+ *
+ * @return `ViaConfigStatus_Ok` if all files are valid.
+ * @return `ViaConfigStatus_ValidationFailed` if any problem was found.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_validate_files(const char *system_path,
+                                          const char *hardware_path,
+                                          const char *profile_path,
+                                          char **out_report);
+
+/**
+ * @brief Reads a single string value without keeping a handle.
+ *
+ * Loads and merges the files exactly as `via_config_load()`, reads one key and
+ * frees everything again. Convenient for scripts and tooling that need one
+ * value; code reading several keys should keep a handle instead.
+ *
+ * @param system_path   A UTF-8 encoded, null-terminated path to the system config.
+ * @param hardware_path A UTF-8 encoded, null-terminated path to the hardware config.
+ * @param profile_path  A UTF-8 encoded, null-terminated path to the user profile.
+ * @param key           A null-terminated string representing the key.
+ * @param out_value     A pointer to a `char*` that will receive the value.
+ *                      The string must be released with `via_config_free_string()`.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return The load error (e.g., `ViaConfigStatus_FileNotFound`) if the files could not be loaded.
+ * @return `ViaConfigStatus_TypeError` if the value is not a string.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_read_string(const char *system_path,
+                                       const char *hardware_path,
+                                       const char *profile_path,
+                                       const char *key,
+                                       char **out_value);
+
+/**
+ * @brief Sets the maximum nesting depth of configuration trees.
+ *
+ * Configuration files, binary buffers and keys that would nest deeper than
+ * this are rejected with `ViaConfigStatus_InternalError` and a descriptive
+ * message, instead of risking a stack overflow on pathological input. The
+ * setting is process-wide.
+ *
+ * @param depth The maximum depth, or 0 to restore the default of 128 (the
+ *              recursion limit of the YAML parser).
+ */
+void via_config_set_max_depth(size_t depth);
+
+/**
+ * @brief Sets limits on the size of configuration sources.
+ *
+ * For configuration from semi-trusted sources, e.g. documents passed to
+ * `via_config_load_from_strings()`. A document larger than
+ * `max_document_size` bytes is rejected before it is parsed, which bounds
+ * the memory the parser can use. A parsed tree with more than `max_keys`
+ * mapping entries in total, or a string or key longer than
+ * `max_string_length` bytes, is rejected as well. Rejected sources fail with
+ * `ViaConfigStatus_InternalError` and a message naming the limit. The limits
+ * apply to files, in-memory documents and JSON patches alike, and are
+ * process-wide.
+ *
+ * @param max_document_size The maximum size of a document in bytes, or 0 for no limit.
+ * @param max_keys          The maximum number of keys in a tree, or 0 for no limit.
+ * @param max_string_length The maximum length of a string or key in bytes, or 0 for no limit.
+ *
+ * @note There are no size limits by default. The depth limit of
+ *       `via_config_set_max_depth()` always applies.
+ */
+void via_config_set_size_limits(size_t max_document_size, size_t max_keys, size_t max_string_length);
+
+/**
+ * @brief Restores a key to the value the system file specified.
+ *
+ * Any hardware, profile or runtime override of the key (and everything below
+ * it) is discarded. If the system file does not define the key, it is removed.
+ * Inside an update opened with `via_config_begin_update()`, the reset is
+ * staged until `via_config_commit_update()`.
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param key A null-terminated string representing the key.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_KeyNotFound` if neither the system file nor the configuration has the key.
+ * @return `ViaConfigStatus_Frozen` if the configuration has been frozen.
+ * @return `ViaConfigStatus_InternalError` if the handle was restored with
+ *         `via_config_from_bytes()` or from a cache, which do not retain the source files.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_reset_key(ViaConfig *config, const char *key);
+
+/**
+ * @brief Compares the contents of two configurations.
+ *
+ * The merged trees are compared by value. Mapping key order is ignored, while
+ * sequences must match element by element. Where values came from is not
+ * compared.
+ *
+ * @param a         A valid `ViaConfig` handle.
+ * @param b         A valid `ViaConfig` handle.
+ * @param out_equal A pointer to a `bool` that receives `true` if the contents are equal.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_equals(const ViaConfig *a, const ViaConfig *b, bool *out_equal);
+
+/**
+ * @brief Retrieves a duration in milliseconds.
+ *
+ * The value may be a string with unit suffixes `ms`, `s`, `m`, `h` and `d`
+ * (e.g., "500ms", "30s", "1h30m"), or a non-negative integer read as
+ * milliseconds. Durations longer than `UINT64_MAX` nanoseconds (about 584
+ * years, e.g. "9999999999h") are rejected rather than wrapped, so any result
+ * can be converted to nanoseconds safely.
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param key A null-terminated string representing the key (e.g., "reasoning.llm.timeout").
+ * @param out_ms A pointer to a `uint64_t` where the result will be stored.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_TypeError` if the value is malformed or overflows.
+ *         `via_config_last_error_message()` describes the problem.
+ * @return An error status code on failure. `out_ms` will be untouched.
+ */
+ViaConfigStatus via_config_get_duration_ms(const ViaConfig *config, const char *key, uint64_t *out_ms);
+
+/**
+ * @brief Creates an independent handle for one section of the configuration.
+ *
+ * The new handle's root is a copy of the mapping at `key`, so a module can be
+ * given just its own section and query it with relative keys. For example,
+ * with `key = "hardware"`, the module reads "camera.device-id". Later changes
+ * to either handle do not affect the other.
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param key A null-terminated string representing the key of the section.
+ *
+ * @return A pointer to a new `ViaConfig` handle on success.
+ * @return `NULL` if the key is missing (`ViaConfigStatus_KeyNotFound`) or not a
+ *         mapping (`ViaConfigStatus_TypeError`), as reported by `via_config_last_error_status()`.
+ *
+ * @note The returned pointer MUST be freed using `via_config_free()` to avoid memory leaks.
+ */
+ViaConfig *via_config_subconfig(const ViaConfig *config, const char *key);
+
+/**
+ * @brief Checks that the files referenced by the given keys exist on disk.
+ *
+ * A key may hold a single path (e.g., "reasoning.llm.model-path") or a mapping
+ * or sequence of paths (e.g., "perception.model-paths"), in which case every
+ * path in it is checked. Relative paths are resolved against the current
+ * working directory.
+ *
+ * @param config     A valid `ViaConfig` handle.
+ * @param keys       An array of `count` null-terminated keys.
+ * @param count      The number of entries in `keys`.
+ * @param out_report A pointer to a `char*` that receives a newline-separated
+ *                   report, one line per missing path, missing key or non-path
+ *                   value, or `NULL` if everything exists. A non-null report
+ *                   must be released with `via_config_free_string()`.
+ *
+ * @return `ViaConfigStatus_Ok` if every path exists.
+ * @return `ViaConfigStatus_ValidationFailed` if any problem was found.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_check_paths_exist(const ViaConfig *config,
+                                             const char *const *keys,
+                                             size_t count,
+                                             char **out_report);
+
+/**
+ * @brief Normalizes the separators of path values for the current platform.
+ *
+ * Every `/` and `\` in the paths at the given keys is replaced with
+ * `separator`, so that Windows-authored values such as `models\yolo.onnx`
+ * reach model loaders in a portable form. A key may hold a single path, or a
+ * mapping or sequence of paths (e.g., "perception.model-paths"). Changed
+ * values are set as with `via_config_set_string()`.
+ *
+ * @param config     A valid `ViaConfig` handle.
+ * @param keys       An array of `count` null-terminated keys.
+ * @param count      The number of keys.
+ * @param separator  The separator to normalize to: '/' or '\'.
+ * @param out_report A pointer to a `char*` that receives a newline-separated
+ *                   report if validation fails, or `NULL` on success.
+ *
+ * @return `ViaConfigStatus_Ok` if every path was clean.
+ * @return `ViaConfigStatus_ValidationFailed` if a key is missing or not a path,
+ *         or a path mixes separators, starts with a drive letter while
+ *         normalizing to '/', or is not valid UTF-8. Paths are normalized either way.
+ * @return `ViaConfigStatus_Frozen` if the configuration has been frozen.
+ * @return An error status code on failure.
+ *
+ * @note A non-NULL report MUST be freed using `via_config_free_string()`.
+ */
+ViaConfigStatus via_config_normalize_paths(ViaConfig *config,
+                                           const char *const *keys,
+                                           size_t count,
+                                           char separator,
+                                           char **out_report);
+
+/**
+ * @brief Describes the rules registered on a handle, for debugging.
+ *
+ * The report has one line per rule: each validator registered with
+ * `via_config_register_validator()` (`validator: <key> at <address>`), then
+ * each rule registered with `via_config_add_dependency_rule()`
+ * (`dependency: <key> requires <key> [<bounds>] when <value>`), then each key registered
+ * with `via_config_add_secret_key()` (`secret: <pattern>`).
+ *
+ * @param config     A valid `ViaConfig` handle.
+ * @param out_report A pointer to a `char*` that receives the report, which is
+ *                   empty if no rule is registered. The string must be
+ *                   released with `via_config_free_string()`.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_list_constraints(const ViaConfig *config, char **out_report);
+
+/**
+ * @brief Retrieves a timestamp as Unix epoch seconds.
+ *
+ * The value must be an RFC 3339 timestamp (e.g., "2024-03-01T10:00:00Z" or
+ * "2024-03-01T10:00:00-03:00") or a plain date (e.g., "2024-03-01"), which is
+ * taken as midnight UTC.
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param key A null-terminated string representing the key (e.g., "profile.last-calibration").
+ * @param out_epoch_secs A pointer to an `int64_t` where the result will be stored.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_TypeError` if the value is not a valid timestamp or date.
+ * @return An error status code on failure. `out_epoch_secs` will be untouched.
+ */
+ViaConfigStatus via_config_get_timestamp(const ViaConfig *config, const char *key, int64_t *out_epoch_secs);
+
+/**
+ * @brief Confines every file access of the library to one directory.
+ *
+ * Once set, every path the library reads or writes (configuration files,
+ * files edited with `via_config_save_file()`, paths resolved by
+ * `via_config_get_path()`) is canonicalized and rejected with
+ * `ViaConfigStatus_FileNotFound` unless it lies inside `root`. Canonicalization
+ * resolves `..` components and symbolic links, so they cannot escape the root.
+ * A cache file outside the root is ignored. The setting is process-wide.
+ *
+ * @param root A UTF-8 encoded, null-terminated path of an existing directory,
+ *             or `NULL` to remove the confinement.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_FileNotFound` if `root` does not exist.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_set_root_jail(const char *root);
+
+/**
+ * @brief Registers a fact about the device for `$when` conditions.
+ *
+ * A mapping in a configuration file that holds a `$when: <expression>` key
+ * only applies if the expression holds; otherwise it is dropped when the file
+ * is parsed, before the files are merged. Expressions combine facts, numbers,
+ * quoted strings, `true`/`false`, the comparisons `==`, `!=`, `<`, `<=`, `>`,
+ * `>=`, and `!`, `&&`, `||` with parentheses, e.g.
+ * `$when: has_gpu && cpu_count >= 4`. A condition that refers to a fact that
+ * was not registered fails the load with `ViaConfigStatus_ParseError`.
+ *
+ * Facts are process-wide and only affect files parsed after they are set.
+ *
+ * @param name  A null-terminated fact name: letters, digits, `_`, `-` and `.`,
+ *              starting with a letter or `_`.
+ * @param value A null-terminated value, read as a YAML scalar (so `"true"` is
+ *              a boolean and `"8"` a number), or `NULL` to remove the fact.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_InternalError` if the name is not a valid fact name.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_set_fact(const char *name, const char *value);
+
+/**
+ * @brief Retrieves a color packed as `0xRRGGBBAA`.
+ *
+ * The value may be a hex string ("#FF8800" or "#FF880080") or a sequence of
+ * 3 or 4 integer components in 0..255 (e.g., `[255, 136, 0]`). When no alpha
+ * is given it is 255 (opaque).
+ *
+ * @param config   A valid `ViaConfig` handle.
+ * @param key      A null-terminated string representing the key (e.g., "alerts.alert-color").
+ * @param out_rgba A pointer to a `uint32_t` where the packed color will be stored.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_TypeError` if the color is malformed.
+ *         `via_config_last_error_message()` describes the problem.
+ * @return An error status code on failure. `out_rgba` will be untouched.
+ */
+ViaConfigStatus via_config_get_color(const ViaConfig *config, const char *key, uint32_t *out_rgba);
+
+/**
+ * @brief Creates an iterator over the entries of a mapping.
+ *
+ * Unlike the array accessors, the iterator returns one entry at a time. It
+ * holds a snapshot of the keys and value types of the mapping taken when it
+ * is created, so it stays valid if the handle is later modified or freed.
+ * Entries are returned in document/merge order.
+ *
+ * @param config A valid `ViaConfig` handle.
+ * @param key    A null-terminated key of the mapping, or "" for the root.
+ *
+ * @return A pointer to a `ViaConfigIter` on success.
+ * @return `NULL` on failure. `via_config_last_error_status()` reports
+ *         `ViaConfigStatus_KeyNotFound` if the key does not exist and
+ *         `ViaConfigStatus_TypeError` if it is not a mapping.
+ *
+ * @note The returned pointer MUST be freed using `via_config_iter_free()`.
+ */
+ViaConfigIter *via_config_iter_create(const ViaConfig *config, const char *key);
+
+/**
+ * @brief Advances an iterator to the next entry.
+ *
+ * @param iter           A valid `ViaConfigIter` handle.
+ * @param out_key        A pointer to a `const char*` that will receive the
+ *                       entry's key. The string is owned by the iterator and
+ *                       is valid until the next call or `via_config_iter_free()`.
+ * @param out_value_type A pointer that will receive the type of the entry's
+ *                       value. Nested mappings are reported as
+ *                       `ViaConfigValueType_Mapping`.
+ *
+ * @return `true` if an entry was returned.
+ * @return `false` once every entry has been returned, or if an argument is `NULL`.
+ */
+bool via_config_iter_next(ViaConfigIter *iter, const char **out_key, ViaConfigValueType *out_value_type);
+
+/**
+ * @brief Frees an iterator created by `via_config_iter_create()`.
+ *
+ * @param iter The iterator to free. Passing `NULL` is safe.
+ */
+void via_config_iter_free(ViaConfigIter *iter);
+
+/**
+ * @brief Reloads the configuration from its files and reports which keys changed.
+ *
+ * The files the handle was loaded from are read again and replace its
+ * contents. Load flags, validators and secret keys are kept; values set at
+ * runtime are discarded. The returned keys let a caller reconfigure only the
+ * affected subsystems (e.g., just the camera or just the LLM).
+ *
+ * @param config           A valid `ViaConfig` handle loaded from files.
+ * @param out_changed_keys A pointer to a `char**` that will receive the leaf
+ *                         keys whose value was changed, added or removed. The
+ *                         array must be released with `via_config_free_string_array()`.
+ * @param out_len          A pointer to a `size_t` that will receive the number of keys.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_InternalError` if the handle was not loaded from
+ *         files (e.g., from strings or bytes) or an update is open.
+ * @return `ViaConfigStatus_Frozen` if the configuration has been frozen.
+ * @return An error status code if the files cannot be loaded; the handle is
+ *         then left unchanged.
+ *
+ * @warning Strings previously returned by `via_config_get_string()` become invalid.
+ */
+ViaConfigStatus via_config_reload_with_changes(ViaConfig *config, char ***out_changed_keys, size_t *out_len);
+
+/**
+ * @brief Compares the merged configurations of two sets of files.
+ *
+ * Meant for configuration reviews in CI: loads the system, hardware and
+ * profile files of each set (e.g., from a branch and from main) as
+ * `via_config_load()` does, and reports every leaf key whose effective value
+ * differs, one per line as `key: a -> b`, e.g. `camera.fps: 30 -> 15`. A side
+ * that lacks the key is shown as `(unset)`. Changed and added keys come first,
+ * in the order of the second set, followed by keys only the first set has.
+ * The values of keys that are secret in either set, such as those read with
+ * `$secret_file`, are shown as `***`. No handle is needed; both
+ * configurations are freed internally.
+ *
+ * @param system_a   Path of the system config of the first set.
+ * @param hardware_a Path of the hardware config of the first set.
+ * @param profile_a  Path of the profile config of the first set.
+ * @param system_b   Path of the system config of the second set.
+ * @param hardware_b Path of the hardware config of the second set.
+ * @param profile_b  Path of the profile config of the second set.
+ * @param out_report A pointer to a `char*` that will receive the report, or
+ *                   `NULL` if the configurations are equal.
+ *
+ * @return `ViaConfigStatus_Ok` on success, whether or not they differ.
+ * @return The status of the failed load if either set cannot be loaded. Use
+ *         `via_config_last_error_message()` for details.
+ *
+ * @note A non-null report MUST be freed using `via_config_free_string()`.
+ */
+ViaConfigStatus via_config_diff_files(const char *system_a,
+                                      const char *hardware_a,
+                                      const char *profile_a,
+                                      const char *system_b,
+                                      const char *hardware_b,
+                                      const char *profile_b,
+                                      char **out_report);
+
+/**
+ * @brief Writes the effective configuration to a file, for audits and for reproducing a device's state.
+ *
+ * The file receives the fully merged configuration as YAML, including
+ * interpolated values and values set at runtime, i.e. exactly what the
+ * device is running with. Loading it as the system file, with empty hardware
+ * and profile files, gives back an equal configuration. It is written under
+ * a temporary name and renamed into place, so a reader never sees a partial
+ * file.
+ *
+ * @warning Values of keys registered with `via_config_add_secret_key()` are
+ *          written as they are, so on Unix the file is created readable by
+ *          its owner only (mode 0600). Use `via_config_dump_yaml()` for a
+ *          redacted copy to share, e.g. in a support bundle.
+ *
+ * @param config   A valid `ViaConfig` handle.
+ * @param out_path A null-terminated path of the file to create or replace.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_FileNotFound` if the path is outside the root set
+ *         with `via_config_set_root_jail()`.
+ * @return `ViaConfigStatus_InternalError` if the file cannot be written.
+ * @return An error status code on failure.
+ */
+ViaConfigStatus via_config_write_effective(const ViaConfig *config, const char *out_path);
+
+/**
+ * @brief Upgrades a configuration written for an older schema.
+ *
+ * The top-level `schema-version` key records the schema a configuration was
+ * written for; without it, the configuration is version 1. The migrations
+ * between its version and `target_version` are applied in order (e.g.
+ * version 2 renames `max-threads` to `threads.perception`), and
+ * `schema-version` is set to `target_version`. Only the handle is changed,
+ * not the files on disk. A renamed key whose new name is already set keeps
+ * the existing value.
+ *
+ * @param config         A valid `ViaConfig` handle.
+ * @param target_version The schema version to upgrade to, or 0 for the
+ *                       newest version this library supports.
+ * @param out_report     A pointer to a `char*` that will receive one line per
+ *                       change, or `NULL` if the configuration was already at
+ *                       `target_version`.
+ *
+ * @return `ViaConfigStatus_Ok` on success.
+ * @return `ViaConfigStatus_TypeError` if `schema-version` is not a positive integer.
+ * @return `ViaConfigStatus_InternalError` if `target_version` is newer than
+ *         supported or older than the configuration, or an update is open.
+ * @return `ViaConfigStatus_Frozen` if the configuration has been frozen.
+ * @return An error status code on failure.
+ *
+ * @note A non-null report MUST be freed using `via_config_free_string()`.
+ */
+ViaConfigStatus via_config_migrate(ViaConfig *config, uint32_t target_version, char **out_report);
+
+/**
+ * @brief Checks whether a configuration's schema version is supported.
+ *
+ * Compares the top-level `schema-version` (1 if absent) with the versions
+ * this library reads, to tell operators at startup whether a configuration
+ * is too old or too new for the binary. The handle is not changed.
+ *
+ * @param config     A valid `ViaConfig` handle.
+ * @param out_report A pointer to a `char*` that will receive a description of
+ *                   the result. For an older configuration, it lists the
+ *                   steps of the migrations `via_config_migrate()` would
+ *                   apply, one per line.
+ *
+ * @return `ViaConfigStatus_Ok` if the configuration is at the current version.
+ * @return `ViaConfigStatus_ValidationFailed` if it is older and must be
+ *         migrated with `via_config_migrate()`.
+ * @return `ViaConfigStatus_InternalError` if it is newer than this library
+ *         supports.
+ * @return `ViaConfigStatus_TypeError` if `schema-version` is not a positive integer.
+ * @return An error status code on failure.
+ *
+ * @note The report MUST be freed using `via_config_free_string()`, whatever
+ *       the status.
+ */
+ViaConfigStatus via_config_check_compatibility(const ViaConfig *config, char **out_report);
+
 #ifdef __cplusplus
 } // extern "C"
 #endif // __cplusplus
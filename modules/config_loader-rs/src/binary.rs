@@ -0,0 +1,221 @@
+/**
+ * @file binary.rs
+ * @author TrackieLLM Rust Team
+ * @brief Compact binary serialization of a loaded configuration.
+ *
+ * @copyright Copyright (c) 2024
+ *
+ * Child processes can receive an already-merged configuration as a byte
+ * buffer instead of re-reading and re-parsing the YAML files. The tree is
+ * flattened into a list of events mirroring `serde_yaml::Value` and encoded
+ * with `bincode`, behind a magic header and a format version so that stale or
+ * foreign buffers are rejected instead of misread.
+ */
+
+use bincode::Options;
+use serde::{Deserialize, Serialize};
+use serde_yaml::value::{Tag, TaggedValue};
+use serde_yaml::{Mapping, Number, Value};
+use std::collections::HashMap;
+
+/// Identifies a buffer produced by `encode`.
+const MAGIC: &[u8; 4] = b"VIAC";
+
+/// Bumped whenever the encoded layout changes.
+const FORMAT_VERSION: u8 = 2;
+
+/// One node of a tree in pre-order. Collections are flattened into their
+/// length followed by their children, so that encoding and decoding need no
+/// recursion and a deeply nested buffer cannot overflow the stack. `bincode`
+/// is not self-describing, so `Value` itself could not be decoded with it
+/// directly anyway.
+#[derive(Serialize, Deserialize)]
+enum Event {
+    Null,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    String(String),
+    /// Followed by this many values.
+    Sequence(u64),
+    /// Followed by this many key-value pairs.
+    Mapping(u64),
+    /// Followed by the tagged value.
+    Tagged(String),
+}
+
+/// Flattens a tree into events, in pre-order.
+fn flatten(root: &Value) -> Vec<Event> {
+    let mut events = Vec::new();
+    let mut pending = vec![root];
+    while let Some(val) = pending.pop() {
+        let event = match val {
+            Value::Null => Event::Null,
+            Value::Bool(b) => Event::Bool(*b),
+            Value::Number(n) => match (n.as_i64(), n.as_u64(), n.as_f64()) {
+                (Some(i), _, _) => Event::Int(i),
+                (None, Some(u), _) => Event::UInt(u),
+                (None, None, f) => Event::Float(f.unwrap_or(f64::NAN)),
+            },
+            Value::String(s) => Event::String(s.clone()),
+            Value::Sequence(seq) => {
+                pending.extend(seq.iter().rev());
+                Event::Sequence(seq.len() as u64)
+            }
+            Value::Mapping(map) => {
+                let entries: Vec<&Value> = map.iter().flat_map(|(k, v)| [k, v]).collect();
+                pending.extend(entries.into_iter().rev());
+                Event::Mapping(map.len() as u64)
+            }
+            Value::Tagged(tagged) => {
+                pending.push(&tagged.value);
+                Event::Tagged(tagged.tag.to_string())
+            }
+        };
+        events.push(event);
+    }
+    events
+}
+
+/// A collection being rebuilt by `unflatten`.
+enum Frame {
+    Sequence(u64, Vec<Value>),
+    Mapping(u64, Mapping, Option<Value>),
+    Tagged(String),
+}
+
+/// Rebuilds a tree from events produced by `flatten`, failing if the events
+/// are malformed or the tree is nested deeper than `max_depth`.
+fn unflatten(events: Vec<Event>, max_depth: usize) -> Result<Value, DecodeError> {
+    let mut events = events.into_iter();
+    let mut stack: Vec<Frame> = Vec::new();
+    let root = 'events: loop {
+        let mut val = match events.next().ok_or(DecodeError::Invalid)? {
+            Event::Null => Value::Null,
+            Event::Bool(b) => Value::Bool(b),
+            Event::Int(i) => Value::Number(Number::from(i)),
+            Event::UInt(u) => Value::Number(Number::from(u)),
+            Event::Float(f) => Value::Number(Number::from(f)),
+            Event::String(s) => Value::String(s),
+            Event::Sequence(0) => Value::Sequence(Vec::new()),
+            Event::Mapping(0) => Value::Mapping(Mapping::new()),
+            Event::Sequence(len) => {
+                stack.push(Frame::Sequence(len, Vec::new()));
+                if stack.len() > max_depth {
+                    return Err(DecodeError::TooDeep);
+                }
+                continue;
+            }
+            Event::Mapping(len) => {
+                stack.push(Frame::Mapping(len, Mapping::new(), None));
+                if stack.len() > max_depth {
+                    return Err(DecodeError::TooDeep);
+                }
+                continue;
+            }
+            Event::Tagged(tag) => {
+                stack.push(Frame::Tagged(tag));
+                if stack.len() > max_depth {
+                    return Err(DecodeError::TooDeep);
+                }
+                continue;
+            }
+        };
+
+        // Attach the finished value to its parent, closing every collection it completes.
+        loop {
+            match stack.last_mut() {
+                None => break 'events val,
+                Some(Frame::Sequence(remaining, items)) => {
+                    items.push(val);
+                    *remaining -= 1;
+                    if *remaining > 0 {
+                        continue 'events;
+                    }
+                    let Some(Frame::Sequence(_, items)) = stack.pop() else { unreachable!() };
+                    val = Value::Sequence(items);
+                }
+                Some(Frame::Mapping(remaining, map, key)) => {
+                    let Some(k) = key.take() else {
+                        *key = Some(val);
+                        continue 'events;
+                    };
+                    map.insert(k, val);
+                    *remaining -= 1;
+                    if *remaining > 0 {
+                        continue 'events;
+                    }
+                    let Some(Frame::Mapping(_, map, _)) = stack.pop() else { unreachable!() };
+                    val = Value::Mapping(map);
+                }
+                Some(Frame::Tagged(_)) => {
+                    let Some(Frame::Tagged(tag)) = stack.pop() else { unreachable!() };
+                    val = Value::Tagged(Box::new(TaggedValue { tag: Tag::new(tag), value: val }));
+                }
+            }
+        }
+    };
+    if events.next().is_some() {
+        return Err(DecodeError::Invalid);
+    }
+    Ok(root)
+}
+
+/// Everything about a handle that survives a trip across a process boundary.
+/// Registered callbacks are process-local and are deliberately not included.
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    tree: Vec<Event>,
+    flags: u32,
+    provenance: Vec<(String, i32)>,
+}
+
+/// The decoded contents of a buffer produced by `encode`.
+pub struct Decoded {
+    pub tree: Value,
+    pub flags: u32,
+    pub provenance: HashMap<String, i32>,
+}
+
+/// Reasons a buffer cannot be decoded.
+#[derive(Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The buffer is not a valid encoding for this format version.
+    Invalid,
+    /// The encoded tree is nested deeper than the allowed maximum.
+    TooDeep,
+}
+
+/// Encodes a configuration tree and its metadata into a byte buffer.
+pub fn encode(tree: &Value, flags: u32, provenance: &HashMap<String, i32>) -> Option<Vec<u8>> {
+    let mut provenance: Vec<(String, i32)> = provenance.iter().map(|(k, v)| (k.clone(), *v)).collect();
+    // Sort so that equal configurations always encode to identical bytes.
+    provenance.sort();
+    let snapshot = Snapshot { tree: flatten(tree), flags, provenance };
+
+    let mut bytes = Vec::from(&MAGIC[..]);
+    bytes.push(FORMAT_VERSION);
+    bincode::DefaultOptions::new().serialize_into(&mut bytes, &snapshot).ok()?;
+    Some(bytes)
+}
+
+/// Decodes a buffer produced by `encode`, rejecting trees nested deeper than
+/// `max_depth`.
+pub fn decode(bytes: &[u8], max_depth: usize) -> Result<Decoded, DecodeError> {
+    let payload = bytes
+        .strip_prefix(&MAGIC[..])
+        .and_then(|rest| rest.strip_prefix(&[FORMAT_VERSION]))
+        .ok_or(DecodeError::Invalid)?;
+    // Bound reads by the buffer size so a corrupt length prefix cannot trigger
+    // an oversized allocation.
+    let snapshot: Snapshot = bincode::DefaultOptions::new()
+        .with_limit(payload.len() as u64)
+        .deserialize(payload)
+        .map_err(|_| DecodeError::Invalid)?;
+    Ok(Decoded {
+        tree: unflatten(snapshot.tree, max_depth)?,
+        flags: snapshot.flags,
+        provenance: snapshot.provenance.into_iter().collect(),
+    })
+}
@@ -0,0 +1,58 @@
+/**
+ * @file cache.rs
+ * @author TrackieLLM Rust Team
+ * @brief On-disk cache of merged configurations, keyed by the source files.
+ *
+ * @copyright Copyright (c) 2024
+ *
+ * Parsing and merging the large configurations we ship is a noticeable part
+ * of startup. A cache file holds the binary encoding of a merged
+ * configuration (see `binary.rs`) behind a fingerprint of the source files:
+ * their paths, sizes and modification times, plus the load flags and the
+ * process-wide settings that change what a load produces, i.e. the facts
+ * that `$when` conditions test and the limits. A cache whose fingerprint no
+ * longer matches is stale and is simply rebuilt.
+ *
+ * The cache is an optimization only. Any failure to read or write it falls
+ * back to a normal load and is never reported as an error.
+ */
+
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+
+/// Computes the fingerprint of a set of source files. Returns `None` if any
+/// of them cannot be inspected, in which case the cache is bypassed.
+pub fn fingerprint(paths: &[&Path], flags: u32) -> Option<u64> {
+    let mut hasher = DefaultHasher::new();
+    flags.hash(&mut hasher);
+    crate::conditions::hash_facts(&mut hasher);
+    crate::limits::hash_limits(&mut hasher);
+    for path in paths {
+        let metadata = fs::metadata(path).ok()?;
+        path.hash(&mut hasher);
+        metadata.len().hash(&mut hasher);
+        metadata.modified().ok()?.hash(&mut hasher);
+    }
+    Some(hasher.finish())
+}
+
+/// Returns the encoded configuration stored in `cache_path`, if the cache
+/// exists and was written for `fingerprint`.
+pub fn read(cache_path: &Path, fingerprint: u64) -> Option<Vec<u8>> {
+    let contents = fs::read(cache_path).ok()?;
+    let (stored, encoded) = contents.split_first_chunk::<8>()?;
+    (u64::from_le_bytes(*stored) == fingerprint).then(|| encoded.to_vec())
+}
+
+/// Stores an encoded configuration in `cache_path` for `fingerprint`. The
+/// file is written under a temporary name and renamed into place, so a
+/// concurrent reader never sees a partially written cache.
+pub fn write(cache_path: &Path, fingerprint: u64, encoded: &[u8]) {
+    let mut contents = Vec::with_capacity(8 + encoded.len());
+    contents.extend_from_slice(&fingerprint.to_le_bytes());
+    contents.extend_from_slice(encoded);
+
+    let _ = crate::write_file_atomically(cache_path, &contents);
+}
@@ -0,0 +1,297 @@
+/**
+ * @file conditions.rs
+ * @author TrackieLLM Rust Team
+ * @brief `$when` blocks that only apply on some devices.
+ *
+ * @copyright Copyright (c) 2024
+ *
+ * A mapping holding a `$when: <expression>` key only applies when the
+ * expression holds, so that one configuration can serve several device
+ * tiers, e.g. `accelerated: { $when: has_gpu && cpu_count >= 4, batch-size: 8 }`.
+ *
+ * Expressions read facts about the device, which the application detects and
+ * registers with `via_config_set_fact` before loading. When a file is parsed,
+ * every block whose condition is false is removed, and the `$when` key of the
+ * others; this happens before the files are merged.
+ *
+ * An expression is made of fact names, numbers, quoted strings, `true` and
+ * `false`, the comparisons `==`, `!=`, `<`, `<=`, `>`, `>=`, and `!`, `&&`,
+ * `||` with parentheses. A fact used on its own must be a boolean. Facts are
+ * process-wide.
+ */
+
+use serde_yaml::Value;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+use std::sync::RwLock;
+
+/// The key that makes a mapping conditional.
+pub const WHEN_KEY: &str = "$when";
+
+static FACTS: RwLock<BTreeMap<String, Value>> = RwLock::new(BTreeMap::new());
+
+/// The operators of the expression language, longest first so that `<=` is
+/// not read as `<` followed by `=`.
+const OPERATORS: &[&str] = &["&&", "||", "==", "!=", "<=", ">=", "<", ">", "!", "(", ")"];
+
+/// Returns true if `name` can be used as a fact name in expressions.
+pub fn is_fact_name(name: &str) -> bool {
+    // `true` and `false` would read as the literals.
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(continues_name)
+        && !matches!(name, "true" | "false")
+}
+
+/// Returns true for the characters allowed after the first one of a name.
+fn continues_name(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')
+}
+
+/// Registers a fact, replacing any earlier value, or removes it if `raw` is
+/// `None`. The value is read as a YAML scalar, so `4` is a number and `true`
+/// a boolean; anything else is kept as a string.
+pub fn set_fact(name: &str, raw: Option<&str>) {
+    let mut facts = FACTS.write().unwrap_or_else(|e| e.into_inner());
+    let Some(raw) = raw else {
+        facts.remove(name);
+        return;
+    };
+    let value = match serde_yaml::from_str::<Value>(raw) {
+        Ok(val @ (Value::Bool(_) | Value::Number(_) | Value::String(_))) => val,
+        _ => Value::String(raw.to_string()),
+    };
+    facts.insert(name.to_string(), value);
+}
+
+/// Feeds the registered facts to `hasher`, so that a cached configuration
+/// built under other facts is not reused.
+pub fn hash_facts<H: Hasher>(hasher: &mut H) {
+    FACTS.read().unwrap_or_else(|e| e.into_inner()).hash(hasher);
+}
+
+/// Removes every `$when` block of a parsed document whose condition is false
+/// and the `$when` key of every other one. A document whose root is a false
+/// block becomes empty. Returns a message naming the block if a condition is
+/// invalid or refers to an unknown fact.
+pub fn apply(root: &mut Value) -> Result<(), String> {
+    let facts = FACTS.read().unwrap_or_else(|e| e.into_inner());
+    if !keep(root, "", &facts)? {
+        *root = Value::Null;
+    }
+    Ok(())
+}
+
+/// Applies the conditions under `val`, whose dot-key is `key`, and returns
+/// whether `val` itself is kept.
+fn keep(val: &mut Value, key: &str, facts: &BTreeMap<String, Value>) -> Result<bool, String> {
+    match val {
+        Value::Mapping(map) => {
+            if let Some(condition) = map.shift_remove(WHEN_KEY) {
+                let holds = evaluate(&condition, facts).map_err(|e| {
+                    format!("`{}` of {}: {}", WHEN_KEY, if key.is_empty() { "the document root" } else { key }, e)
+                })?;
+                if !holds {
+                    return Ok(false);
+                }
+            }
+            let mut dropped = Vec::new();
+            for (k, v) in map.iter_mut() {
+                if !keep(v, &crate::child_key(key, &crate::value_to_text(k)), facts)? {
+                    dropped.push(k.clone());
+                }
+            }
+            for k in dropped {
+                map.shift_remove(&k);
+            }
+        }
+        Value::Sequence(seq) => {
+            let mut kept = Vec::with_capacity(seq.len());
+            for (i, mut item) in std::mem::take(seq).into_iter().enumerate() {
+                if keep(&mut item, &format!("{}[{}]", key, i), facts)? {
+                    kept.push(item);
+                }
+            }
+            *seq = kept;
+        }
+        Value::Tagged(tagged) => return keep(&mut tagged.value, key, facts),
+        _ => {}
+    }
+    Ok(true)
+}
+
+/// Evaluates the value of a `$when` key: an expression, or a plain boolean.
+fn evaluate(condition: &Value, facts: &BTreeMap<String, Value>) -> Result<bool, String> {
+    let expr = match condition {
+        Value::Bool(b) => return Ok(*b),
+        Value::String(expr) => expr,
+        other => return Err(format!("the condition must be an expression or a boolean, not `{}`", crate::value_to_text(other))),
+    };
+    let mut parser = Parser { tokens: tokenize(expr)?, pos: 0, facts };
+    let holds = parser.or()?;
+    match parser.tokens.get(parser.pos) {
+        None => Ok(holds),
+        Some(token) => Err(format!("unexpected {} in {:?}", token, expr)),
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Token {
+    Name(String),
+    Number(f64),
+    Text(String),
+    Op(&'static str),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Name(name) => write!(f, "`{}`", name),
+            Token::Number(n) => write!(f, "`{}`", n),
+            Token::Text(s) => write!(f, "{:?}", s),
+            Token::Op(op) => write!(f, "`{}`", op),
+        }
+    }
+}
+
+fn tokenize(expr: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = expr.trim_start();
+    while let Some(c) = rest.chars().next() {
+        let len = if let Some(op) = OPERATORS.iter().find(|op| rest.starts_with(**op)) {
+            tokens.push(Token::Op(op));
+            op.len()
+        } else if c == '"' || c == '\'' {
+            let end = rest[1..].find(c).ok_or_else(|| format!("unterminated string in {:?}", expr))?;
+            tokens.push(Token::Text(rest[1..end + 1].to_string()));
+            end + 2
+        } else if c.is_ascii_digit() || (c == '-' && rest[1..].starts_with(|c: char| c.is_ascii_digit())) {
+            let len = 1 + rest[1..].find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len() - 1);
+            let n = rest[..len].parse().map_err(|_| format!("invalid number `{}` in {:?}", &rest[..len], expr))?;
+            tokens.push(Token::Number(n));
+            len
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let len = rest.find(|c: char| !continues_name(c)).unwrap_or(rest.len());
+            tokens.push(Token::Name(rest[..len].to_string()));
+            len
+        } else {
+            return Err(format!("unexpected `{}` in {:?}", c, expr));
+        };
+        rest = rest[len..].trim_start();
+    }
+    Ok(tokens)
+}
+
+/// A value in an expression.
+enum Operand {
+    Bool(bool),
+    Number(f64),
+    Text(String),
+}
+
+impl Operand {
+    fn kind(&self) -> &'static str {
+        match self {
+            Operand::Bool(_) => "boolean",
+            Operand::Number(_) => "number",
+            Operand::Text(_) => "string",
+        }
+    }
+}
+
+/// A recursive-descent parser that evaluates as it parses. From loosest to
+/// tightest binding: `||`, `&&`, `!`, comparisons.
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    facts: &'a BTreeMap<String, Value>,
+}
+
+impl Parser<'_> {
+    /// Consumes the next token if it is the operator `op`.
+    fn eat(&mut self, op: &str) -> bool {
+        let matched = matches!(self.tokens.get(self.pos), Some(Token::Op(next)) if *next == op);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn or(&mut self) -> Result<bool, String> {
+        let mut holds = self.and()?;
+        while self.eat("||") {
+            // Every operand is still evaluated, so that errors are not hidden by short-circuiting.
+            holds |= self.and()?;
+        }
+        Ok(holds)
+    }
+
+    fn and(&mut self) -> Result<bool, String> {
+        let mut holds = self.not()?;
+        while self.eat("&&") {
+            holds &= self.not()?;
+        }
+        Ok(holds)
+    }
+
+    fn not(&mut self) -> Result<bool, String> {
+        if self.eat("!") {
+            return Ok(!self.not()?);
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<bool, String> {
+        if self.eat("(") {
+            let holds = self.or()?;
+            if !self.eat(")") {
+                return Err("missing `)`".to_string());
+            }
+            return Ok(holds);
+        }
+        let lhs = self.operand()?;
+        let op = match self.tokens.get(self.pos) {
+            Some(Token::Op(op)) if matches!(*op, "==" | "!=" | "<" | "<=" | ">" | ">=") => *op,
+            _ => {
+                return match lhs {
+                    Operand::Bool(b) => Ok(b),
+                    other => Err(format!("a {} is not a condition on its own; compare it with a value", other.kind())),
+                }
+            }
+        };
+        self.pos += 1;
+        let rhs = self.operand()?;
+        let ordering = match (&lhs, &rhs) {
+            (Operand::Number(a), Operand::Number(b)) => a.partial_cmp(b),
+            (Operand::Bool(a), Operand::Bool(b)) if matches!(op, "==" | "!=") => Some(a.cmp(b)),
+            (Operand::Text(a), Operand::Text(b)) if matches!(op, "==" | "!=") => Some(a.cmp(b)),
+            _ => return Err(format!("cannot compare a {} with a {} using `{}`", lhs.kind(), rhs.kind(), op)),
+        };
+        let Some(ordering) = ordering else { return Ok(op == "!=") };
+        Ok(match op {
+            "==" => ordering.is_eq(),
+            "!=" => ordering.is_ne(),
+            "<" => ordering.is_lt(),
+            "<=" => ordering.is_le(),
+            ">" => ordering.is_gt(),
+            _ => ordering.is_ge(),
+        })
+    }
+
+    fn operand(&mut self) -> Result<Operand, String> {
+        let token = self.tokens.get(self.pos).cloned().ok_or_else(|| "the expression ends too early".to_string())?;
+        self.pos += 1;
+        match token {
+            Token::Number(n) => Ok(Operand::Number(n)),
+            Token::Text(s) => Ok(Operand::Text(s)),
+            Token::Name(name) if name == "true" => Ok(Operand::Bool(true)),
+            Token::Name(name) if name == "false" => Ok(Operand::Bool(false)),
+            Token::Name(name) => match self.facts.get(&name) {
+                Some(Value::Bool(b)) => Ok(Operand::Bool(*b)),
+                Some(Value::Number(n)) => Ok(Operand::Number(crate::number_to_f64(n))),
+                Some(val) => Ok(Operand::Text(crate::value_to_text(val))),
+                None => Err(format!("unknown fact '{}'; register it with via_config_set_fact", name)),
+            },
+            Token::Op(_) => Err(format!("expected a value but found {}", token)),
+        }
+    }
+}
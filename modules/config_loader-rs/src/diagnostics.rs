@@ -0,0 +1,81 @@
+/**
+ * @file diagnostics.rs
+ * @author TrackieLLM Rust Team
+ * @brief Error reporting and the per-thread "last error" channel.
+ *
+ * @copyright Copyright (c) 2024
+ *
+ * Functions that return a pointer (such as the loaders) can only signal
+ * failure with `NULL`. To tell the caller *why*, every error is recorded here
+ * as a status code plus a descriptive message, which the C side can retrieve
+ * with `via_config_last_error_status` and `via_config_last_error_message`.
+ *
+ * Errors are also logged. By default log messages are written to stderr; a
+ * host application can capture them in its own log pipeline instead by
+ * installing a callback with `via_config_set_log_callback`.
+ */
+
+use crate::{ViaConfigLogCallback, ViaConfigStatus, VIA_CONFIG_LOG_LEVEL_ERROR};
+use std::cell::RefCell;
+use std::ffi::{c_char, CString};
+use std::sync::Mutex;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<(ViaConfigStatus, CString)>> = const { RefCell::new(None) };
+}
+
+/// The process-wide log callback, or `None` to log to stderr.
+static LOG_CALLBACK: Mutex<Option<ViaConfigLogCallback>> = Mutex::new(None);
+
+/// Messages are built from Rust strings; drop any NUL bytes rather than lose the message.
+fn to_c_message(message: &str) -> CString {
+    CString::new(message.replace('\0', "")).unwrap_or_default()
+}
+
+/// Installs the log callback, or restores logging to stderr if `None`.
+pub fn set_log_callback(callback: Option<ViaConfigLogCallback>) {
+    *LOG_CALLBACK.lock().unwrap_or_else(|e| e.into_inner()) = callback;
+}
+
+/// Logs a message at one of the `VIA_CONFIG_LOG_LEVEL_*` levels.
+pub fn log(level: i32, message: &str) {
+    // Copy the callback out so that it is not called with the lock held.
+    let callback = *LOG_CALLBACK.lock().unwrap_or_else(|e| e.into_inner());
+    match callback {
+        Some(callback) => callback(level, to_c_message(message).as_ptr()),
+        None if level == VIA_CONFIG_LOG_LEVEL_ERROR => eprintln!("Error: {}", message),
+        None => eprintln!("{}", message),
+    }
+}
+
+/// Reports an error: logs it and records it as the calling thread's last error.
+/// Returns `status` so it can be used directly in a `return` or `map_err`.
+pub fn report(status: ViaConfigStatus, message: String) -> ViaConfigStatus {
+    log(VIA_CONFIG_LOG_LEVEL_ERROR, &message);
+    let message = to_c_message(&message);
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some((status, message)));
+    status
+}
+
+/// Clears the calling thread's last error. Called at the start of operations
+/// that report errors through this channel.
+pub fn clear() {
+    LAST_ERROR.with(|e| *e.borrow_mut() = None);
+}
+
+/// Returns the status of the calling thread's last error, or `Ok` if none.
+pub fn last_status() -> ViaConfigStatus {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ViaConfigStatus::Ok, |(status, _)| *status))
+}
+
+/// Returns a copy of the calling thread's last error message, if any.
+#[cfg(feature = "async")]
+pub fn last_message() -> Option<String> {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map(|(_, message)| message.to_string_lossy().into_owned()))
+}
+
+/// Returns the calling thread's last error message, or null if none. The
+/// pointer stays valid until the next error is reported or cleared.
+pub fn last_message_ptr() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(std::ptr::null(), |(_, message)| message.as_ptr()))
+}
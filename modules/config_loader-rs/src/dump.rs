@@ -0,0 +1,108 @@
+/**
+ * @file dump.rs
+ * @author TrackieLLM Rust Team
+ * @brief Normalization passes applied when dumping a configuration as YAML.
+ *
+ * @copyright Copyright (c) 2024
+ *
+ * A dump is meant to be compared against snapshots, so it must be stable
+ * across runs. The passes here rewrite a copy of the tree just before it is
+ * serialized; the loaded configuration itself is never modified, and the
+ * getters keep returning the original values. Keys registered as secrets
+ * are always redacted, so a dump can be logged safely.
+ */
+
+use serde_yaml::{Number, Value};
+use std::cmp::Ordering;
+
+/// The text that replaces the value of a secret key in a dump.
+pub const REDACTED: &str = "***";
+
+/// The largest useful precision: an `f64` carries at most 17 significant digits.
+const MAX_FLOAT_PRECISION: usize = 17;
+
+/// Rounds a float to `precision` decimal places. Non-finite values are kept.
+fn round_float(f: f64, precision: usize) -> f64 {
+    if !f.is_finite() {
+        return f;
+    }
+    // Going through the decimal text rounds exactly as it would be printed,
+    // which avoids the representation errors of scaling by a power of ten.
+    format!("{:.*}", precision.min(MAX_FLOAT_PRECISION), f).parse().unwrap_or(f)
+}
+
+/// Rounds every float in the tree to `precision` decimal places, so that
+/// values such as `0.7000000001` dump as `0.7`. Integers are left untouched.
+pub fn round_floats(val: &mut Value, precision: usize) {
+    match val {
+        Value::Number(n) if n.is_f64() => {
+            if let Some(f) = n.as_f64() {
+                *n = Number::from(round_float(f, precision));
+            }
+        }
+        Value::Sequence(seq) => seq.iter_mut().for_each(|v| round_floats(v, precision)),
+        Value::Mapping(map) => map.values_mut().for_each(|v| round_floats(v, precision)),
+        Value::Tagged(tagged) => round_floats(&mut tagged.value, precision),
+        _ => {}
+    }
+}
+
+/// Sorts the keys of every mapping in the tree, so that two configurations
+/// with the same contents dump identically whatever order their sources
+/// listed the keys in.
+pub fn sort_keys(val: &mut Value) {
+    match val {
+        Value::Mapping(map) => {
+            let mut entries: Vec<(Value, Value)> = std::mem::take(map).into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+            for (k, mut v) in entries {
+                sort_keys(&mut v);
+                map.insert(k, v);
+            }
+        }
+        Value::Sequence(seq) => seq.iter_mut().for_each(sort_keys),
+        Value::Tagged(tagged) => sort_keys(&mut tagged.value),
+        _ => {}
+    }
+}
+
+/// Matches a dot-separated key against a pattern where `*` matches any run of
+/// characters within one segment and `**` matches any run of characters,
+/// dots included. A pattern without wildcards must match the key exactly.
+pub fn key_matches(pattern: &str, key: &str) -> bool {
+    match pattern.strip_prefix("**") {
+        Some(rest) => (0..=key.len()).filter(|&i| key.is_char_boundary(i)).any(|i| key_matches(rest, &key[i..])),
+        None => match pattern.strip_prefix('*') {
+            Some(rest) => {
+                let segment_len = key.find('.').unwrap_or(key.len());
+                (0..=segment_len).filter(|&i| key.is_char_boundary(i)).any(|i| key_matches(rest, &key[i..]))
+            }
+            None => match (pattern.chars().next(), key.chars().next()) {
+                (None, None) => true,
+                (Some(p), Some(k)) if p == k => key_matches(&pattern[p.len_utf8()..], &key[k.len_utf8()..]),
+                _ => false,
+            },
+        },
+    }
+}
+
+/// Returns true if `key`, or any key it is nested in, matches one of `patterns`.
+pub fn is_secret(key: &str, patterns: &[String]) -> bool {
+    let mut ancestors = key.match_indices('.').map(|(i, _)| &key[..i]).chain([key]);
+    ancestors.any(|k| patterns.iter().any(|p| key_matches(p, k)))
+}
+
+/// Replaces every value whose key matches one of `patterns`, including whole
+/// subtrees, with `REDACTED`. `key` is the full dot-separated key of `val`,
+/// or empty for the root.
+pub fn redact(val: &mut Value, key: &str, patterns: &[String]) {
+    if !key.is_empty() && patterns.iter().any(|p| key_matches(p, key)) {
+        *val = Value::String(REDACTED.to_string());
+        return;
+    }
+    if let Value::Mapping(map) = val {
+        for (k, v) in map.iter_mut() {
+            redact(v, &crate::child_key(key, &crate::value_to_text(k)), patterns);
+        }
+    }
+}
@@ -0,0 +1,217 @@
+/**
+ * @file edit.rs
+ * @author TrackieLLM Rust Team
+ * @brief In-place, comment-preserving editing of a single YAML scalar, and
+ * reading of the comments attached to a key.
+ *
+ * @copyright Copyright (c) 2024
+ *
+ * `serde_yaml` discards comments and formatting, so re-serializing a parsed
+ * tree would destroy the documentation our users keep in their config files.
+ * Instead, this module locates the line that holds a dot-separated key in a
+ * block-style YAML document and rewrites only the value portion of that line.
+ * Indentation, the trailing comment, line endings and every other byte of the
+ * file are left untouched.
+ *
+ * The same line lookup recovers the comments that document a key, which a
+ * parsed tree cannot provide.
+ *
+ * Only block mappings are supported. Keys inside flow collections (`{ ... }`)
+ * or values spanning multiple lines cannot be edited this way.
+ */
+
+use serde_yaml::Value;
+
+/// Reasons an in-place edit can fail.
+#[derive(Debug, PartialEq, Eq)]
+pub enum EditError {
+    /// The key path does not exist as a block mapping entry in the document.
+    KeyNotFound,
+    /// The key exists but does not hold a single-line scalar, or the new value
+    /// is not a single-line YAML scalar.
+    NotAScalar,
+}
+
+/// A single line of the document, split into its content and line ending.
+struct Line<'a> {
+    content: &'a str,
+    ending: &'a str,
+}
+
+fn split_lines(text: &str) -> Vec<Line<'_>> {
+    let mut lines = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let (line, remainder) = match rest.find('\n') {
+            Some(pos) => rest.split_at(pos + 1),
+            None => (rest, ""),
+        };
+        let content_len = line.trim_end_matches(['\r', '\n']).len();
+        let (content, ending) = line.split_at(content_len);
+        lines.push(Line { content, ending });
+        rest = remainder;
+    }
+    lines
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// Blank lines, comment-only lines and document markers never start or end a block.
+fn is_structural(line: &str) -> bool {
+    let trimmed = line.trim();
+    !(trimmed.is_empty() || trimmed.starts_with('#') || trimmed == "---" || trimmed == "...")
+}
+
+/// If `line` is a block mapping entry for `key`, returns the byte offset just
+/// past the `:` separator.
+fn match_key(line: &str, key: &str) -> Option<usize> {
+    let body = &line[indent_of(line)..];
+    let offset = line.len() - body.len();
+    for quoted in [format!("\"{key}\""), format!("'{key}'"), key.to_string()] {
+        if let Some(after) = body.strip_prefix(quoted.as_str()) {
+            let after_trimmed = after.trim_start_matches(' ');
+            if let Some(value) = after_trimmed.strip_prefix(':') {
+                if value.is_empty() || value.starts_with([' ', '\t']) {
+                    return Some(offset + quoted.len() + (after.len() - after_trimmed.len()) + 1);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Returns the byte offset where a trailing `# comment` starts in a scalar
+/// value, ignoring `#` characters inside quotes.
+fn comment_start(value: &str) -> Option<usize> {
+    let mut in_single = false;
+    let mut in_double = false;
+    let mut prev = ' ';
+    for (i, c) in value.char_indices() {
+        match c {
+            '\'' if !in_double => in_single = !in_single,
+            '"' if !in_single && prev != '\\' => in_double = !in_double,
+            '#' if !in_single && !in_double && (prev == ' ' || prev == '\t') => return Some(i),
+            _ => {}
+        }
+        prev = c;
+    }
+    None
+}
+
+/// Locates the line index holding the final segment of `key` within `lines`.
+fn find_key_line(lines: &[Line<'_>], key: &str) -> Option<(usize, usize)> {
+    let mut start = 0;
+    let mut end = lines.len();
+    let mut parent_indent: Option<usize> = None;
+    let mut found = None;
+
+    for segment in crate::key_segments(key) {
+        // The block's indentation is that of its first structural line.
+        let block_indent = lines[start..end]
+            .iter()
+            .find(|l| is_structural(l.content))
+            .map(|l| indent_of(l.content))?;
+        if parent_indent.is_some_and(|p| block_indent <= p) {
+            return None;
+        }
+
+        let mut hit = None;
+        for (i, line) in lines.iter().enumerate().take(end).skip(start) {
+            if !is_structural(line.content) || indent_of(line.content) != block_indent {
+                continue;
+            }
+            if let Some(value_offset) = match_key(line.content, &segment) {
+                hit = Some((i, value_offset));
+                break;
+            }
+        }
+        let (line_index, value_offset) = hit?;
+
+        // The child block runs until the next structural line at or above this indentation.
+        let child_end = lines[line_index + 1..end]
+            .iter()
+            .position(|l| is_structural(l.content) && indent_of(l.content) <= block_indent)
+            .map_or(end, |p| line_index + 1 + p);
+
+        found = Some((line_index, value_offset));
+        parent_indent = Some(block_indent);
+        start = line_index + 1;
+        end = child_end;
+    }
+    found
+}
+
+/// Replaces the scalar value of `key` in `document` with `new_value`, keeping
+/// everything else byte-identical. `new_value` is raw YAML scalar text.
+pub fn replace_scalar(document: &str, key: &str, new_value: &str) -> Result<String, EditError> {
+    match serde_yaml::from_str::<Value>(new_value) {
+        Ok(Value::Mapping(_)) | Ok(Value::Sequence(_)) | Ok(Value::Tagged(_)) | Err(_) => {
+            return Err(EditError::NotAScalar)
+        }
+        Ok(_) if new_value.contains(['\n', '\r']) => return Err(EditError::NotAScalar),
+        Ok(_) => {}
+    }
+
+    let lines = split_lines(document);
+    let (line_index, value_offset) = find_key_line(&lines, key).ok_or(EditError::KeyNotFound)?;
+    let line = lines[line_index].content;
+
+    // Split the tail into `<spaces><value><spaces><comment>`.
+    let tail = &line[value_offset..];
+    let value_start = tail.len() - tail.trim_start_matches([' ', '\t']).len();
+    let value_and_comment = &tail[value_start..];
+    let value_len = comment_start(value_and_comment)
+        .map_or(value_and_comment.len(), |c| value_and_comment[..c].trim_end_matches([' ', '\t']).len());
+    if value_len == 0 {
+        // Either a nested block or an empty (null) value spanning no text we can replace.
+        return Err(EditError::NotAScalar);
+    }
+    let value_text = &value_and_comment[..value_len];
+    if value_text.starts_with(['|', '>', '{', '[', '&', '*', '!']) {
+        return Err(EditError::NotAScalar);
+    }
+
+    let mut edited = String::with_capacity(document.len() + new_value.len());
+    for (i, l) in lines.iter().enumerate() {
+        if i == line_index {
+            let prefix_len = value_offset + value_start;
+            edited.push_str(&line[..prefix_len]);
+            edited.push_str(new_value);
+            edited.push_str(&line[prefix_len + value_len..]);
+        } else {
+            edited.push_str(l.content);
+        }
+        edited.push_str(l.ending);
+    }
+    Ok(edited)
+}
+
+/// Strips the `#` and the single space that conventionally follows it.
+fn comment_text(comment: &str) -> &str {
+    let text = comment.trim_start().trim_start_matches('#');
+    text.strip_prefix(' ').unwrap_or(text).trim_end()
+}
+
+/// Returns the comments that document `key` in `document`: the comment-only
+/// lines directly above its line, followed by the trailing comment on the
+/// line itself, one per line and without the `#` markers. Returns `None` if
+/// the key is not found or has no comment.
+pub fn find_description(document: &str, key: &str) -> Option<String> {
+    let lines = split_lines(document);
+    let (line_index, value_offset) = find_key_line(&lines, key)?;
+
+    let mut description: Vec<&str> = lines[..line_index]
+        .iter()
+        .rev()
+        .take_while(|l| l.content.trim_start().starts_with('#'))
+        .map(|l| comment_text(l.content))
+        .collect();
+    description.reverse();
+    let value = &lines[line_index].content[value_offset..];
+    if let Some(start) = comment_start(value) {
+        description.push(comment_text(&value[start..]));
+    }
+    (!description.is_empty()).then(|| description.join("\n"))
+}
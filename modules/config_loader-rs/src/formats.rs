@@ -0,0 +1,161 @@
+/**
+ * @file formats.rs
+ * @author TrackieLLM Rust Team
+ * @brief The parsers that turn the text of a source into a configuration tree.
+ *
+ * @copyright Copyright (c) 2024
+ *
+ * A configuration file is parsed by the `ConfigParser` registered for its
+ * extension with `register_parser`, so that a crate embedding this library can
+ * load its own formats without changes here. Files with any other extension,
+ * and sources that are not files, are parsed as YAML (which includes JSON).
+ *
+ * Whatever the parser, the loader then applies the steps that do not depend
+ * on the format: `$when` conditions, the depth limit and the merge.
+ */
+
+use crate::{merge, merge_kind, VIA_CONFIG_FLAG_MERGE_DOCUMENTS};
+use serde_yaml::Value;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+/// Parses the text of a configuration source in one format.
+pub trait ConfigParser: Send + Sync {
+    /// Parses `text` into a configuration tree, normally a mapping (or null
+    /// for an empty source). `origin` names the source, usually its path, and
+    /// should appear in error messages; the message is reported with
+    /// `ParseError`. `flags` holds the load's `VIA_CONFIG_FLAG_*` bits, which
+    /// a parser may ignore.
+    fn parse(&self, text: &str, origin: &str, flags: u32) -> Result<Value, String>;
+}
+
+/// The registered parsers with the (lowercase) extension each is used for.
+static PARSERS: RwLock<Vec<(String, Arc<dyn ConfigParser>)>> = RwLock::new(Vec::new());
+
+/// Registers `parser` for files whose extension is `extension` (with or
+/// without the leading dot, in any case), for every thread. It replaces any
+/// parser registered earlier for the extension, including the YAML parser
+/// for `yaml` and `yml`.
+pub fn register_parser(extension: &str, parser: impl ConfigParser + 'static) {
+    let extension = extension.trim_start_matches('.').to_ascii_lowercase();
+    let mut parsers = PARSERS.write().unwrap_or_else(|e| e.into_inner());
+    parsers.retain(|(registered, _)| *registered != extension);
+    parsers.push((extension, Arc::new(parser)));
+}
+
+/// Returns the parser for a file: the one registered for its extension, or
+/// the YAML parser.
+pub fn for_path(path: &Path) -> Arc<dyn ConfigParser> {
+    let parsers = PARSERS.read().unwrap_or_else(|e| e.into_inner());
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .and_then(|extension| parsers.iter().find(|(registered, _)| registered.eq_ignore_ascii_case(extension)))
+        .map_or_else(|| Arc::new(YamlParser) as Arc<dyn ConfigParser>, |(_, parser)| Arc::clone(parser))
+}
+
+/// The built-in YAML parser.
+///
+/// A source holding several YAML documents fails, rather than silently using
+/// only one of them, unless `VIA_CONFIG_FLAG_MERGE_DOCUMENTS` is set, in which
+/// case the documents are merged in order.
+pub struct YamlParser;
+
+impl ConfigParser for YamlParser {
+    fn parse(&self, text: &str, origin: &str, flags: u32) -> Result<Value, String> {
+        lint_document(text, origin, flags)?;
+        let mut documents = serde_yaml::Deserializer::from_str(text)
+            .map(<Value as serde::Deserialize>::deserialize)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to parse YAML in file {:?}: {}", origin, e))?;
+        for document in &mut documents {
+            expand_merge_keys(document).map_err(|message| format!("Invalid merge key in file {:?}: {}", origin, message))?;
+            // Conditions are applied to each document, so that a document whose
+            // root is a false `$when` block is dropped before the merge.
+            crate::conditions::apply(document)
+                .map_err(|message| format!("Invalid condition in file {:?}: {}", origin, message))?;
+        }
+
+        match documents.len() {
+            0 => Ok(Value::Null),
+            1 => Ok(documents.remove(0)),
+            count if flags & VIA_CONFIG_FLAG_MERGE_DOCUMENTS == 0 => Err(format!(
+                "File {:?} contains {} YAML documents separated by `---`; a configuration file must hold a single document",
+                origin, count
+            )),
+            _ => {
+                let mut merged = Value::Null;
+                for (index, document) in documents.into_iter().enumerate() {
+                    match document {
+                        Value::Null => {}
+                        Value::Mapping(_) if merged.is_null() => merged = document,
+                        Value::Mapping(_) => merge(&mut merged, &document),
+                        other => {
+                            return Err(format!(
+                                "Document {} of file {:?} is a {} instead of a mapping of settings",
+                                index + 1,
+                                origin,
+                                merge_kind(&other)
+                            ))
+                        }
+                    }
+                }
+                Ok(merged)
+            }
+        }
+    }
+}
+
+/// Runs the pre-parse lints on the (normalized) text of one configuration
+/// document, returning a specific message for the first problem found.
+fn lint_document(content: &str, origin: &str, flags: u32) -> Result<(), String> {
+    if let Some(line) = crate::lint::find_tab_indentation(content) {
+        return Err(format!("Tabs are not allowed for indentation at line {} of {}", line, origin));
+    }
+    if flags & crate::VIA_CONFIG_FLAG_RESOLVE_ANCHORS_STRICT != 0 {
+        if let Some((line, alias)) = crate::lint::find_dangling_alias(content) {
+            return Err(format!("Alias '*{}' at line {} of {} references an undefined anchor", alias, line, origin));
+        }
+    }
+    Ok(())
+}
+
+/// Expands YAML merge keys (`<<: *base` or `<<: [*a, *b]`) into their
+/// enclosing mapping and removes the `<<` key. `serde_yaml` resolves the
+/// aliases but leaves `<<` as an ordinary key.
+///
+/// Keys written in the mapping itself win over merged ones, and earlier
+/// entries of a merge sequence win over later ones. A merged mapping may carry
+/// its own `<<` key (a template built on another template); it is expanded in
+/// turn, with lower precedence than the keys that came with it.
+fn expand_merge_keys(root: &mut Value) -> Result<(), String> {
+    let mut pending = vec![root];
+    while let Some(val) = pending.pop() {
+        match val {
+            Value::Mapping(map) => {
+                while let Some(merged) = map.remove("<<") {
+                    let sources = match merged {
+                        Value::Mapping(source) => vec![source],
+                        Value::Sequence(seq) => seq
+                            .into_iter()
+                            .map(|item| match item {
+                                Value::Mapping(source) => Ok(source),
+                                _ => Err("every entry of a `<<` sequence must be a mapping".to_string()),
+                            })
+                            .collect::<Result<_, _>>()?,
+                        _ => return Err("the value of `<<` must be a mapping or a sequence of mappings".to_string()),
+                    };
+                    for source in sources {
+                        for (k, v) in source {
+                            map.entry(k).or_insert(v);
+                        }
+                    }
+                }
+                pending.extend(map.values_mut());
+            }
+            Value::Sequence(seq) => pending.extend(seq.iter_mut()),
+            Value::Tagged(tagged) => pending.push(&mut tagged.value),
+            _ => {}
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,50 @@
+/**
+ * @file interpolate.rs
+ * @author TrackieLLM Rust Team
+ * @brief Parsing of `${VAR}` environment-variable references in string values.
+ *
+ * @copyright Copyright (c) 2024
+ *
+ * String values may reference environment variables as `${NAME}`, or as
+ * `${NAME:-fallback}` to provide a value used when `NAME` is not set. A
+ * literal `$` is written as `$$`. This module only finds the references; it
+ * never substitutes them.
+ */
+
+/// A single `${...}` reference found in a string.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Reference<'a> {
+    /// The environment variable name.
+    pub name: &'a str,
+    /// True if the reference provides a fallback with `:-`.
+    pub has_fallback: bool,
+}
+
+/// Returns every `${...}` reference in `text`, in order. Unterminated
+/// references are ignored.
+pub fn references(text: &str) -> Vec<Reference<'_>> {
+    let mut found = Vec::new();
+    let mut rest = text;
+    while let Some(pos) = rest.find('$') {
+        let after = &rest[pos + 1..];
+        if let Some(escaped) = after.strip_prefix('$') {
+            rest = escaped;
+            continue;
+        }
+        let Some(body) = after.strip_prefix('{') else {
+            rest = after;
+            continue;
+        };
+        let Some(end) = body.find('}') else { break };
+        let inner = &body[..end];
+        let (name, has_fallback) = match inner.split_once(":-") {
+            Some((name, _)) => (name, true),
+            None => (inner, false),
+        };
+        if !name.is_empty() {
+            found.push(Reference { name, has_fallback });
+        }
+        rest = &body[end + 1..];
+    }
+    found
+}
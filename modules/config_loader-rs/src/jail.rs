@@ -0,0 +1,42 @@
+/**
+ * @file jail.rs
+ * @author TrackieLLM Rust Team
+ * @brief Optional confinement of every file access to one directory.
+ *
+ * @copyright Copyright (c) 2024
+ *
+ * When configuration paths come from untrusted input, a crafted path such as
+ * `../../etc/passwd` must not make the library read (or write) arbitrary
+ * files. Once a root is set with `via_config_set_root_jail`, every path the
+ * library opens is canonicalized first, which resolves `..` components and
+ * symbolic links, and rejected unless it lies inside the root.
+ *
+ * The root is process-wide. No root is set by default.
+ */
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+static ROOT: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Sets the root directory, or removes the confinement if `None`. The root
+/// itself is canonicalized, so it must exist.
+pub fn set_root(root: Option<&Path>) -> std::io::Result<()> {
+    let root = root.map(fs::canonicalize).transpose()?;
+    *ROOT.write().unwrap_or_else(|e| e.into_inner()) = root;
+    Ok(())
+}
+
+/// Checks that `path` lies inside the root, if one is set. Returns a
+/// descriptive message if it does not, or if it cannot be resolved.
+pub fn check(path: &Path) -> Result<(), String> {
+    let root = ROOT.read().unwrap_or_else(|e| e.into_inner());
+    let Some(root) = root.as_ref() else { return Ok(()) };
+    let resolved = fs::canonicalize(path)
+        .map_err(|e| format!("Path {:?} cannot be resolved inside the root {:?}: {}", path, root, e))?;
+    if !resolved.starts_with(root) {
+        return Err(format!("Path {:?} resolves to {:?}, outside the root {:?}", path, resolved, root));
+    }
+    Ok(())
+}
@@ -0,0 +1,130 @@
+/**
+ * @file legacy.rs
+ * @author TrackieLLM Rust Team
+ * @brief Migration of the legacy flat-key configuration format.
+ *
+ * @copyright Copyright (c) 2024
+ *
+ * Before the configuration was split into nested sections, TrackieLLM stored
+ * every setting as a top-level key whose words were joined with underscores,
+ * e.g. `camera_device_id: 0` for what is now `camera: { device-id: 0 }`.
+ *
+ * Underscores are ambiguous on their own (`camera_resolution_width` could be
+ * `camera.resolution-width` or `camera.resolution.width`), so flat keys are
+ * matched against the key paths of the typed models in `models.rs`, where `.`
+ * and `-` both correspond to `_`. Keys below a free-form table such as
+ * `perception.model-paths` are taken verbatim. Keys that are already nested,
+ * and flat keys the models do not know, are kept unchanged.
+ */
+
+use crate::models::{HardwareConfig, ProfileConfig, SystemConfig};
+use schemars::schema::{RootSchema, Schema, SchemaObject};
+use serde_yaml::{Mapping, Value};
+
+/// A key path known to the models, in its current dot-separated form.
+struct KnownPath {
+    path: String,
+    /// True for a free-form table (a map in the models), whose entries may
+    /// have any key.
+    is_table: bool,
+}
+
+/// Returns the schema object `schema` refers to, following `$ref` links and
+/// single-entry `allOf` wrappers into the root's definitions.
+fn resolve<'a>(root: &'a RootSchema, mut schema: &'a SchemaObject) -> &'a SchemaObject {
+    loop {
+        let target = match (&schema.reference, schema.subschemas.as_ref().and_then(|s| s.all_of.as_deref())) {
+            (Some(reference), _) => reference.rsplit('/').next().and_then(|name| root.definitions.get(name)),
+            (None, Some([only])) => Some(only),
+            _ => None,
+        };
+        match target {
+            Some(Schema::Object(target)) => schema = target,
+            _ => return schema,
+        }
+    }
+}
+
+/// Collects the key path of every leaf and table below `schema`.
+fn collect_paths(root: &RootSchema, schema: &SchemaObject, prefix: &str, out: &mut Vec<KnownPath>) {
+    let schema = resolve(root, schema);
+    let Some(object) = schema.object.as_deref() else {
+        out.push(KnownPath { path: prefix.to_string(), is_table: false });
+        return;
+    };
+    if object.properties.is_empty() {
+        out.push(KnownPath { path: prefix.to_string(), is_table: object.additional_properties.is_some() });
+        return;
+    }
+    for (name, property) in &object.properties {
+        let path = if prefix.is_empty() { name.clone() } else { format!("{}.{}", prefix, name) };
+        match property {
+            Schema::Object(property) => collect_paths(root, property, &path, out),
+            Schema::Bool(_) => out.push(KnownPath { path, is_table: false }),
+        }
+    }
+}
+
+/// The key paths of every setting of the system, hardware and profile files.
+fn known_paths() -> Vec<KnownPath> {
+    let mut paths = Vec::new();
+    for root in [
+        schemars::schema_for!(SystemConfig),
+        schemars::schema_for!(HardwareConfig),
+        schemars::schema_for!(ProfileConfig),
+    ] {
+        collect_paths(&root, &root.schema, "", &mut paths);
+    }
+    paths
+}
+
+/// Spells a current key path the way the legacy format did.
+fn flat_form(path: &str) -> String {
+    path.replace(['.', '-'], "_")
+}
+
+/// Maps a legacy flat key to its current dot-separated path, or returns
+/// `None` if the models do not know it.
+fn nested_key(flat_key: &str, known: &[KnownPath]) -> Option<String> {
+    for known_path in known {
+        let flat_path = flat_form(&known_path.path);
+        if flat_key == flat_path {
+            return Some(known_path.path.clone());
+        }
+        if known_path.is_table {
+            if let Some(entry) = flat_key.strip_prefix(&flat_path).and_then(|rest| rest.strip_prefix('_')) {
+                if !entry.is_empty() {
+                    return Some(format!("{}.{}", known_path.path, entry));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Returns true if a top-level entry is in the legacy flat format: a scalar
+/// or sequence under a key whose words are joined with underscores.
+fn is_flat_entry(key: &Value, val: &Value) -> bool {
+    key.as_str().is_some_and(|k| k.contains('_')) && !val.is_mapping()
+}
+
+/// Expands the legacy flat keys of a document into nested mappings. Entries
+/// that are not flat, or whose key the models do not know, are kept as-is.
+/// A document that is not a mapping is returned unchanged.
+pub fn expand(document: Value) -> Value {
+    let Value::Mapping(map) = document else { return document };
+    if !map.iter().any(|(k, v)| is_flat_entry(k, v)) {
+        return Value::Mapping(map);
+    }
+
+    let known = known_paths();
+    let mut expanded = Value::Mapping(Mapping::new());
+    for (key, val) in map {
+        let nested = if is_flat_entry(&key, &val) { key.as_str().and_then(|k| nested_key(k, &known)) } else { None };
+        match nested {
+            Some(path) => crate::set_value_by_key(&mut expanded, &path, val),
+            None => crate::merge(&mut expanded, &Value::Mapping(Mapping::from_iter([(key, val)]))),
+        }
+    }
+    expanded
+}
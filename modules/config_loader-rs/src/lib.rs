@@ -10,10 +10,38 @@
  * handling file I/O and parsing within Rust to prevent common C/C++ vulnerabilities.
  */
 
+mod binary;
+mod cache;
+mod conditions;
+mod diagnostics;
+mod dump;
+mod edit;
+mod formats;
+mod interpolate;
+mod jail;
+mod legacy;
+mod limits;
+mod lint;
+mod messages;
+mod metrics;
+mod migrate;
+#[cfg(feature = "async")]
+mod nonblocking;
+pub mod models;
+mod patch;
+mod secrets;
+mod template;
+
 use serde_yaml::Value;
-use std::ffi::{c_char, CStr};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ffi::{c_char, c_void, CStr, CString};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+pub use formats::{register_parser, ConfigParser};
+#[cfg(feature = "async")]
+pub use nonblocking::{load_async, load_async_with_flags, ViaConfigError};
 
 // --- Data Structures ---
 
@@ -24,11 +52,416 @@ pub struct ViaConfig {
     /// The merged configuration tree. We use the dynamic `Value` type
     /// to make querying by string key straightforward.
     merged_value: Value,
+    /// Custom per-key checks registered by the C side, run on demand by
+    /// `via_config_run_validators`.
+    validators: Vec<(String, ViaConfigValidator)>,
+    /// Rules registered with `via_config_add_dependency_rule`, checked by
+    /// `via_config_check_dependencies`.
+    dependency_rules: Vec<DependencyRule>,
+    /// The `VIA_CONFIG_FLAG_*` bits the handle was loaded with.
+    flags: u32,
+    /// For each leaf dot-key, the index of the source layer that supplied its
+    /// value (0 = system, 1 = hardware, 2 = profile), or `SOURCE_RUNTIME` if
+    /// it was changed after load.
+    provenance: HashMap<String, i32>,
+    /// Set by `via_config_freeze`; once true, every mutating call fails with
+    /// `ViaConfigStatus::Frozen`.
+    frozen: bool,
+    /// Changes staged by the `via_config_set_*` functions,
+    /// `via_config_remove_key` and `via_config_reset_key` while an update
+    /// started with `via_config_begin_update` is open.
+    pending_update: Option<Vec<(String, StagedChange)>>,
+    /// The parsed source layers the tree was merged from, in merge order
+    /// (index 0 is the system layer). Empty for handles restored from a
+    /// binary buffer or cache.
+    layers: Vec<Value>,
+    /// True if the configuration was restored from an on-disk cache instead
+    /// of being parsed from the YAML files.
+    cached: bool,
+    /// Keys or glob patterns registered with `via_config_add_secret_key`, and
+    /// the keys whose value was read from a `$secret_file`, whose values are
+    /// redacted in dumps.
+    secret_keys: Vec<String>,
+    /// With `VIA_CONFIG_FLAG_RECORD_TIMINGS`, the read+parse duration of each
+    /// source file in microseconds, in load order.
+    load_timings: Vec<(String, u64)>,
+    /// The files the loaded profiles inherit from through `extends`, each
+    /// profile's farthest ancestor first. These are not part of the cache
+    /// fingerprint.
+    parent_profiles: Vec<PathBuf>,
+    /// The files the handle was loaded from, in merge order, so that it can
+    /// be reloaded. Empty for handles not loaded from the system, hardware
+    /// and profile files.
+    source_paths: Vec<PathBuf>,
+    /// With `VIA_CONFIG_FLAG_RECORD_OVERRIDES`, every value a later source
+    /// replaced during the merge, in merge order.
+    overrides: Vec<Override>,
+    /// For leaves changed after load by something other than a plain
+    /// `via_config_set_*` call, what changed them (one of the `ORIGIN_*`
+    /// labels), for `via_config_explain_key`.
+    runtime_origins: HashMap<String, &'static str>,
+    /// With `VIA_CONFIG_FLAG_RECORD_METRICS`, the lookups made by the typed
+    /// getters, for `via_config_dump_metrics`.
+    metrics: Option<metrics::Metrics>,
+}
+
+/// A change staged while an update is open, applied by
+/// `via_config_commit_update`.
+#[derive(Debug)]
+enum StagedChange {
+    /// Sets the key to the value.
+    Set(Value),
+    /// Removes the key and the subtree below it.
+    Remove,
+    /// Restores the key to its value in the system layer.
+    Reset,
+}
+
+/// A rule that `require_key` must be set whenever `if_key` has the value
+/// `if_equals`, e.g. a window size whenever a filter is enabled.
+#[derive(Debug)]
+struct DependencyRule {
+    if_key: String,
+    /// Compared with the plain-text form of the value, as `value_to_text` renders it.
+    if_equals: String,
+    require_key: String,
+    /// Bounds the required value must also lie within, if any.
+    range: Option<DependencyRange>,
+}
+
+/// Numeric bounds on the required key of a `DependencyRule`. An infinite
+/// bound leaves that side open.
+#[derive(Debug)]
+struct DependencyRange {
+    min: f64,
+    min_exclusive: bool,
+    max: f64,
+    max_exclusive: bool,
+}
+
+impl DependencyRange {
+    fn contains(&self, x: f64) -> bool {
+        let above = if self.min_exclusive { x > self.min } else { x >= self.min };
+        let below = if self.max_exclusive { x < self.max } else { x <= self.max };
+        above && below
+    }
+
+    /// Describes the bounds as comparisons, e.g. `> 0` or `>= 1 and < 10`.
+    fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if self.min.is_finite() {
+            parts.push(format!("{} {}", if self.min_exclusive { ">" } else { ">=" }, self.min));
+        }
+        if self.max.is_finite() {
+            parts.push(format!("{} {}", if self.max_exclusive { "<" } else { "<=" }, self.max));
+        }
+        parts.join(" and ")
+    }
+}
+
+/// A value of one source replaced by a later source during the merge.
+#[derive(Debug)]
+struct Override {
+    key: String,
+    old: Value,
+    new: Value,
+    /// The layer index of the overriding source.
+    source: usize,
+}
+
+/// Provenance index for values changed after load rather than read from a file.
+const SOURCE_RUNTIME: i32 = -1;
+
+/// How `via_config_explain_key` describes a value changed after load.
+const ORIGIN_RUNTIME: &str = "runtime change";
+const ORIGIN_OVERRIDE: &str = "command-line override";
+const ORIGIN_MERGE: &str = "merged handle";
+const ORIGIN_PATCH: &str = "JSON patch";
+
+impl ViaConfig {
+    fn new(merged_value: Value, flags: u32) -> Self {
+        ViaConfig {
+            merged_value,
+            validators: Vec::new(),
+            dependency_rules: Vec::new(),
+            flags,
+            provenance: HashMap::new(),
+            frozen: false,
+            pending_update: None,
+            layers: Vec::new(),
+            cached: false,
+            secret_keys: Vec::new(),
+            load_timings: Vec::new(),
+            parent_profiles: Vec::new(),
+            source_paths: Vec::new(),
+            overrides: Vec::new(),
+            runtime_origins: HashMap::new(),
+            metrics: (flags & VIA_CONFIG_FLAG_RECORD_METRICS != 0).then(metrics::Metrics::default),
+        }
+    }
+
+    /// Fails with `Frozen` if the handle has been frozen. Every function that
+    /// modifies the configuration tree must call this first.
+    fn ensure_mutable(&self) -> Result<(), ViaConfigStatus> {
+        if self.frozen {
+            Err(ViaConfigStatus::Frozen)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Merges the source layers in order (later layers win) and records which
+    /// layer supplied each leaf value. With `VIA_CONFIG_FLAG_STRICT_MERGE`,
+    /// a layer that changes the kind of an existing value fails the merge.
+    /// With `VIA_CONFIG_FLAG_RESOLVE_TEMPLATES`, `{{ key }}` references are
+    /// resolved in the merged tree. With `VIA_CONFIG_FLAG_RECORD_OVERRIDES`,
+    /// every replaced value is recorded. With `VIA_CONFIG_FLAG_STRICT_TOP_LEVEL`
+    /// or `VIA_CONFIG_FLAG_STRICT_SCHEMA`, a merged tree with an unknown
+    /// section or one that does not match the models fails with
+    /// `ValidationFailed`.
+    ///
+    /// An empty layer (an empty or comment-only file parses to null) is a
+    /// no-op override and is retained as an empty mapping. Any other layer
+    /// that is not a mapping fails with `ParseError`.
+    fn from_layers(layers: &[Value], flags: u32) -> Result<Self, ViaConfigStatus> {
+        let layers = layers
+            .iter()
+            .enumerate()
+            .map(|(index, layer)| match layer {
+                Value::Null => Ok(Value::Mapping(serde_yaml::Mapping::new())),
+                Value::Mapping(_) => Ok(layer.clone()),
+                Value::Tagged(tagged) if tagged.value.is_mapping() => Ok(tagged.value.clone()),
+                other => Err(diagnostics::report(
+                    ViaConfigStatus::ParseError,
+                    format!("Source {} is a {} instead of a mapping of settings", index, merge_kind(other)),
+                )),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut merged_value = Value::Mapping(serde_yaml::Mapping::new());
+        let mut overrides = Vec::new();
+        for (index, layer) in layers.iter().enumerate() {
+            if flags & VIA_CONFIG_FLAG_RECORD_OVERRIDES != 0 {
+                collect_overrides(&merged_value, layer, "", index, &mut overrides);
+            }
+            if flags & VIA_CONFIG_FLAG_STRICT_MERGE == 0 {
+                merge(&mut merged_value, layer);
+                continue;
+            }
+            if let Err(conflict) = merge_strict(&mut merged_value, layer, "") {
+                let key = if conflict.key.is_empty() { "(root)" } else { &conflict.key };
+                return Err(diagnostics::report(
+                    ViaConfigStatus::TypeError,
+                    format!(
+                        "Key '{}' is a {} in an earlier source but a {} in source {}",
+                        key, conflict.existing, conflict.replacement, index
+                    ),
+                ));
+            }
+        }
+        if flags & VIA_CONFIG_FLAG_RESOLVE_TEMPLATES != 0 {
+            template::resolve(&mut merged_value).map_err(|message| {
+                diagnostics::report(ViaConfigStatus::ParseError, format!("Invalid template reference: {}", message))
+            })?;
+        }
+        if flags & VIA_CONFIG_FLAG_STRICT_TOP_LEVEL != 0 {
+            let allowed = top_level_keys();
+            let unknown: Vec<String> = merged_value
+                .as_mapping()
+                .into_iter()
+                .flat_map(|map| map.keys())
+                .filter(|k| !k.as_str().is_some_and(|k| allowed.iter().any(|a| a == k)))
+                .map(|k| {
+                    // Name the first source with the key, which is where a typo was made.
+                    let source = layers.iter().position(|layer| layer.as_mapping().is_some_and(|map| map.contains_key(k)));
+                    format!("'{}' (source {})", value_to_text(k), source.unwrap_or_default())
+                })
+                .collect();
+            if !unknown.is_empty() {
+                return Err(diagnostics::report(
+                    ViaConfigStatus::ValidationFailed,
+                    format!("Unknown top-level key {}; expected one of {}", unknown.join(", "), allowed.join(", ")),
+                ));
+            }
+        }
+        if flags & VIA_CONFIG_FLAG_STRICT_SCHEMA != 0 {
+            if let Err(e) = serde_yaml::from_value::<models::MergedConfig>(merged_value.clone()) {
+                return Err(diagnostics::report(
+                    ViaConfigStatus::ValidationFailed,
+                    format!("The merged configuration does not match the schema: {}", e),
+                ));
+            }
+        }
+
+        let mut provenance = HashMap::new();
+        for_each_leaf(&merged_value, "", &mut |key, _| {
+            // The winning layer is the last one that defines the leaf.
+            let source = layers
+                .iter()
+                .rposition(|layer| get_value_by_key(layer, key).is_some_and(|v| !v.is_null()))
+                .map_or(SOURCE_RUNTIME, |i| i as i32);
+            provenance.insert(key.to_string(), source);
+        });
+
+        let mut config = ViaConfig::new(merged_value, flags);
+        config.provenance = provenance;
+        config.layers = layers;
+        config.overrides = overrides;
+        Ok(config)
+    }
+
+    /// Sets a value, or stages it if an update is open.
+    fn set(&mut self, key: &str, val: Value) -> Result<(), ViaConfigStatus> {
+        self.ensure_mutable()?;
+        limits::check_key(key).map_err(|message| diagnostics::report(ViaConfigStatus::InternalError, message))?;
+        match &mut self.pending_update {
+            Some(pending) => pending.push((key.to_string(), StagedChange::Set(val))),
+            None => {
+                set_value_by_key(&mut self.merged_value, key, val);
+                self.mark_runtime(key);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the tree as it will be once the open update, if any, is
+    /// committed.
+    fn staged_tree(&self) -> Cow<'_, Value> {
+        let Some(pending) = &self.pending_update else { return Cow::Borrowed(&self.merged_value); };
+        let mut tree = self.merged_value.clone();
+        for (key, change) in pending {
+            match change {
+                StagedChange::Set(val) => set_value_by_key(&mut tree, key, val.clone()),
+                StagedChange::Remove => remove_pruning_parents(&mut tree, key),
+                StagedChange::Reset => match self.layers.first().and_then(|system| get_value_by_key(system, key)) {
+                    Some(val) => set_value_by_key(&mut tree, key, val.clone()),
+                    None => remove_pruning_parents(&mut tree, key),
+                },
+            }
+        }
+        Cow::Owned(tree)
+    }
+
+    /// Removes a key and the subtree below it, or stages the removal if an
+    /// update is open. Fails with `KeyNotFound` if the key does not exist,
+    /// taking the changes already staged in the open update into account.
+    fn remove(&mut self, key: &str) -> Result<(), ViaConfigStatus> {
+        self.ensure_mutable()?;
+        if get_value_by_key(&self.staged_tree(), key).is_none() {
+            return Err(ViaConfigStatus::KeyNotFound);
+        }
+        match &mut self.pending_update {
+            Some(pending) => pending.push((key.to_string(), StagedChange::Remove)),
+            None => self.apply_removal(key),
+        }
+        Ok(())
+    }
+
+    /// Removes a key, its provenance, and every parent mapping left empty by
+    /// the removal, so that no stray `{}` remains where the key used to be.
+    fn apply_removal(&mut self, key: &str) {
+        if get_value_by_key(&self.merged_value, key).is_none() {
+            return;
+        }
+        remove_pruning_parents(&mut self.merged_value, key);
+        self.provenance.retain(|leaf_key, _| !key_matches_prefix(leaf_key, key));
+        self.runtime_origins.retain(|leaf_key, _| !key_matches_prefix(leaf_key, key));
+    }
+
+    /// Restores a key to its value in the system layer, with the system
+    /// layer as its provenance, or removes it if the system layer does not
+    /// define it.
+    fn apply_reset(&mut self, key: &str) {
+        let Some(val) = self.layers.first().and_then(|system| get_value_by_key(system, key)).cloned() else {
+            self.apply_removal(key);
+            return;
+        };
+        self.provenance.retain(|leaf_key, _| !key_matches_prefix(leaf_key, key));
+        self.runtime_origins.retain(|leaf_key, _| !key_matches_prefix(leaf_key, key));
+        let provenance = &mut self.provenance;
+        for_each_leaf(&val, key, &mut |leaf_key, _| {
+            provenance.insert(leaf_key.to_string(), 0);
+        });
+        set_value_by_key(&mut self.merged_value, key, val);
+    }
+
+    /// Records every leaf under `key` (or the whole tree, if empty) as having
+    /// been set after load.
+    fn mark_runtime(&mut self, key: &str) {
+        let Some(val) = get_node(&self.merged_value, key) else { return; };
+        let provenance = &mut self.provenance;
+        let runtime_origins = &mut self.runtime_origins;
+        for_each_leaf(val, key, &mut |leaf_key, _| {
+            provenance.insert(leaf_key.to_string(), SOURCE_RUNTIME);
+            runtime_origins.remove(leaf_key);
+        });
+    }
+
+    /// Records what changed every leaf under `key` after load, if it has
+    /// been changed (a staged change is only marked once it is committed).
+    fn record_origin(&mut self, key: &str, origin: &'static str) {
+        let Some(val) = get_node(&self.merged_value, key) else { return; };
+        let provenance = &self.provenance;
+        let runtime_origins = &mut self.runtime_origins;
+        for_each_leaf(val, key, &mut |leaf_key, _| {
+            if provenance.get(leaf_key) == Some(&SOURCE_RUNTIME) {
+                runtime_origins.insert(leaf_key.to_string(), origin);
+            }
+        });
+    }
+
+    /// Looks up a key for the typed getters, applying the handle's lookup flags.
+    fn lookup(&self, key: &str) -> Option<&Value> {
+        let val = get_value_by_key(&self.merged_value, key)
+            .filter(|val| !(val.is_null() && self.flags & VIA_CONFIG_FLAG_NULL_AS_MISSING != 0));
+        if let Some(metrics) = &self.metrics {
+            metrics.record(key, val.is_some());
+        }
+        val
+    }
+
+    /// Looks up a key for the numeric getters. With
+    /// `VIA_CONFIG_FLAG_COERCE_NUMERIC_STRINGS`, a string that holds a number
+    /// (e.g. `"4096"`) is returned as that number; any other value is
+    /// returned unchanged.
+    fn lookup_numeric(&self, key: &str) -> Option<Cow<'_, Value>> {
+        self.lookup(key).map(|val| self.coerce_numeric(val))
+    }
+
+    /// Applies `VIA_CONFIG_FLAG_COERCE_NUMERIC_STRINGS` to a value that has
+    /// already been looked up, as `lookup_numeric` does.
+    fn coerce_numeric<'a>(&self, val: &'a Value) -> Cow<'a, Value> {
+        if self.flags & VIA_CONFIG_FLAG_COERCE_NUMERIC_STRINGS != 0 {
+            if let Some(s) = val.as_str() {
+                if let Ok(number @ Value::Number(_)) = serde_yaml::from_str::<Value>(s.trim()) {
+                    return Cow::Owned(number);
+                }
+            }
+        }
+        Cow::Borrowed(val)
+    }
 }
 
+/// A C callback that receives the string form of a value and returns `true`
+/// if the value is acceptable.
+pub type ViaConfigValidator = extern "C" fn(value_as_cstr: *const c_char) -> bool;
+
+/// A C callback that receives the library's log messages. `level` is one of
+/// the `VIA_CONFIG_LOG_LEVEL_*` constants; `msg` is only valid for the
+/// duration of the call.
+pub type ViaConfigLogCallback = extern "C" fn(level: i32, msg: *const c_char);
+
+/// A C callback that `via_config_visit` calls for every node of the tree with
+/// its dot-key, its type, its value as YAML (null for a mapping) and the
+/// caller's `user_data`. The strings are only valid for the duration of the
+/// call.
+pub type ViaConfigVisitor =
+    extern "C" fn(path: *const c_char, value_type: ViaConfigValueType, value_yaml: *const c_char, user_data: *mut c_void);
+
 /// C-compatible enum representing the status of an operation.
 /// Must match the definition in `via_config.h`.
 #[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ViaConfigStatus {
     Ok = 0,
     FileNotFound = 1,
@@ -37,15 +470,284 @@ pub enum ViaConfigStatus {
     TypeError = 4,
     NullArgument = 5,
     InternalError = 6,
+    ValidationFailed = 7,
+    Frozen = 8,
+}
+
+// --- ABI Version ---
+
+/// The version of the C ABI implemented by this library, returned by
+/// `via_config_abi_version`. It must be incremented, together with the
+/// matching `#define` in `via_config.h`, whenever a function, type or
+/// constant is added to or changed in the header.
+pub const VIA_CONFIG_ABI_VERSION: u32 = 36;
+
+// --- Load Flags ---
+
+/// Fail the load with a specific error if an alias (`*name`) references an
+/// anchor (`&name`) that is not defined earlier in the same file, instead of
+/// reporting a generic parse failure.
+pub const VIA_CONFIG_FLAG_RESOLVE_ANCHORS_STRICT: u32 = 1 << 0;
+
+/// Make the typed getters report an explicit null (`key:` with no value) as
+/// `KeyNotFound` instead of `TypeError`, so it can mean "use the default".
+pub const VIA_CONFIG_FLAG_NULL_AS_MISSING: u32 = 1 << 1;
+
+/// Record how long reading and parsing each source file took, for
+/// `via_config_get_load_timings`.
+pub const VIA_CONFIG_FLAG_RECORD_TIMINGS: u32 = 1 << 2;
+
+/// Make the numeric getters accept strings that hold a number, such as a
+/// quoted `"4096"`, instead of reporting `TypeError`.
+pub const VIA_CONFIG_FLAG_COERCE_NUMERIC_STRINGS: u32 = 1 << 3;
+
+/// Make `via_config_get_boolean` also accept `yes`/`no`, `on`/`off` and
+/// `1`/`0` (case-insensitive, as strings or integers), which YAML 1.2 does
+/// not treat as booleans.
+pub const VIA_CONFIG_FLAG_LENIENT_BOOLEANS: u32 = 1 << 4;
+
+/// Fail the load with `TypeError` if a source changes the kind of a value an
+/// earlier source defined (e.g. a mapping redefined as a string), instead of
+/// silently replacing it.
+pub const VIA_CONFIG_FLAG_STRICT_MERGE: u32 = 1 << 5;
+
+/// Replace `{{ dot.key }}` references in string values with the referenced
+/// value after merging, e.g. `greeting: "Hello {{ profile.user-name }}"`.
+/// An unknown key, a reference to a collection or a reference cycle fails
+/// the load with `ParseError`.
+pub const VIA_CONFIG_FLAG_RESOLVE_TEMPLATES: u32 = 1 << 6;
+
+/// Load only the system and hardware files and ignore the profile, e.g. to
+/// reproduce factory defaults for support. `via_config_load_with_flags`
+/// then also accepts a null profile path.
+pub const VIA_CONFIG_FLAG_SKIP_PROFILE: u32 = 1 << 7;
+
+/// Treat a profile file that does not exist as an empty profile instead of
+/// failing with `FileNotFound`, e.g. before a device has been provisioned.
+/// The system and hardware files remain mandatory.
+pub const VIA_CONFIG_FLAG_PROFILE_OPTIONAL: u32 = 1 << 8;
+
+/// Record every value that a later source replaces with a different one
+/// during the merge, with its old and new value, for
+/// `via_config_get_override_log`.
+pub const VIA_CONFIG_FLAG_RECORD_OVERRIDES: u32 = 1 << 9;
+
+/// Accept sources that hold several YAML documents separated by `---` and
+/// merge them in order, later documents winning, instead of failing with
+/// `ParseError`.
+pub const VIA_CONFIG_FLAG_MERGE_DOCUMENTS: u32 = 1 << 10;
+
+/// Fail the load with `ValidationFailed` if the merged configuration does not
+/// match `models::MergedConfig` (a required setting is missing or has the
+/// wrong type), for deployments that require a complete configuration.
+pub const VIA_CONFIG_FLAG_STRICT_SCHEMA: u32 = 1 << 11;
+
+/// Count the lookups of each key made by the typed getters, for
+/// `via_config_dump_metrics`.
+pub const VIA_CONFIG_FLAG_RECORD_METRICS: u32 = 1 << 12;
+
+/// Fail the load with `ValidationFailed` if the merged configuration has a
+/// top-level key that `models::MergedConfig` does not define, e.g. a
+/// misspelled `threds:`. Much cheaper than `VIA_CONFIG_FLAG_STRICT_SCHEMA`.
+pub const VIA_CONFIG_FLAG_STRICT_TOP_LEVEL: u32 = 1 << 13;
+
+/// The top-level keys allowed with `VIA_CONFIG_FLAG_STRICT_TOP_LEVEL`: the
+/// settings at the root of `models::MergedConfig`, plus the schema version.
+fn top_level_keys() -> Vec<String> {
+    let root = schemars::schema_for!(models::MergedConfig);
+    let mut keys: Vec<String> = root.schema.object.map(|object| object.properties.into_keys().collect()).unwrap_or_default();
+    keys.push(migrate::VERSION_KEY.to_string());
+    keys
 }
 
+// --- Log Levels ---
+
+pub const VIA_CONFIG_LOG_LEVEL_DEBUG: i32 = 0;
+pub const VIA_CONFIG_LOG_LEVEL_INFO: i32 = 1;
+pub const VIA_CONFIG_LOG_LEVEL_WARN: i32 = 2;
+pub const VIA_CONFIG_LOG_LEVEL_ERROR: i32 = 3;
+
+// --- Dump Flags ---
+
+/// Round floating-point values to the requested number of decimal places in
+/// the dumped YAML, so that snapshots are stable across merge round-trips.
+pub const VIA_CONFIG_DUMP_FLAG_ROUND_FLOATS: u32 = 1 << 0;
+
+/// Sort the keys of every mapping in the dumped YAML, so that equal
+/// configurations produce byte-identical dumps (and hashes) on every machine.
+pub const VIA_CONFIG_DUMP_FLAG_SORT_KEYS: u32 = 1 << 1;
+
 // --- Internal Helper Functions ---
 
+/// Parses the text of one configuration source as YAML. `origin` names the
+/// source (usually the file path) in diagnostics.
+fn parse_document(content: &str, origin: &str, flags: u32) -> Result<Value, ViaConfigStatus> {
+    parse_source(&formats::YamlParser, content, origin, flags)
+}
+
+/// Parses the text of one configuration source with `parser`, then drops the
+/// `$when` blocks whose condition is false. A leading UTF-8 BOM is ignored
+/// and CRLF line endings are accepted, whatever the format. The size of the
+/// text is checked against the limits before it is parsed, and the tree after.
+fn parse_source(parser: &dyn ConfigParser, content: &str, origin: &str, flags: u32) -> Result<Value, ViaConfigStatus> {
+    limits::check_document_size(content.len(), origin)
+        .map_err(|message| diagnostics::report(ViaConfigStatus::InternalError, message))?;
+    let content = &*lint::normalize_text(content);
+    let mut document =
+        parser.parse(content, origin, flags).map_err(|message| diagnostics::report(ViaConfigStatus::ParseError, message))?;
+    conditions::apply(&mut document).map_err(|message| {
+        diagnostics::report(ViaConfigStatus::ParseError, format!("Invalid condition in file {:?}: {}", origin, message))
+    })?;
+    limits::check_tree(&document, origin).map_err(|message| diagnostics::report(ViaConfigStatus::InternalError, message))?;
+    Ok(document)
+}
+
+/// Reads the text of a source file. Loads normally pass `fs::read_to_string`;
+/// `load_async` passes the contents it has already read without blocking.
+type ReadFile<'a> = dyn FnMut(&Path) -> std::io::Result<String> + 'a;
+
+/// Reads and parses one configuration file.
+fn parse_file(p: &Path, flags: u32) -> Result<Value, ViaConfigStatus> {
+    read_and_parse(p, flags, &mut |p| fs::read_to_string(p), &mut Vec::new())
+}
+
+/// Reads one configuration file with `read`, parses it and replaces its
+/// `$secret_file` references with the secrets, whose keys are appended to
+/// `secret_keys`.
+fn read_and_parse(
+    p: &Path,
+    flags: u32,
+    read: &mut ReadFile<'_>,
+    secret_keys: &mut Vec<String>,
+) -> Result<Value, ViaConfigStatus> {
+    jail::check(p).map_err(|message| diagnostics::report(ViaConfigStatus::FileNotFound, message))?;
+    let content =
+        read(p).map_err(|e| diagnostics::report(ViaConfigStatus::FileNotFound, format!("Failed to read file {:?}: {}", p, e)))?;
+    let mut document = parse_source(&*formats::for_path(p), &content, &p.to_string_lossy(), flags)?;
+    secrets::resolve(&mut document, p, secret_keys).map_err(|(status, message)| diagnostics::report(status, message))?;
+    Ok(document)
+}
+
+/// Reads, parses and merges the system, hardware and profile files.
+fn load_files(system_path: &Path, hardware_path: &Path, profile_path: &Path, flags: u32) -> Result<ViaConfig, ViaConfigStatus> {
+    // Merge configurations (profile > hardware > system).
+    load_layers(&[system_path, hardware_path, profile_path], flags)
+}
+
+/// The layer index of the first profile; layers before it are the system and
+/// hardware files.
+const FIRST_PROFILE_LAYER: usize = 2;
+
+/// The profile key naming the parent profile it inherits from.
+const EXTENDS_KEY: &str = "extends";
+
+/// Reads and parses each file, then merges them in order, later files winning.
+/// `paths` holds the system and hardware files followed by the profiles,
+/// which are ignored with `VIA_CONFIG_FLAG_SKIP_PROFILE` and may be missing
+/// with `VIA_CONFIG_FLAG_PROFILE_OPTIONAL`.
+fn load_layers(paths: &[&Path], flags: u32) -> Result<ViaConfig, ViaConfigStatus> {
+    load_layers_with(paths, flags, &mut |p| fs::read_to_string(p))
+}
+
+/// Loads like `load_layers`, reading every file with `read`.
+fn load_layers_with(paths: &[&Path], flags: u32, read: &mut ReadFile<'_>) -> Result<ViaConfig, ViaConfigStatus> {
+    let paths = if flags & VIA_CONFIG_FLAG_SKIP_PROFILE != 0 { &paths[..FIRST_PROFILE_LAYER.min(paths.len())] } else { paths };
+    let mut layers = Vec::with_capacity(paths.len());
+    let mut timings = Vec::new();
+    let mut parent_profiles = Vec::new();
+    let mut secret_keys = Vec::new();
+    for (index, path) in paths.iter().enumerate() {
+        let start = std::time::Instant::now();
+        if index < FIRST_PROFILE_LAYER {
+            layers.push(read_and_parse(path, flags, read, &mut secret_keys)?);
+        } else if flags & VIA_CONFIG_FLAG_PROFILE_OPTIONAL != 0 && !path.exists() {
+            layers.push(Value::Mapping(serde_yaml::Mapping::new()));
+        } else {
+            let (profile, ancestors) = parse_profile(path, flags, read, &mut secret_keys)?;
+            layers.push(profile);
+            parent_profiles.extend(ancestors);
+        }
+        if flags & VIA_CONFIG_FLAG_RECORD_TIMINGS != 0 {
+            timings.push((path.to_string_lossy().into_owned(), start.elapsed().as_micros() as u64));
+        }
+    }
+    let mut config = ViaConfig::from_layers(&layers, flags)?;
+    config.load_timings = timings;
+    config.parent_profiles = parent_profiles;
+    secret_keys.sort();
+    secret_keys.dedup();
+    config.secret_keys = secret_keys;
+    config.source_paths = paths.iter().map(|path| path.to_path_buf()).collect();
+    Ok(config)
+}
+
+/// Reads and parses a profile. If it names a parent with `extends`, the parent
+/// is loaded first and the profile merged on top of it, without the
+/// `extends` key. Parents may extend further profiles in turn; each profile
+/// in the chain overrides the ones it extends. Returns the merged profile and
+/// the paths of its ancestors, farthest first.
+///
+/// A chain that leads back to a profile already in it fails with
+/// `ParseError`, naming the cycle.
+fn parse_profile(
+    path: &Path,
+    flags: u32,
+    read: &mut ReadFile<'_>,
+    secret_keys: &mut Vec<String>,
+) -> Result<(Value, Vec<PathBuf>), ViaConfigStatus> {
+    // The profile and its ancestors, nearest first.
+    let mut chain = vec![(path.to_path_buf(), read_and_parse(path, flags, read, secret_keys)?)];
+    loop {
+        let Some((child_path, child)) = chain.last_mut() else { unreachable!() };
+        let Some(parent_path) = take_extends(child, child_path)? else { break };
+        if chain.iter().any(|(p, _)| same_file(p, &parent_path)) {
+            let cycle: Vec<String> = chain.iter().map(|(p, _)| format!("{:?}", p)).chain([format!("{:?}", parent_path)]).collect();
+            return Err(diagnostics::report(
+                ViaConfigStatus::ParseError,
+                format!("Profiles extend each other in a cycle: {}", cycle.join(" -> ")),
+            ));
+        }
+        let parent = read_and_parse(&parent_path, flags, read, secret_keys)?;
+        chain.push((parent_path, parent));
+    }
+
+    let Some((farthest_path, mut merged)) = chain.pop() else { unreachable!() };
+    let mut ancestors = vec![farthest_path];
+    while let Some((child_path, child)) = chain.pop() {
+        merge(&mut merged, &child);
+        ancestors.push(child_path);
+    }
+    // The last path popped is the profile itself, which is not an ancestor.
+    ancestors.pop();
+    Ok((merged, ancestors))
+}
+
+/// Removes the `extends` key from a parsed profile and returns the parent it
+/// names, resolved against the directory of `profile_path`.
+fn take_extends(profile: &mut Value, profile_path: &Path) -> Result<Option<PathBuf>, ViaConfigStatus> {
+    let Some(parent) = profile.as_mapping_mut().and_then(|map| map.remove(EXTENDS_KEY)) else { return Ok(None) };
+    let Some(parent) = parent.as_str() else {
+        return Err(diagnostics::report(
+            ViaConfigStatus::ParseError,
+            format!("The `{}` key in {:?} must be a file path", EXTENDS_KEY, profile_path),
+        ));
+    };
+    Ok(Some(profile_path.parent().unwrap_or(Path::new("")).join(parent)))
+}
+
+/// Returns true if both paths exist and refer to the same file.
+fn same_file(a: &Path, b: &Path) -> bool {
+    matches!((fs::canonicalize(a), fs::canonicalize(b)), (Ok(a), Ok(b)) if a == b)
+}
+
 /// Merges `source` Value into `dest` Value recursively.
-/// `dest` is modified in place.
+/// `dest` is modified in place. Mappings are merged key by key; any other
+/// value in `source` replaces the one in `dest`. An explicit null in `source`
+/// is not an override and leaves `dest` unchanged.
 fn merge(dest: &mut Value, source: &Value) {
-    if let Value::Mapping(dest_map) = dest {
-        if let Value::Mapping(source_map) = source {
+    match (dest, source) {
+        (_, Value::Null) => {}
+        (Value::Mapping(dest_map), Value::Mapping(source_map)) => {
             for (key, source_val) in source_map {
                 if let Some(dest_val) = dest_map.get_mut(key) {
                     merge(dest_val, source_val);
@@ -54,14 +756,139 @@ fn merge(dest: &mut Value, source: &Value) {
                 }
             }
         }
+        (dest, source) => *dest = source.clone(),
+    }
+}
+
+/// Records every value of `dest` that merging `source` into it replaces with a
+/// different value, following the rules of `merge`. `path` is the dot-key of
+/// `dest`. Filling in a null, or a key `dest` does not have, is not an
+/// override.
+fn collect_overrides(dest: &Value, source: &Value, path: &str, source_index: usize, out: &mut Vec<Override>) {
+    match (dest, source) {
+        (Value::Null, _) | (_, Value::Null) => {}
+        (Value::Mapping(dest_map), Value::Mapping(source_map)) => {
+            for (key, source_val) in source_map {
+                if let Some(dest_val) = dest_map.get(key) {
+                    let child_path = child_key(path, &value_to_text(key));
+                    collect_overrides(dest_val, source_val, &child_path, source_index, out);
+                }
+            }
+        }
+        (dest, source) if dest != source => out.push(Override {
+            key: path.to_string(),
+            old: dest.clone(),
+            new: source.clone(),
+            source: source_index,
+        }),
+        _ => {}
+    }
+}
+
+/// A value whose kind a source tried to change during a strict merge.
+struct MergeConflict {
+    key: String,
+    existing: &'static str,
+    replacement: &'static str,
+}
+
+/// The kind of a value, as far as strict merging is concerned. Scalars of
+/// different types (e.g. an integer replaced by a string) are compatible.
+fn merge_kind(val: &Value) -> &'static str {
+    match val {
+        Value::Mapping(_) => "mapping",
+        Value::Sequence(_) => "sequence",
+        Value::Tagged(tagged) => merge_kind(&tagged.value),
+        _ => "scalar",
+    }
+}
+
+/// Variant of `merge` that fails instead of replacing a value with one of a
+/// different kind (mapping, sequence or scalar). `path` is the dot-key of
+/// `dest`, used to name the conflicting key. Nulls on either side never
+/// conflict.
+fn merge_strict(dest: &mut Value, source: &Value, path: &str) -> Result<(), MergeConflict> {
+    match (dest, source) {
+        (_, Value::Null) => Ok(()),
+        (Value::Mapping(dest_map), Value::Mapping(source_map)) => {
+            for (key, source_val) in source_map {
+                let child_path = child_key(path, &value_to_text(key));
+                match dest_map.get_mut(key) {
+                    Some(dest_val) => merge_strict(dest_val, source_val, &child_path)?,
+                    None => {
+                        dest_map.insert(key.clone(), source_val.clone());
+                    }
+                }
+            }
+            Ok(())
+        }
+        (dest, source) if dest.is_null() || merge_kind(dest) == merge_kind(source) => {
+            *dest = source.clone();
+            Ok(())
+        }
+        (dest, source) => Err(MergeConflict {
+            key: path.to_string(),
+            existing: merge_kind(dest),
+            replacement: merge_kind(source),
+        }),
+    }
+}
+
+/// Splits a dot-separated key into its segments. A dot escaped with a
+/// backslash is part of the segment, so `perception.model-paths.yolo\.v8`
+/// names the entry `yolo.v8`; `\\` stands for a backslash. Any other
+/// backslash is kept as is.
+pub(crate) fn key_segments(key: &str) -> Vec<Cow<'_, str>> {
+    if !key.contains('\\') {
+        return key.split('.').map(Cow::Borrowed).collect();
+    }
+    let mut segments = Vec::new();
+    let mut segment = String::new();
+    let mut chars = key.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if matches!(chars.peek(), Some('.' | '\\')) => segment.extend(chars.next()),
+            '.' => segments.push(Cow::Owned(std::mem::take(&mut segment))),
+            _ => segment.push(c),
+        }
+    }
+    segments.push(Cow::Owned(segment));
+    segments
+}
+
+/// Builds the dot-key of a mapping entry from the key of its mapping (empty
+/// for the root), escaping dots and backslashes in the entry's name so that
+/// `key_segments` splits it back into the same segments.
+pub(crate) fn child_key(parent: &str, name: &str) -> String {
+    let name = if name.contains(['.', '\\']) { name.replace('\\', "\\\\").replace('.', "\\.") } else { name.to_string() };
+    if parent.is_empty() {
+        name
+    } else {
+        format!("{}.{}", parent, name)
+    }
+}
+
+/// Splits a dot-separated key at its last unescaped dot into the key of the
+/// parent and the last segment, or returns `None` for a top-level key.
+fn split_parent(key: &str) -> Option<(&str, &str)> {
+    let mut last_dot = None;
+    let mut escaped = false;
+    for (i, c) in key.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '.' => last_dot = Some(i),
+            _ => {}
+        }
     }
+    last_dot.map(|i| (&key[..i], &key[i + 1..]))
 }
 
 /// Traverses the YAML `Value` using a dot-separated key string.
 fn get_value_by_key<'a>(mut current_val: &'a Value, key: &str) -> Option<&'a Value> {
-    for part in key.split('.') {
+    for part in key_segments(key) {
         if let Some(map) = current_val.as_mapping() {
-            if let Some(next_val) = map.get(&Value::String(part.to_string())) {
+            if let Some(next_val) = map.get(Value::String(part.into_owned())) {
                 current_val = next_val;
             } else {
                 return None; // Key part not found
@@ -73,6 +900,212 @@ fn get_value_by_key<'a>(mut current_val: &'a Value, key: &str) -> Option<&'a Val
     Some(current_val)
 }
 
+/// Calls `f` with the full dot-key and value of every leaf under `val`.
+/// Non-empty mappings are descended into; every other value is a leaf.
+/// `prefix` is the dot-key of `val` itself (empty for the root).
+fn for_each_leaf(val: &Value, prefix: &str, f: &mut dyn FnMut(&str, &Value)) {
+    match val.as_mapping() {
+        Some(map) if !map.is_empty() => {
+            for (k, v) in map {
+                let key = child_key(prefix, &value_to_text(k));
+                for_each_leaf(v, &key, f);
+            }
+        }
+        _ if !prefix.is_empty() => f(prefix, val),
+        _ => {}
+    }
+}
+
+/// Counts the leaves `for_each_leaf` would visit, without building their keys.
+fn count_leaves(root: &Value) -> usize {
+    let mut count = 0;
+    let mut pending: Vec<&Value> = root.as_mapping().map_or_else(Vec::new, |map| map.values().collect());
+    while let Some(val) = pending.pop() {
+        match val.as_mapping() {
+            Some(map) if !map.is_empty() => pending.extend(map.values()),
+            _ => count += 1,
+        }
+    }
+    count
+}
+
+/// Sets the value at a dot-separated key, creating intermediate mappings as
+/// needed. Any non-mapping value found along the path is replaced.
+fn set_value_by_key(root: &mut Value, key: &str, new_val: Value) {
+    let mut current_val = root;
+    for part in key_segments(key) {
+        if !current_val.is_mapping() {
+            *current_val = Value::Mapping(serde_yaml::Mapping::new());
+        }
+        let Value::Mapping(map) = current_val else { unreachable!() };
+        current_val = map.entry(Value::String(part.into_owned())).or_insert(Value::Null);
+    }
+    *current_val = new_val;
+}
+
+/// Removes the value at a dot-separated key, returning it if it existed.
+fn remove_value_by_key(root: &mut Value, key: &str) -> Option<Value> {
+    let (parent, last) = match split_parent(key) {
+        Some((parent_key, last)) => (get_value_by_key_mut(root, parent_key)?, last),
+        None => (root, key),
+    };
+    let last = key_segments(last).pop()?;
+    parent.as_mapping_mut()?.shift_remove(Value::String(last.into_owned()))
+}
+
+/// Removes the value at a dot-separated key and every parent mapping left
+/// empty by the removal, so that no stray `{}` remains where the key was.
+fn remove_pruning_parents(root: &mut Value, key: &str) {
+    if remove_value_by_key(root, key).is_none() {
+        return;
+    }
+    let mut child = key;
+    while let Some((parent, _)) = split_parent(child) {
+        if !get_value_by_key(root, parent).and_then(Value::as_mapping).is_some_and(|map| map.is_empty()) {
+            break;
+        }
+        remove_value_by_key(root, parent);
+        child = parent;
+    }
+}
+
+/// Mutable counterpart of `get_value_by_key`.
+fn get_value_by_key_mut<'a>(mut current_val: &'a mut Value, key: &str) -> Option<&'a mut Value> {
+    for part in key_segments(key) {
+        current_val = current_val.as_mapping_mut()?.get_mut(Value::String(part.into_owned()))?;
+    }
+    Some(current_val)
+}
+
+/// Renders a value as plain text: strings are returned verbatim, other
+/// scalars in their YAML form, and collections as compact YAML.
+pub(crate) fn value_to_text(val: &Value) -> String {
+    match val {
+        Value::String(s) => s.clone(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::Null => "null".to_string(),
+        _ => value_to_yaml(val),
+    }
+}
+
+/// Serializes a value as YAML, without the trailing newline.
+fn value_to_yaml(val: &Value) -> String {
+    serde_yaml::to_string(val).map(|s| s.trim_end().to_string()).unwrap_or_default()
+}
+
+/// Looks up a node for the collection accessors, where an empty key refers to
+/// the root of the tree.
+fn get_node<'a>(root: &'a Value, key: &str) -> Option<&'a Value> {
+    if key.is_empty() {
+        Some(root)
+    } else {
+        get_value_by_key(root, key)
+    }
+}
+
+/// Borrows an array of `count` C strings as Rust string slices. Returns
+/// `Err(NullArgument)` for null entries and `Err(InternalError)` for invalid
+/// UTF-8. A null `array` is only accepted when `count` is zero.
+unsafe fn borrow_c_string_array<'a>(array: *const *const c_char, count: usize) -> Result<Vec<&'a str>, ViaConfigStatus> {
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+    if array.is_null() {
+        return Err(ViaConfigStatus::NullArgument);
+    }
+    let mut strings = Vec::with_capacity(count);
+    for i in 0..count {
+        let s = *array.add(i);
+        if s.is_null() {
+            return Err(ViaConfigStatus::NullArgument);
+        }
+        strings.push(CStr::from_ptr(s).to_str().map_err(|_| ViaConfigStatus::InternalError)?);
+    }
+    Ok(strings)
+}
+
+/// Converts a string argument of a loader, reporting a null or non-UTF-8
+/// argument through the last-error channel. `what` names it in the message.
+unsafe fn str_arg<'a>(s: *const c_char, what: &str) -> Option<&'a str> {
+    if s.is_null() {
+        diagnostics::report(ViaConfigStatus::NullArgument, format!("The {} argument is null", what));
+        return None;
+    }
+    match CStr::from_ptr(s).to_str() {
+        Ok(s) => Some(s),
+        Err(_) => {
+            diagnostics::report(ViaConfigStatus::InternalError, format!("The {} argument is not valid UTF-8", what));
+            None
+        }
+    }
+}
+
+/// Writes `contents` to a temporary file next to `path` and renames it into
+/// place, so that a concurrent reader sees either the old file or the new one,
+/// never a partial write.
+fn write_file_atomically(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    write_atomically(path, contents, false)
+}
+
+/// Like `write_file_atomically`, but on Unix the file is created readable and
+/// writable by its owner only (mode 0600), for contents that include secrets.
+fn write_private_file_atomically(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    write_atomically(path, contents, true)
+}
+
+fn write_atomically(path: &Path, contents: &[u8], private: bool) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut temp_name = path.as_os_str().to_owned();
+    temp_name.push(format!(".{}.tmp", std::process::id()));
+    let temp_path = Path::new(&temp_name);
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    if private {
+        // The mode only applies when the file is created, so never reuse a
+        // temporary file left behind by an earlier crash.
+        let _ = fs::remove_file(temp_path);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    }
+    let result = options
+        .open(temp_path)
+        .and_then(|mut file| file.write_all(contents))
+        .and_then(|()| fs::rename(temp_path, path));
+    if result.is_err() {
+        let _ = fs::remove_file(temp_path);
+    }
+    result
+}
+
+/// Moves a Rust string onto the heap as a C string owned by the caller.
+/// Returns `None` if the string contains an interior NUL byte.
+/// The result must be released with `via_config_free_string`.
+fn into_owned_c_string(s: String) -> Option<*mut c_char> {
+    CString::new(s).ok().map(CString::into_raw)
+}
+
+/// Moves a list of strings onto the heap as an array of owned C strings.
+/// The result must be released with `via_config_free_string_array`.
+fn into_owned_c_string_array(strings: Vec<String>) -> Option<(*mut *mut c_char, usize)> {
+    let mut array = Vec::with_capacity(strings.len());
+    for s in strings {
+        match into_owned_c_string(s) {
+            Some(s_c) => array.push(s_c),
+            None => {
+                for s_c in array {
+                    // SAFETY: every entry was just produced by `CString::into_raw`.
+                    drop(unsafe { CString::from_raw(s_c) });
+                }
+                return None;
+            }
+        }
+    }
+    let len = array.len();
+    Some((Box::into_raw(array.into_boxed_slice()) as *mut *mut c_char, len))
+}
+
 // ============================================================================
 // Public C-ABI Functions
 // ============================================================================
@@ -88,48 +1121,289 @@ pub unsafe extern "C" fn via_config_load(
     hardware_path_c: *const c_char,
     profile_path_c: *const c_char,
 ) -> *mut ViaConfig {
-    // --- 1. Convert C strings to Rust strings safely ---
-    let to_string = |s: *const c_char| {
-        if s.is_null() {
-            return None;
-        }
-        CStr::from_ptr(s).to_str().ok().map(String::from)
-    };
+    via_config_load_with_flags(system_path_c, hardware_path_c, profile_path_c, 0)
+}
 
-    let Some(system_path) = to_string(system_path_c) else { return std::ptr::null_mut(); };
-    let Some(hardware_path) = to_string(hardware_path_c) else { return std::ptr::null_mut(); };
-    let Some(profile_path) = to_string(profile_path_c) else { return std::ptr::null_mut(); };
+/// Loads and parses configuration from specified YAML files, with optional
+/// behaviour selected by a bitwise OR of `VIA_CONFIG_FLAG_*` constants.
+///
+/// # Safety
+/// The caller must ensure that all `_path` arguments are valid, null-terminated
+/// C strings; `profile_path_c` may be null with `VIA_CONFIG_FLAG_SKIP_PROFILE`.
+/// The returned pointer must be freed with `via_config_free`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_load_with_flags(
+    system_path_c: *const c_char,
+    hardware_path_c: *const c_char,
+    profile_path_c: *const c_char,
+    flags: u32,
+) -> *mut ViaConfig {
+    diagnostics::clear();
 
-    // --- 2. Read and parse files ---
-    let parse_file = |p: &Path| -> Result<Value, ()> {
-        let content = fs::read_to_string(p).map_err(|_| eprintln!("Error: Failed to read file {:?}", p))?;
-        serde_yaml::from_str(&content).map_err(|_| eprintln!("Error: Failed to parse YAML in file {:?}", p))
+    // --- 1. Convert C strings to Rust strings safely ---
+    let Some(system_path) = str_arg(system_path_c, "system path") else { return std::ptr::null_mut(); };
+    let Some(hardware_path) = str_arg(hardware_path_c, "hardware path") else { return std::ptr::null_mut(); };
+    // The profile is never read when skipped, so its path may be omitted.
+    let profile_path = if profile_path_c.is_null() && flags & VIA_CONFIG_FLAG_SKIP_PROFILE != 0 {
+        ""
+    } else {
+        let Some(profile_path) = str_arg(profile_path_c, "profile path") else { return std::ptr::null_mut(); };
+        profile_path
     };
 
-    let Ok(mut system_config) = parse_file(Path::new(&system_path)) else { return std::ptr::null_mut(); };
-    let Ok(hardware_config) = parse_file(Path::new(&hardware_path)) else { return std::ptr::null_mut(); };
-    let Ok(profile_config) = parse_file(Path::new(&profile_path)) else { return std::ptr::null_mut(); };
-
-    // --- 3. Merge configurations (profile > hardware > system) ---
-    merge(&mut system_config, &hardware_config);
-    merge(&mut system_config, &profile_config);
+    // --- 2. Read, parse and merge files ---
+    let Ok(config) = load_files(Path::new(system_path), Path::new(hardware_path), Path::new(profile_path), flags) else {
+        return std::ptr::null_mut();
+    };
 
-    // --- 4. Create heap-allocated object and return raw pointer ---
-    let config = ViaConfig { merged_value: system_config };
+    // --- 3. Create heap-allocated object and return raw pointer ---
     Box::into_raw(Box::new(config))
 }
 
-/// Frees all memory associated with a `ViaConfig` handle.
+/// Loads configuration with a profile selected by name, resolved as
+/// `<profiles_dir>/<profile_name>.yml`.
+///
+/// Returns null on failure; a missing profile is reported as `FileNotFound`
+/// through `via_config_last_error_status`.
 ///
 /// # Safety
-/// The `config` pointer must be one that was returned from `via_config_load`
-/// and has not been freed yet. Passing a null pointer is safe.
+/// All arguments must be valid, null-terminated C strings. The returned
+/// pointer must be freed with `via_config_free`.
 #[no_mangle]
-pub unsafe extern "C" fn via_config_free(config: *mut ViaConfig) {
-    if !config.is_null() {
-        // Re-constitute the Box and let Rust's RAII drop it, freeing the memory.
-        let _ = Box::from_raw(config);
+pub unsafe extern "C" fn via_config_load_profile(
+    system_path_c: *const c_char,
+    hardware_path_c: *const c_char,
+    profiles_dir_c: *const c_char,
+    profile_name_c: *const c_char,
+) -> *mut ViaConfig {
+    diagnostics::clear();
+
+    let Some(system_path) = str_arg(system_path_c, "system path") else { return std::ptr::null_mut(); };
+    let Some(hardware_path) = str_arg(hardware_path_c, "hardware path") else { return std::ptr::null_mut(); };
+    let Some(profiles_dir) = str_arg(profiles_dir_c, "profiles directory") else { return std::ptr::null_mut(); };
+    let Some(profile_name) = str_arg(profile_name_c, "profile name") else { return std::ptr::null_mut(); };
+
+    // A profile name is a plain file stem; it must not escape the profiles directory.
+    if profile_name.is_empty() || profile_name.contains(['/', '\\']) || profile_name == ".." {
+        diagnostics::report(ViaConfigStatus::FileNotFound, format!("Invalid profile name {:?}", profile_name));
+        return std::ptr::null_mut();
+    }
+    let profile_path = Path::new(profiles_dir).join(format!("{}.yml", profile_name));
+    if !profile_path.is_file() {
+        diagnostics::report(ViaConfigStatus::FileNotFound, format!("Profile {:?} not found at {:?}", profile_name, profile_path));
+        return std::ptr::null_mut();
+    }
+
+    let Ok(config) = load_files(Path::new(system_path), Path::new(hardware_path), &profile_path, 0) else {
+        return std::ptr::null_mut();
+    };
+    Box::into_raw(Box::new(config))
+}
+
+/// Loads configuration from a single file with per-environment sections.
+/// The top-level `default` section is merged with the section named
+/// `env_name_c` (e.g. `dev`, `prod`), with the environment winning.
+///
+/// Returns null on failure; a missing environment section is reported as
+/// `KeyNotFound` through `via_config_last_error_status`. Source indices
+/// reported by `via_config_get_source` are 0 for `default` and 1 for the
+/// environment section.
+///
+/// # Safety
+/// All arguments must be valid, null-terminated C strings. The returned
+/// pointer must be freed with `via_config_free`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_load_environment(
+    path_c: *const c_char,
+    env_name_c: *const c_char,
+) -> *mut ViaConfig {
+    diagnostics::clear();
+
+    let Some(path) = str_arg(path_c, "path") else { return std::ptr::null_mut(); };
+    let Some(env_name) = str_arg(env_name_c, "environment name") else { return std::ptr::null_mut(); };
+    let Ok(document) = parse_file(Path::new(path), 0) else { return std::ptr::null_mut(); };
+
+    let section = |name: &str| document.as_mapping().and_then(|map| map.get(name)).cloned();
+    let default_section = section("default").unwrap_or(Value::Null);
+    let Some(env_section) = section(env_name) else {
+        diagnostics::report(
+            ViaConfigStatus::KeyNotFound,
+            format!("Environment section {:?} not found in {:?}", env_name, path),
+        );
+        return std::ptr::null_mut();
+    };
+
+    let Ok(config) = ViaConfig::from_layers(&[default_section, env_section], 0) else {
+        return std::ptr::null_mut();
+    };
+    Box::into_raw(Box::new(config))
+}
+
+/// Loads a single configuration file written in the legacy flat-key format,
+/// where nested keys were joined with underscores (e.g. `camera_device_id: 0`
+/// for `camera.device-id`). Flat keys are expanded into the nested structure
+/// the current code expects; keys that are already nested, or that the
+/// configuration models do not know, are kept unchanged.
+///
+/// Returns null on failure. Every value reports source index 0 through
+/// `via_config_get_source`.
+///
+/// # Safety
+/// `path_c` must be a valid, null-terminated C string. The returned pointer
+/// must be freed with `via_config_free`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_load_legacy(path_c: *const c_char) -> *mut ViaConfig {
+    diagnostics::clear();
+
+    let Some(path) = str_arg(path_c, "path") else { return std::ptr::null_mut(); };
+    let Ok(document) = parse_file(Path::new(path), 0) else { return std::ptr::null_mut(); };
+    let Ok(config) = ViaConfig::from_layers(&[legacy::expand(document)], 0) else {
+        return std::ptr::null_mut();
+    };
+    Box::into_raw(Box::new(config))
+}
+
+/// Loads configuration with several profiles applied on top of the system
+/// and hardware files, in order. Each profile overrides the ones before it,
+/// so the precedence is `profiles[n-1] > ... > profiles[0] > hardware > system`.
+///
+/// Source indices reported by `via_config_get_source` are 0 for system, 1
+/// for hardware and `2 + i` for `profile_paths[i]`.
+///
+/// # Safety
+/// All strings must be valid and null-terminated, and `profile_paths` must
+/// point to `profile_count` of them. The returned pointer must be freed with
+/// `via_config_free`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_load_with_profiles(
+    system_path_c: *const c_char,
+    hardware_path_c: *const c_char,
+    profile_paths: *const *const c_char,
+    profile_count: usize,
+) -> *mut ViaConfig {
+    diagnostics::clear();
+
+    let Some(system_path) = str_arg(system_path_c, "system path") else { return std::ptr::null_mut(); };
+    let Some(hardware_path) = str_arg(hardware_path_c, "hardware path") else { return std::ptr::null_mut(); };
+    let profile_paths = match borrow_c_string_array(profile_paths, profile_count) {
+        Ok(paths) => paths,
+        Err(status) => {
+            diagnostics::report(status, "The profile path list is invalid".to_string());
+            return std::ptr::null_mut();
+        }
+    };
+
+    let mut paths = vec![Path::new(system_path), Path::new(hardware_path)];
+    paths.extend(profile_paths.into_iter().map(Path::new));
+    let Ok(config) = load_layers(&paths, 0) else {
+        return std::ptr::null_mut();
+    };
+    Box::into_raw(Box::new(config))
+}
+
+/// Loads configuration like `via_config_load_with_flags`, going through an
+/// on-disk cache at `cache_path_c`. The cache is used when it matches the
+/// current paths, sizes and modification times of the three files (and the
+/// flags, facts and limits); otherwise the YAML is parsed and the cache
+/// rewritten. Use `via_config_was_cached` to tell which happened.
+///
+/// Failing to read or write the cache is not an error; the load then simply
+/// parses the files.
+///
+/// # Safety
+/// All arguments must be valid, null-terminated C strings. The returned
+/// pointer must be freed with `via_config_free`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_load_cached(
+    system_path_c: *const c_char,
+    hardware_path_c: *const c_char,
+    profile_path_c: *const c_char,
+    cache_path_c: *const c_char,
+    flags: u32,
+) -> *mut ViaConfig {
+    diagnostics::clear();
+
+    let Some(system_path) = str_arg(system_path_c, "system path") else { return std::ptr::null_mut(); };
+    let Some(hardware_path) = str_arg(hardware_path_c, "hardware path") else { return std::ptr::null_mut(); };
+    let Some(profile_path) = str_arg(profile_path_c, "profile path") else { return std::ptr::null_mut(); };
+    let Some(cache_path) = str_arg(cache_path_c, "cache path") else { return std::ptr::null_mut(); };
+    let paths = [Path::new(system_path), Path::new(hardware_path), Path::new(profile_path)];
+    let cache_path = Path::new(cache_path);
+
+    // A cache outside the root jail is bypassed rather than an error, like any other unusable cache.
+    let fingerprint = cache::fingerprint(&paths, flags).filter(|_| jail::check(cache_path).is_ok());
+    if let Some(fingerprint) = fingerprint {
+        if let Some(decoded) = cache::read(cache_path, fingerprint).and_then(|bytes| binary::decode(&bytes, limits::max_depth()).ok()) {
+            let mut config = ViaConfig::new(decoded.tree, decoded.flags);
+            config.provenance = decoded.provenance;
+            config.cached = true;
+            config.source_paths = paths.iter().map(|path| path.to_path_buf()).collect();
+            return Box::into_raw(Box::new(config));
+        }
+    }
+
+    let Ok(config) = load_layers(&paths, flags) else {
+        return std::ptr::null_mut();
+    };
+    // A parent profile or secret file is not part of the fingerprint, so a cache built from it could go
+    // stale unnoticed; secrets should not be written to disk in the first place.
+    if let Some(fingerprint) = fingerprint.filter(|_| config.parent_profiles.is_empty() && config.secret_keys.is_empty()) {
+        if let Some(bytes) = binary::encode(&config.merged_value, config.flags, &config.provenance) {
+            cache::write(cache_path, fingerprint, &bytes);
+        }
     }
+    Box::into_raw(Box::new(config))
+}
+
+/// Loads configuration directly from in-memory YAML documents instead of
+/// files. The documents are merged exactly as in `via_config_load`.
+///
+/// # Safety
+/// All arguments must be valid, null-terminated C strings. The returned
+/// pointer must be freed with `via_config_free`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_load_from_strings(
+    system_yaml_c: *const c_char,
+    hardware_yaml_c: *const c_char,
+    profile_yaml_c: *const c_char,
+) -> *mut ViaConfig {
+    diagnostics::clear();
+    let parse = |s: *const c_char, origin: &str| -> Option<Value> {
+        let text = str_arg(s, origin)?;
+        parse_document(text, origin, 0).ok()
+    };
+
+    let Some(system_config) = parse(system_yaml_c, "system document") else { return std::ptr::null_mut(); };
+    let Some(hardware_config) = parse(hardware_yaml_c, "hardware document") else { return std::ptr::null_mut(); };
+    let Some(profile_config) = parse(profile_yaml_c, "profile document") else { return std::ptr::null_mut(); };
+
+    let Ok(config) = ViaConfig::from_layers(&[system_config, hardware_config, profile_config], 0) else {
+        return std::ptr::null_mut();
+    };
+    Box::into_raw(Box::new(config))
+}
+
+/// Frees all memory associated with a `ViaConfig` handle.
+///
+/// # Safety
+/// The `config` pointer must be one that was returned from `via_config_load`
+/// and has not been freed yet. Passing a null pointer is safe.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_free(config: *mut ViaConfig) {
+    if !config.is_null() {
+        // Re-constitute the Box and let Rust's RAII drop it, freeing the memory.
+        let _ = Box::from_raw(config);
+    }
+}
+
+/// Reports a getter's `TypeError` through the last-error channel, naming the
+/// type the getter expected and the type of the value it found, e.g.
+/// "Expected integer for key 'camera.device-id' but found string".
+fn type_mismatch(key: &str, expected: &str, found: &Value) -> ViaConfigStatus {
+    diagnostics::report(
+        ViaConfigStatus::TypeError,
+        format!("Expected {} for key '{}' but found {}", expected, key, ViaConfigValueType::of(found).name()),
+    )
 }
 
 /// Retrieves a string value from the configuration.
@@ -149,7 +1423,7 @@ pub unsafe extern "C" fn via_config_get_string(
     let config = &*config;
     let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
 
-    match get_value_by_key(&config.merged_value, key) {
+    match config.lookup(key) {
         Some(val) => {
             if let Some(s) = val.as_str() {
                 // WARNING: This relies on the C++ side to copy the string immediately.
@@ -157,19 +1431,78 @@ pub unsafe extern "C" fn via_config_get_string(
                 *out_value = s.as_ptr() as *const c_char;
                 ViaConfigStatus::Ok
             } else {
-                ViaConfigStatus::TypeError
+                type_mismatch(key, "string", val)
             }
         }
         None => ViaConfigStatus::KeyNotFound,
     }
 }
 
-/// Retrieves an integer value from the configuration.
+/// Copies a string value into a caller-provided buffer of `buf_len` bytes,
+/// without allocating. `out_needed` receives the buffer size the whole value
+/// needs, including the terminating NUL. A value that does not fit is
+/// truncated at a character boundary and still terminated, so a caller can
+/// compare `out_needed` with `buf_len` and retry with a larger buffer.
+///
+/// # Safety
+/// All pointers must be valid, and `out_buf` must have room for `buf_len`
+/// bytes; it may be null if `buf_len` is zero.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_copy_string(
+    config: *const ViaConfig,
+    key_c: *const c_char,
+    out_buf: *mut c_char,
+    buf_len: usize,
+    out_needed: *mut usize,
+) -> ViaConfigStatus {
+    if config.is_null() || key_c.is_null() || (out_buf.is_null() && buf_len > 0) || out_needed.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
+
+    let Some(val) = config.lookup(key) else { return ViaConfigStatus::KeyNotFound; };
+    let Some(s) = val.as_str() else { return type_mismatch(key, "string", val) };
+    *out_needed = s.len() + 1;
+    if buf_len == 0 {
+        return ViaConfigStatus::Ok;
+    }
+    let mut copied = s.len().min(buf_len - 1);
+    while !s.is_char_boundary(copied) {
+        copied -= 1;
+    }
+    std::ptr::copy_nonoverlapping(s.as_ptr() as *const c_char, out_buf, copied);
+    *out_buf.add(copied) = 0;
+    ViaConfigStatus::Ok
+}
+
+/// Retrieves an integer value from the configuration. A value above
+/// `i64::MAX` is a `TypeError` reported as out of range; read it with
+/// `via_config_get_u64`.
+///
+/// # Safety
+/// All pointers must be valid, and `key_c` null-terminated.
 #[no_mangle]
 pub unsafe extern "C" fn via_config_get_integer(
     config: *const ViaConfig,
     key_c: *const c_char,
     out_value: *mut i64,
+) -> ViaConfigStatus {
+    get_integer_as(config, key_c, out_value)
+}
+
+/// Returns the value of an integer node. `serde_yaml` stores integers above
+/// `i64::MAX` (up to `u64::MAX`) as `u64`, which `as_i64` does not return.
+fn integer_value(val: &Value) -> Option<i128> {
+    val.as_i64().map(i128::from).or_else(|| val.as_u64().map(i128::from))
+}
+
+/// Shared implementation of the integer getters. Reads the value as an
+/// integer and narrows it to `T`, returning `TypeError` if it does not fit.
+unsafe fn get_integer_as<T: TryFrom<i128>>(
+    config: *const ViaConfig,
+    key_c: *const c_char,
+    out_value: *mut T,
 ) -> ViaConfigStatus {
     if config.is_null() || key_c.is_null() || out_value.is_null() {
         return ViaConfigStatus::NullArgument;
@@ -177,20 +1510,126 @@ pub unsafe extern "C" fn via_config_get_integer(
     let config = &*config;
     let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
 
-    match get_value_by_key(&config.merged_value, key) {
-        Some(val) => {
-            if let Some(i) = val.as_i64() {
+    match config.lookup_numeric(key) {
+        Some(val) => match integer_value(&val).map(|i| (i, T::try_from(i))) {
+            Some((_, Ok(i))) => {
                 *out_value = i;
                 ViaConfigStatus::Ok
-            } else {
-                ViaConfigStatus::TypeError
             }
-        }
+            Some((i, Err(_))) => diagnostics::report(
+                ViaConfigStatus::TypeError,
+                format!("Value {} for key '{}' is out of range for {}", i, key, std::any::type_name::<T>()),
+            ),
+            None => type_mismatch(key, "integer", &val),
+        },
         None => ViaConfigStatus::KeyNotFound,
     }
 }
 
-/// Retrieves a floating-point value from the configuration.
+/// Retrieves a non-negative integer value, up to `u64::MAX`.
+///
+/// # Safety
+/// All pointers must be valid. Returns `TypeError` if the value is negative.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_get_u64(
+    config: *const ViaConfig,
+    key_c: *const c_char,
+    out_value: *mut u64,
+) -> ViaConfigStatus {
+    get_integer_as(config, key_c, out_value)
+}
+
+/// Retrieves an integer value that must fit in a `u32`.
+///
+/// # Safety
+/// All pointers must be valid. Returns `TypeError` if the value is negative
+/// or larger than `u32::MAX`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_get_u32(
+    config: *const ViaConfig,
+    key_c: *const c_char,
+    out_value: *mut u32,
+) -> ViaConfigStatus {
+    get_integer_as(config, key_c, out_value)
+}
+
+/// Retrieves an integer value that must fit in an `i32`.
+///
+/// # Safety
+/// All pointers must be valid. Returns `TypeError` if the value is outside
+/// the `i32` range.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_get_i32(
+    config: *const ViaConfig,
+    key_c: *const c_char,
+    out_value: *mut i32,
+) -> ViaConfigStatus {
+    get_integer_as(config, key_c, out_value)
+}
+
+/// Retrieves an integer value that must fit in a `u16`.
+///
+/// # Safety
+/// All pointers must be valid. Returns `TypeError` if the value is negative
+/// or larger than `u16::MAX`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_get_u16(
+    config: *const ViaConfig,
+    key_c: *const c_char,
+    out_value: *mut u16,
+) -> ViaConfigStatus {
+    get_integer_as(config, key_c, out_value)
+}
+
+/// Retrieves an integer value clamped into `[min, max]`, as a fail-soft
+/// alternative to range validation for settings such as thread counts. An
+/// out-of-range value is logged as a warning, and `out_clamped` tells the
+/// caller whether it was adjusted.
+///
+/// # Safety
+/// All pointers must be valid. Returns `InternalError` if `min > max`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_get_integer_clamped(
+    config: *const ViaConfig,
+    key_c: *const c_char,
+    min: i64,
+    max: i64,
+    out_value: *mut i64,
+    out_clamped: *mut bool,
+) -> ViaConfigStatus {
+    if config.is_null() || key_c.is_null() || out_value.is_null() || out_clamped.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
+    if min > max {
+        return diagnostics::report(
+            ViaConfigStatus::InternalError,
+            format!("Invalid range [{}, {}] for key '{}'", min, max, key),
+        );
+    }
+
+    let Some(val) = config.lookup_numeric(key) else { return ViaConfigStatus::KeyNotFound; };
+    let Some(i) = integer_value(&val) else { return type_mismatch(key, "integer", &val) };
+    // Clamp in `i128`, so that a value above `i64::MAX` clamps to `max` like any other.
+    let clamped = i.clamp(i128::from(min), i128::from(max)) as i64;
+    if i128::from(clamped) != i {
+        diagnostics::log(
+            VIA_CONFIG_LOG_LEVEL_WARN,
+            &format!("Key '{}' is {}, outside [{}, {}]; using {}", key, i, min, max, clamped),
+        );
+    }
+    *out_value = clamped;
+    *out_clamped = i128::from(clamped) != i;
+    ViaConfigStatus::Ok
+}
+
+/// Retrieves a floating-point value from the configuration. Integer values
+/// (e.g. `5` or `-1`) are promoted to `f64`, so numeric keys authored without
+/// a decimal point read the same as floats.
+///
+/// # Safety
+/// All pointers must be valid, and `key_c` null-terminated.
 #[no_mangle]
 pub unsafe extern "C" fn via_config_get_float(
     config: *const ViaConfig,
@@ -203,20 +1642,122 @@ pub unsafe extern "C" fn via_config_get_float(
     let config = &*config;
     let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
 
-    match get_value_by_key(&config.merged_value, key) {
-        Some(val) => {
-            if let Some(f) = val.as_f64() {
-                *out_value = f;
-                ViaConfigStatus::Ok
-            } else {
-                ViaConfigStatus::TypeError
-            }
+    match config.lookup_numeric(key).as_deref() {
+        Some(Value::Number(n)) => {
+            *out_value = number_to_f64(n);
+            ViaConfigStatus::Ok
         }
+        Some(val) => type_mismatch(key, "float", val),
         None => ViaConfigStatus::KeyNotFound,
     }
 }
 
+/// Retrieves a numeric value like `via_config_get_float` and multiplies it by
+/// `scale`, to convert units in one place, e.g. a sample rate of `44100` Hz
+/// with a scale of `0.001` yields `44.1` kHz. Integers are promoted first.
+///
+/// # Safety
+/// All pointers must be valid, and `key_c` null-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_get_scaled_float(
+    config: *const ViaConfig,
+    key_c: *const c_char,
+    scale: f64,
+    out_value: *mut f64,
+) -> ViaConfigStatus {
+    if out_value.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let mut value = 0.0;
+    let status = via_config_get_float(config, key_c, &mut value);
+    if status == ViaConfigStatus::Ok {
+        *out_value = value * scale;
+    }
+    status
+}
+
+/// Copies a numeric sequence into a caller-provided buffer of `capacity`
+/// elements, for small fixed-size lists such as a calibration offset.
+/// `out_written` receives the number of elements written. A sequence longer
+/// than `capacity` fails with `TypeError` and sets `out_written` to its length,
+/// unless `truncate` is true, in which case the first `capacity` elements are
+/// written. Integers are promoted as in `via_config_get_float`.
+///
+/// # Safety
+/// All pointers must be valid, and `out_buf` must have room for `capacity`
+/// elements; it may be null if `capacity` is zero.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_get_float_into(
+    config: *const ViaConfig,
+    key_c: *const c_char,
+    out_buf: *mut f64,
+    capacity: usize,
+    truncate: bool,
+    out_written: *mut usize,
+) -> ViaConfigStatus {
+    if config.is_null() || key_c.is_null() || (out_buf.is_null() && capacity > 0) || out_written.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
+
+    let Some(val) = config.lookup(key) else { return ViaConfigStatus::KeyNotFound; };
+    let Some(seq) = val.as_sequence() else { return type_mismatch(key, "sequence", val) };
+    if seq.len() > capacity && !truncate {
+        *out_written = seq.len();
+        return diagnostics::report(
+            ViaConfigStatus::TypeError,
+            format!("Key '{}' has {} elements, more than the buffer's {}", key, seq.len(), capacity),
+        );
+    }
+    let mut values = Vec::with_capacity(seq.len().min(capacity));
+    for (index, item) in seq.iter().take(capacity).enumerate() {
+        match config.coerce_numeric(item).as_ref() {
+            Value::Number(n) => values.push(number_to_f64(n)),
+            item => return type_mismatch(&format!("{}[{}]", key, index), "float", item),
+        }
+    }
+    if !values.is_empty() {
+        std::ptr::copy_nonoverlapping(values.as_ptr(), out_buf, values.len());
+    }
+    *out_written = values.len();
+    ViaConfigStatus::Ok
+}
+
+/// Converts any YAML number to `f64`. Integers are converted explicitly
+/// rather than through `as_f64`, so that integer-typed values are accepted
+/// wherever a float is expected.
+fn number_to_f64(n: &serde_yaml::Number) -> f64 {
+    match (n.as_i64(), n.as_u64()) {
+        (Some(i), _) => i as f64,
+        (None, Some(u)) => u as f64,
+        (None, None) => n.as_f64().unwrap_or(f64::NAN),
+    }
+}
+
+/// Reads a boolean spelled as `true`/`false`, `yes`/`no`, `on`/`off` or
+/// `1`/`0` (case-insensitive), for `VIA_CONFIG_FLAG_LENIENT_BOOLEANS`.
+fn lenient_bool(val: &Value) -> Option<bool> {
+    match val {
+        Value::Bool(b) => Some(*b),
+        Value::Number(n) => match n.as_i64() {
+            Some(1) => Some(true),
+            Some(0) => Some(false),
+            _ => None,
+        },
+        Value::String(s) => match s.to_ascii_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => Some(true),
+            "false" | "no" | "off" | "0" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 /// Retrieves a boolean value from the configuration.
+///
+/// # Safety
+/// All pointers must be valid, and `key_c` null-terminated.
 #[no_mangle]
 pub unsafe extern "C" fn via_config_get_boolean(
     config: *const ViaConfig,
@@ -229,13 +1770,14 @@ pub unsafe extern "C" fn via_config_get_boolean(
     let config = &*config;
     let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
 
-    match get_value_by_key(&config.merged_value, key) {
+    match config.lookup(key) {
         Some(val) => {
-            if let Some(b) = val.as_bool() {
+            let b = if config.flags & VIA_CONFIG_FLAG_LENIENT_BOOLEANS != 0 { lenient_bool(val) } else { val.as_bool() };
+            if let Some(b) = b {
                 *out_value = b;
                 ViaConfigStatus::Ok
             } else {
-                ViaConfigStatus::TypeError
+                type_mismatch(key, "boolean", val)
             }
         }
         None => ViaConfigStatus::KeyNotFound,
@@ -243,15 +1785,3428 @@ pub unsafe extern "C" fn via_config_get_boolean(
 }
 
 /// Converts a `ViaConfigStatus` enum to a human-readable string.
+///
+/// The message is returned in the locale set on the calling thread with
+/// `via_config_set_locale`, or in English if no locale has been set.
 #[no_mangle]
 pub extern "C" fn via_config_status_to_string(status: ViaConfigStatus) -> *const c_char {
-    match status {
-        ViaConfigStatus::Ok => b"Ok\0".as_ptr() as *const c_char,
-        ViaConfigStatus::FileNotFound => b"Error: File not found\0".as_ptr() as *const c_char,
-        ViaConfigStatus::ParseError => b"Error: Could not parse YAML file\0".as_ptr() as *const c_char,
-        ViaConfigStatus::KeyNotFound => b"Error: The requested key was not found\0".as_ptr() as *const c_char,
-        ViaConfigStatus::TypeError => b"Error: Value has an unexpected type\0".as_ptr() as *const c_char,
-        ViaConfigStatus::NullArgument => b"Error: A null argument was provided\0".as_ptr() as *const c_char,
-        ViaConfigStatus::InternalError => b"Error: An internal error occurred in the Rust library\0".as_ptr() as *const c_char,
+    messages::status_message(status, messages::current_locale()).as_ptr() as *const c_char
+}
+
+/// Returns the version of the C ABI implemented by the linked library. A
+/// caller compares it with the `VIA_CONFIG_ABI_VERSION` its header defines
+/// to detect a header/binary mismatch.
+#[no_mangle]
+pub extern "C" fn via_config_abi_version() -> u32 {
+    VIA_CONFIG_ABI_VERSION
+}
+
+/// Returns the library's semantic version (e.g. `0.1.0`) as a static,
+/// null-terminated string that must not be freed.
+#[no_mangle]
+pub extern "C" fn via_config_library_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr() as *const c_char
+}
+
+/// Sets the language used by `via_config_status_to_string` on the calling thread.
+///
+/// Accepts tags such as `"en"`, `"en-US"`, `"pt-BR"` or `"pt_BR"`. Unsupported
+/// languages fall back to English.
+///
+/// # Safety
+/// `locale_c` must be a valid, null-terminated C string.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_set_locale(locale_c: *const c_char) -> ViaConfigStatus {
+    if locale_c.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let Ok(tag) = CStr::from_ptr(locale_c).to_str() else { return ViaConfigStatus::InternalError; };
+    messages::set_locale(messages::Locale::from_tag(tag));
+    ViaConfigStatus::Ok
+}
+
+/// Merges `overlay` into `base` in place, with values from `overlay` winning.
+/// Uses the same rules as the layered merge performed by `via_config_load`.
+///
+/// # Safety
+/// Both pointers must be valid handles. Passing the same handle twice is a no-op.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_merge_into(
+    base: *mut ViaConfig,
+    overlay: *const ViaConfig,
+) -> ViaConfigStatus {
+    if base.is_null() || overlay.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    if std::ptr::eq(base, overlay) {
+        // Merging a tree into itself is a no-op, and would alias `&mut`/`&`.
+        return ViaConfigStatus::Ok;
+    }
+    let base = &mut *base;
+    let overlay = &*overlay;
+    if let Err(status) = base.ensure_mutable() {
+        return status;
+    }
+
+    merge(&mut base.merged_value, &overlay.merged_value);
+    for_each_leaf(&overlay.merged_value, "", &mut |key, _| {
+        base.provenance.insert(key.to_string(), SOURCE_RUNTIME);
+        base.runtime_origins.insert(key.to_string(), ORIGIN_MERGE);
+    });
+    ViaConfigStatus::Ok
+}
+
+/// Frees a string that was allocated by this library and handed to the caller
+/// (e.g. by `via_config_export_schema`).
+///
+/// # Safety
+/// The `s` pointer must have been returned by this library as an owned string
+/// and must not have been freed yet. Passing a null pointer is safe.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_free_string(s: *mut c_char) {
+    if !s.is_null() {
+        let _ = CString::from_raw(s);
+    }
+}
+
+/// Exports a JSON Schema describing the expected configuration structure.
+///
+/// The schema is derived from the `MergedConfig` models, so field names and
+/// required-ness always match what the Rust types accept.
+///
+/// # Safety
+/// `out_json` must be a valid pointer. On success it receives an owned string
+/// that must be freed with `via_config_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_export_schema(out_json: *mut *mut c_char) -> ViaConfigStatus {
+    if out_json.is_null() {
+        return ViaConfigStatus::NullArgument;
     }
+    let schema = schemars::schema_for!(models::MergedConfig);
+    let Ok(json) = serde_json::to_string_pretty(&schema) else { return ViaConfigStatus::InternalError; };
+    let Some(json_c) = into_owned_c_string(json) else { return ViaConfigStatus::InternalError; };
+    *out_json = json_c;
+    ViaConfigStatus::Ok
+}
+
+/// Updates a single scalar key in a YAML file on disk without disturbing its
+/// comments or formatting, and applies the same value to the loaded config.
+///
+/// `value_c` is raw YAML scalar text (e.g. `4096`, `true`, `"assets/model.gguf"`).
+/// Only the value portion of the key's line is rewritten; every other byte of
+/// the file stays identical. The file is replaced atomically, so a crash
+/// while saving never leaves it truncated.
+///
+/// # Safety
+/// All pointers must be valid, and all strings null-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_save_file(
+    config: *mut ViaConfig,
+    path_c: *const c_char,
+    key_c: *const c_char,
+    value_c: *const c_char,
+) -> ViaConfigStatus {
+    if config.is_null() || path_c.is_null() || key_c.is_null() || value_c.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &mut *config;
+    let Ok(path) = CStr::from_ptr(path_c).to_str() else { return ViaConfigStatus::InternalError; };
+    if let Err(status) = config.ensure_mutable() {
+        return status;
+    }
+    let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
+    let Ok(value_text) = CStr::from_ptr(value_c).to_str() else { return ViaConfigStatus::InternalError; };
+    if let Err(message) = limits::check_key(key) {
+        return diagnostics::report(ViaConfigStatus::InternalError, message);
+    }
+
+    if let Err(message) = jail::check(Path::new(path)) {
+        return diagnostics::report(ViaConfigStatus::FileNotFound, message);
+    }
+    let document = match fs::read_to_string(path) {
+        Ok(document) => document,
+        Err(e) => return diagnostics::report(ViaConfigStatus::FileNotFound, format!("Failed to read file {:?}: {}", path, e)),
+    };
+    let edited = match edit::replace_scalar(&document, key, value_text) {
+        Ok(edited) => edited,
+        Err(edit::EditError::KeyNotFound) => return ViaConfigStatus::KeyNotFound,
+        Err(edit::EditError::NotAScalar) => return ViaConfigStatus::TypeError,
+    };
+    if let Err(e) = write_file_atomically(Path::new(path), edited.as_bytes()) {
+        return diagnostics::report(ViaConfigStatus::InternalError, format!("Failed to write file {:?}: {}", path, e));
+    }
+
+    // `replace_scalar` has already verified that the text parses as a scalar.
+    let Ok(new_val) = serde_yaml::from_str(value_text) else { return ViaConfigStatus::InternalError; };
+    set_value_by_key(&mut config.merged_value, key, new_val);
+    config.mark_runtime(key);
+    ViaConfigStatus::Ok
+}
+
+/// Returns the files a handle was loaded from, highest precedence first, so
+/// that the first file defining a key is the one its value came from.
+/// Handles not loaded from files (e.g. from strings or bytes) have none.
+fn files_by_precedence(config: &ViaConfig) -> Vec<&Path> {
+    let (base, profiles) = config.source_paths.split_at(FIRST_PROFILE_LAYER.min(config.source_paths.len()));
+    profiles
+        .iter()
+        .rev()
+        .chain(config.parent_profiles.iter().rev())
+        .chain(base.iter().rev())
+        .map(PathBuf::as_path)
+        .collect()
+}
+
+/// Reads the comment that documents `key_c` in the YAML files the handle was
+/// loaded from: the comment lines directly above the key followed by its
+/// trailing comment, without the `#` markers. Files are searched from the
+/// highest precedence down, and the first comment found is returned, so an
+/// undocumented override in a profile still shows the description from the
+/// system file. `out_text` is set to null if the key has no comment.
+///
+/// The files are re-read on each call, since the parsed tree does not keep
+/// comments.
+///
+/// # Safety
+/// All pointers must be valid, and `key_c` null-terminated. A non-null
+/// `out_text` must be freed with `via_config_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_get_description(
+    config: *const ViaConfig,
+    key_c: *const c_char,
+    out_text: *mut *mut c_char,
+) -> ViaConfigStatus {
+    if config.is_null() || key_c.is_null() || out_text.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
+    if get_value_by_key(&config.merged_value, key).is_none() {
+        return ViaConfigStatus::KeyNotFound;
+    }
+
+    for path in files_by_precedence(config) {
+        if jail::check(path).is_err() {
+            continue;
+        }
+        let Ok(document) = fs::read_to_string(path) else { continue };
+        if let Some(description) = edit::find_description(&lint::normalize_text(&document), key) {
+            let Some(text_c) = into_owned_c_string(description) else { return ViaConfigStatus::InternalError; };
+            *out_text = text_c;
+            return ViaConfigStatus::Ok;
+        }
+    }
+    *out_text = std::ptr::null_mut();
+    ViaConfigStatus::Ok
+}
+
+/// Retrieves the text form of any scalar, whether authored as a string, number
+/// or boolean (e.g. `4096`, `true`, `0.75`), for consumers that only
+/// interpolate values into text. Strings are returned verbatim and null as
+/// `null`. Mappings and sequences fail with `TypeError`.
+///
+/// # Safety
+/// All pointers must be valid, and `key_c` null-terminated. On success
+/// `out_value` receives an owned string that must be freed with
+/// `via_config_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_get_string_or_number_as_string(
+    config: *const ViaConfig,
+    key_c: *const c_char,
+    out_value: *mut *mut c_char,
+) -> ViaConfigStatus {
+    if config.is_null() || key_c.is_null() || out_value.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
+
+    let Some(mut val) = config.lookup(key) else { return ViaConfigStatus::KeyNotFound; };
+    while let Value::Tagged(tagged) = val {
+        val = &tagged.value;
+    }
+    if matches!(val, Value::Mapping(_) | Value::Sequence(_)) {
+        return type_mismatch(key, "scalar", val);
+    }
+    let Some(value_c) = into_owned_c_string(value_to_text(val)) else { return ViaConfigStatus::InternalError; };
+    *out_value = value_c;
+    ViaConfigStatus::Ok
+}
+
+/// Retrieves a string value, preferring an environment variable over the
+/// configuration. The precedence is: environment variable, then config value.
+///
+/// # Safety
+/// All pointers must be valid, and all strings null-terminated. On success
+/// `out_value` receives an owned string that must be freed with
+/// `via_config_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_get_string_or_env(
+    config: *const ViaConfig,
+    key_c: *const c_char,
+    env_name_c: *const c_char,
+    out_value: *mut *mut c_char,
+) -> ViaConfigStatus {
+    if config.is_null() || key_c.is_null() || env_name_c.is_null() || out_value.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
+    let Ok(env_name) = CStr::from_ptr(env_name_c).to_str() else { return ViaConfigStatus::InternalError; };
+
+    let value = match std::env::var(env_name) {
+        Ok(env_value) => env_value,
+        Err(std::env::VarError::NotUnicode(_)) => {
+            return diagnostics::report(
+                ViaConfigStatus::TypeError,
+                format!("Environment variable '{}' overriding key '{}' is not valid UTF-8", env_name, key),
+            )
+        }
+        Err(std::env::VarError::NotPresent) => match config.lookup(key) {
+            Some(val) => match val.as_str() {
+                Some(s) => s.to_string(),
+                None => return type_mismatch(key, "string", val),
+            },
+            None => return ViaConfigStatus::KeyNotFound,
+        },
+    };
+    let Some(value_c) = into_owned_c_string(value) else { return ViaConfigStatus::InternalError; };
+    *out_value = value_c;
+    ViaConfigStatus::Ok
+}
+
+/// Spells a dot-key as an environment variable name: sections are upper-cased
+/// and joined with `__`, any other character that is not alphanumeric becomes
+/// `_`, and a non-empty `prefix` is prepended with `_`. For example,
+/// `camera.device-id` with prefix `TRACKIE` becomes `TRACKIE_CAMERA__DEVICE_ID`.
+fn env_var_name(prefix: &str, key: &str) -> String {
+    let sections: Vec<String> = key_segments(key)
+        .iter()
+        .map(|section| {
+            section.chars().map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' }).collect()
+        })
+        .collect();
+    let name = sections.join("__");
+    if prefix.is_empty() {
+        name
+    } else {
+        format!("{}_{}", prefix, name)
+    }
+}
+
+/// Renders a value on a single line: scalars as plain text and collections as
+/// compact JSON (or YAML if they have no JSON equivalent).
+fn value_to_inline_text(val: &Value) -> String {
+    match val {
+        Value::Sequence(_) | Value::Mapping(_) => serde_json::to_string(val).unwrap_or_else(|_| value_to_yaml(val)),
+        Value::Tagged(tagged) => value_to_inline_text(&tagged.value),
+        _ => value_to_text(val),
+    }
+}
+
+/// Renders a leaf as the value of an environment variable: scalars as plain
+/// text, null as an empty string, and sequences as compact JSON.
+fn env_var_value(val: &Value) -> String {
+    match val {
+        Value::Null => String::new(),
+        _ => value_to_inline_text(val),
+    }
+}
+
+/// Flattens the merged configuration into environment variables, one per
+/// leaf, named as `PREFIX_SECTION__KEY`, so that it can be handed to a child
+/// process. `out_names` and `out_values` receive parallel arrays of `out_len`
+/// entries, in document/merge order. Secret keys are exported unredacted,
+/// since the child process needs their values.
+///
+/// # Safety
+/// All pointers must be valid, and `prefix_c` null-terminated. On success
+/// `out_names` and `out_values` must each be freed with
+/// `via_config_free_string_array`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_export_env(
+    config: *const ViaConfig,
+    prefix_c: *const c_char,
+    out_names: *mut *mut *mut c_char,
+    out_values: *mut *mut *mut c_char,
+    out_len: *mut usize,
+) -> ViaConfigStatus {
+    if config.is_null() || prefix_c.is_null() || out_names.is_null() || out_values.is_null() || out_len.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let Ok(prefix) = CStr::from_ptr(prefix_c).to_str() else { return ViaConfigStatus::InternalError; };
+
+    let mut names = Vec::new();
+    let mut values = Vec::new();
+    for_each_leaf(&config.merged_value, "", &mut |key, val| {
+        names.push(env_var_name(prefix, key));
+        values.push(env_var_value(val));
+    });
+
+    let Some((names_array, len)) = into_owned_c_string_array(names) else { return ViaConfigStatus::InternalError; };
+    let Some((values_array, _)) = into_owned_c_string_array(values) else {
+        via_config_free_string_array(names_array, len);
+        return ViaConfigStatus::InternalError;
+    };
+    *out_names = names_array;
+    *out_values = values_array;
+    *out_len = len;
+    ViaConfigStatus::Ok
+}
+
+/// Registers a custom check for the value at `key_c`. The check is run, along
+/// with all other registered checks, by `via_config_run_validators`.
+///
+/// # Safety
+/// All pointers must be valid, and `key_c` null-terminated. The validator is
+/// called with a string that is only valid for the duration of the call.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_register_validator(
+    config: *mut ViaConfig,
+    key_c: *const c_char,
+    validator: Option<ViaConfigValidator>,
+) -> ViaConfigStatus {
+    if config.is_null() || key_c.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let Some(validator) = validator else { return ViaConfigStatus::NullArgument; };
+    let config = &mut *config;
+    let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
+
+    config.validators.push((key.to_string(), validator));
+    ViaConfigStatus::Ok
+}
+
+/// Runs every registered validator against the current configuration.
+///
+/// Returns `ValidationFailed` if any check rejects its value or its key is
+/// missing; `out_failures` then receives a newline-separated report, in which
+/// the values of secret keys are shown as `***`. On `Ok`, `out_failures` is
+/// set to null.
+///
+/// # Safety
+/// All pointers must be valid. A non-null report must be freed with
+/// `via_config_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_run_validators(
+    config: *const ViaConfig,
+    out_failures: *mut *mut c_char,
+) -> ViaConfigStatus {
+    if config.is_null() || out_failures.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+
+    let mut failures = Vec::new();
+    for (key, validator) in &config.validators {
+        let Some(val) = get_value_by_key(&config.merged_value, key) else {
+            failures.push(format!("{}: key not found", key));
+            continue;
+        };
+        let text = value_to_text(val);
+        let Ok(text_c) = CString::new(text.as_str()) else {
+            failures.push(format!("{}: value contains a null byte", key));
+            continue;
+        };
+        if !validator(text_c.as_ptr()) {
+            failures.push(format!("{}: value '{}' was rejected by its validator", key, shown_value(config, key, val)));
+        }
+    }
+
+    if failures.is_empty() {
+        *out_failures = std::ptr::null_mut();
+        return ViaConfigStatus::Ok;
+    }
+    let Some(report_c) = into_owned_c_string(failures.join("\n")) else { return ViaConfigStatus::InternalError; };
+    *out_failures = report_c;
+    ViaConfigStatus::ValidationFailed
+}
+
+/// Registers a rule that `require_key_c` must be set whenever the value at
+/// `if_key_c` is `if_equals_c`, compared as plain text (so `true` matches
+/// the boolean). Rules are checked by `via_config_check_dependencies`.
+///
+/// # Safety
+/// All pointers must be valid, null-terminated C strings.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_add_dependency_rule(
+    config: *mut ViaConfig,
+    if_key_c: *const c_char,
+    if_equals_c: *const c_char,
+    require_key_c: *const c_char,
+) -> ViaConfigStatus {
+    add_dependency(config, if_key_c, if_equals_c, require_key_c, None)
+}
+
+/// Registers a rule like `via_config_add_dependency_rule` whose required key
+/// must also be a number within `[min, max]`. Either bound is excluded from
+/// the range when its `*_exclusive` flag is set, so `window-size > 0` is
+/// `min = 0, min_exclusive = true, max = INFINITY`; an infinite bound leaves
+/// that side open.
+///
+/// # Safety
+/// All pointers must be valid, null-terminated C strings. Returns
+/// `InternalError` if a bound is NaN or `min > max`.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn via_config_add_dependency_range(
+    config: *mut ViaConfig,
+    if_key_c: *const c_char,
+    if_equals_c: *const c_char,
+    require_key_c: *const c_char,
+    min: f64,
+    min_exclusive: bool,
+    max: f64,
+    max_exclusive: bool,
+) -> ViaConfigStatus {
+    if min.is_nan() || max.is_nan() || min > max {
+        return diagnostics::report(ViaConfigStatus::InternalError, format!("Invalid dependency range [{}, {}]", min, max));
+    }
+    let range = DependencyRange { min, min_exclusive, max, max_exclusive };
+    add_dependency(config, if_key_c, if_equals_c, require_key_c, Some(range))
+}
+
+unsafe fn add_dependency(
+    config: *mut ViaConfig,
+    if_key_c: *const c_char,
+    if_equals_c: *const c_char,
+    require_key_c: *const c_char,
+    range: Option<DependencyRange>,
+) -> ViaConfigStatus {
+    if config.is_null() || if_key_c.is_null() || if_equals_c.is_null() || require_key_c.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &mut *config;
+    let (Ok(if_key), Ok(if_equals), Ok(require_key)) =
+        (CStr::from_ptr(if_key_c).to_str(), CStr::from_ptr(if_equals_c).to_str(), CStr::from_ptr(require_key_c).to_str())
+    else {
+        return ViaConfigStatus::InternalError;
+    };
+    if if_key.is_empty() || require_key.is_empty() {
+        return ViaConfigStatus::KeyNotFound;
+    }
+
+    config.dependency_rules.push(DependencyRule {
+        if_key: if_key.to_string(),
+        if_equals: if_equals.to_string(),
+        require_key: require_key.to_string(),
+        range,
+    });
+    ViaConfigStatus::Ok
+}
+
+/// Checks every rule registered with `via_config_add_dependency_rule` or
+/// `via_config_add_dependency_range` against the current configuration. A
+/// required key counts as set unless it is missing, null, or an empty string,
+/// sequence or mapping; a rule with a range also requires a number within it.
+///
+/// Returns `ValidationFailed` if a rule applies and its required key is not
+/// set; `out_report` then receives one line per broken rule. On `Ok`,
+/// `out_report` is set to null.
+///
+/// # Safety
+/// All pointers must be valid. A non-null report must be freed with
+/// `via_config_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_check_dependencies(config: *const ViaConfig, out_report: *mut *mut c_char) -> ViaConfigStatus {
+    if config.is_null() || out_report.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+
+    let mut failures = Vec::new();
+    for rule in &config.dependency_rules {
+        if get_value_by_key(&config.merged_value, &rule.if_key).is_none_or(|val| value_to_text(val) != rule.if_equals) {
+            continue;
+        }
+        let problem = match (get_value_by_key(&config.merged_value, &rule.require_key), &rule.range) {
+            (None | Some(Value::Null), _) => "it is not set".to_string(),
+            (Some(Value::String(s)), _) if s.is_empty() => "it is empty".to_string(),
+            (Some(Value::Sequence(seq)), _) if seq.is_empty() => "it is empty".to_string(),
+            (Some(Value::Mapping(map)), _) if map.is_empty() => "it is empty".to_string(),
+            (Some(_), None) => continue,
+            (Some(Value::Number(n)), Some(range)) if range.contains(number_to_f64(n)) => continue,
+            (Some(Value::Number(n)), Some(range)) => format!("it is {}, which is not {}", n, range.describe()),
+            (Some(val), Some(range)) => {
+                format!("it is a {}, not a number {}", ViaConfigValueType::of(val).name(), range.describe())
+            }
+        };
+        failures.push(format!("{}: required because {} is {}, but {}", rule.require_key, rule.if_key, rule.if_equals, problem));
+    }
+
+    if failures.is_empty() {
+        *out_report = std::ptr::null_mut();
+        return ViaConfigStatus::Ok;
+    }
+    let Some(report_c) = into_owned_c_string(failures.join("\n")) else { return ViaConfigStatus::InternalError; };
+    *out_report = report_c;
+    ViaConfigStatus::ValidationFailed
+}
+
+/// Frees an array of strings returned by this library, along with every
+/// string in it.
+///
+/// # Safety
+/// `array` and `len` must be exactly as returned by this library, and must not
+/// have been freed yet. Passing a null pointer is safe.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_free_string_array(array: *mut *mut c_char, len: usize) {
+    if array.is_null() {
+        return;
+    }
+    let array = Box::from_raw(std::ptr::slice_from_raw_parts_mut(array, len));
+    for s in array.iter() {
+        via_config_free_string(*s);
+    }
+}
+
+/// Lists the keys of the mapping at `key_c` (or of the root if `key_c` is
+/// empty). Keys are returned in document/merge order, or sorted
+/// lexicographically if `sorted` is true. Sorting does not modify the tree.
+///
+/// # Safety
+/// All pointers must be valid. On success the array must be freed with
+/// `via_config_free_string_array`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_get_keys(
+    config: *const ViaConfig,
+    key_c: *const c_char,
+    sorted: bool,
+    out_keys: *mut *mut *mut c_char,
+    out_len: *mut usize,
+) -> ViaConfigStatus {
+    if config.is_null() || key_c.is_null() || out_keys.is_null() || out_len.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
+
+    let Some(val) = get_node(&config.merged_value, key) else { return ViaConfigStatus::KeyNotFound; };
+    let Some(map) = val.as_mapping() else { return type_mismatch(key, "mapping", val) };
+
+    let mut keys: Vec<String> = map.keys().map(value_to_text).collect();
+    if sorted {
+        keys.sort();
+    }
+    let Some((array, len)) = into_owned_c_string_array(keys) else { return ViaConfigStatus::InternalError; };
+    *out_keys = array;
+    *out_len = len;
+    ViaConfigStatus::Ok
+}
+
+/// Reports whether the value at `key_c` is an explicit null (e.g. `key:` with
+/// no value), as opposed to a missing key or a value of another type.
+///
+/// # Safety
+/// All pointers must be valid, and `key_c` null-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_is_null(
+    config: *const ViaConfig,
+    key_c: *const c_char,
+    out_is_null: *mut bool,
+) -> ViaConfigStatus {
+    if config.is_null() || key_c.is_null() || out_is_null.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
+
+    match get_value_by_key(&config.merged_value, key) {
+        Some(val) => {
+            *out_is_null = val.is_null();
+            ViaConfigStatus::Ok
+        }
+        None => ViaConfigStatus::KeyNotFound,
+    }
+}
+
+/// Reports which source layer supplied the value of a leaf key: 0 for the
+/// system file, 1 for hardware, 2 for the profile, or -1 if the value was
+/// changed after load.
+///
+/// # Safety
+/// All pointers must be valid, and `key_c` null-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_get_source(
+    config: *const ViaConfig,
+    key_c: *const c_char,
+    out_source_index: *mut i32,
+) -> ViaConfigStatus {
+    if config.is_null() || key_c.is_null() || out_source_index.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
+
+    match config.provenance.get(key) {
+        Some(&source) => {
+            *out_source_index = source;
+            ViaConfigStatus::Ok
+        }
+        // The key exists but is a mapping; only leaves have a single source.
+        None if get_value_by_key(&config.merged_value, key).is_some() => ViaConfigStatus::TypeError,
+        None => ViaConfigStatus::KeyNotFound,
+    }
+}
+
+/// Checks that every key in `keys` exists in the configuration.
+///
+/// Returns `ValidationFailed` if any key is missing; `out_missing` then
+/// receives a newline-separated report naming each missing key. On `Ok`,
+/// `out_missing` is set to null.
+///
+/// # Safety
+/// `config` and `out_missing` must be valid, and `keys` must point to `count`
+/// valid, null-terminated C strings. A non-null report must be freed with
+/// `via_config_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_require_keys(
+    config: *const ViaConfig,
+    keys: *const *const c_char,
+    count: usize,
+    out_missing: *mut *mut c_char,
+) -> ViaConfigStatus {
+    if config.is_null() || out_missing.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let keys = match borrow_c_string_array(keys, count) {
+        Ok(keys) => keys,
+        Err(status) => return status,
+    };
+
+    let missing: Vec<String> = keys
+        .into_iter()
+        .filter(|key| get_value_by_key(&config.merged_value, key).is_none())
+        .map(|key| format!("{}: required key is missing", key))
+        .collect();
+
+    if missing.is_empty() {
+        *out_missing = std::ptr::null_mut();
+        return ViaConfigStatus::Ok;
+    }
+    let Some(report_c) = into_owned_c_string(missing.join("\n")) else { return ViaConfigStatus::InternalError; };
+    *out_missing = report_c;
+    ViaConfigStatus::ValidationFailed
+}
+
+/// The C type of a struct field filled by `via_config_fill_struct`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub enum ViaConfigFieldType {
+    Bool = 0,
+    I32 = 1,
+    U32 = 2,
+    U16 = 3,
+    I64 = 4,
+    F32 = 5,
+    F64 = 6,
+    /// An owned `char*` that the caller must free with `via_config_free_string`.
+    String = 7,
+}
+
+/// Describes one field of a C struct: the config key to read, the field's
+/// byte offset within the struct, and its type.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ViaConfigField {
+    pub key: *const c_char,
+    pub offset: usize,
+    pub field_type: ViaConfigFieldType,
+}
+
+/// A value read for one field, held until every field has been read.
+enum FieldValue {
+    Bool(bool),
+    I32(i32),
+    U32(u32),
+    U16(u16),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+    String(String),
+}
+
+impl FieldValue {
+    fn size(&self) -> usize {
+        match self {
+            FieldValue::Bool(_) => std::mem::size_of::<bool>(),
+            FieldValue::I32(_) => std::mem::size_of::<i32>(),
+            FieldValue::U32(_) => std::mem::size_of::<u32>(),
+            FieldValue::U16(_) => std::mem::size_of::<u16>(),
+            FieldValue::I64(_) => std::mem::size_of::<i64>(),
+            FieldValue::F32(_) => std::mem::size_of::<f32>(),
+            FieldValue::F64(_) => std::mem::size_of::<f64>(),
+            FieldValue::String(_) => std::mem::size_of::<*mut c_char>(),
+        }
+    }
+}
+
+/// Reads one field through the matching typed getter.
+unsafe fn read_field(config: *const ViaConfig, field: &ViaConfigField) -> Result<FieldValue, ViaConfigStatus> {
+    macro_rules! get {
+        ($getter:ident, $init:expr, $variant:ident) => {{
+            let mut v = $init;
+            match $getter(config, field.key, &mut v) {
+                ViaConfigStatus::Ok => Ok(FieldValue::$variant(v)),
+                status => Err(status),
+            }
+        }};
+    }
+    match field.field_type {
+        ViaConfigFieldType::Bool => get!(via_config_get_boolean, false, Bool),
+        ViaConfigFieldType::I32 => get!(via_config_get_i32, 0, I32),
+        ViaConfigFieldType::U32 => get!(via_config_get_u32, 0, U32),
+        ViaConfigFieldType::U16 => get!(via_config_get_u16, 0, U16),
+        ViaConfigFieldType::I64 => get!(via_config_get_integer, 0, I64),
+        ViaConfigFieldType::F32 => {
+            let mut v = 0.0;
+            match via_config_get_float(config, field.key, &mut v) {
+                ViaConfigStatus::Ok => Ok(FieldValue::F32(v as f32)),
+                status => Err(status),
+            }
+        }
+        ViaConfigFieldType::F64 => get!(via_config_get_float, 0.0, F64),
+        ViaConfigFieldType::String => {
+            let Ok(key) = CStr::from_ptr(field.key).to_str() else { return Err(ViaConfigStatus::InternalError); };
+            match (*config).lookup(key) {
+                Some(val) => match val.as_str() {
+                    // An interior NUL cannot be represented as a C string.
+                    Some(s) if s.contains('\0') => Err(ViaConfigStatus::InternalError),
+                    Some(s) => Ok(FieldValue::String(s.to_string())),
+                    None => Err(type_mismatch(key, "string", val)),
+                },
+                None => Err(ViaConfigStatus::KeyNotFound),
+            }
+        }
+    }
+}
+
+/// Fills a caller-provided C struct from config keys, as described by a
+/// field layout. Each field is read with the corresponding typed getter.
+///
+/// All fields are read and bounds-checked before anything is written: on any
+/// failure the struct is left untouched and the first failing status is
+/// returned. String fields receive owned strings that the caller must free
+/// with `via_config_free_string`.
+///
+/// # Safety
+/// `layout` must point to `count` valid fields whose keys are null-terminated
+/// C strings. `out_struct` must point to at least `struct_size` writable
+/// bytes, and each field's offset and type must match the caller's struct
+/// definition. Fields need not be aligned.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_fill_struct(
+    config: *const ViaConfig,
+    layout: *const ViaConfigField,
+    count: usize,
+    out_struct: *mut c_void,
+    struct_size: usize,
+) -> ViaConfigStatus {
+    if config.is_null() || out_struct.is_null() || (layout.is_null() && count > 0) {
+        return ViaConfigStatus::NullArgument;
+    }
+    if count == 0 {
+        return ViaConfigStatus::Ok;
+    }
+    let layout = std::slice::from_raw_parts(layout, count);
+
+    // --- 1. Read and bounds-check every field ---
+    let mut values = Vec::with_capacity(count);
+    for field in layout {
+        if field.key.is_null() {
+            return ViaConfigStatus::NullArgument;
+        }
+        let value = match read_field(config, field) {
+            Ok(value) => value,
+            Err(status) => return status,
+        };
+        match field.offset.checked_add(value.size()) {
+            Some(end) if end <= struct_size => values.push((field.offset, value)),
+            _ => return ViaConfigStatus::InternalError,
+        }
+    }
+
+    // --- 2. Write them into the caller's struct ---
+    let base = out_struct as *mut u8;
+    for (offset, value) in values {
+        let dst = base.add(offset);
+        match value {
+            FieldValue::Bool(v) => std::ptr::write_unaligned(dst as *mut bool, v),
+            FieldValue::I32(v) => std::ptr::write_unaligned(dst as *mut i32, v),
+            FieldValue::U32(v) => std::ptr::write_unaligned(dst as *mut u32, v),
+            FieldValue::U16(v) => std::ptr::write_unaligned(dst as *mut u16, v),
+            FieldValue::I64(v) => std::ptr::write_unaligned(dst as *mut i64, v),
+            FieldValue::F32(v) => std::ptr::write_unaligned(dst as *mut f32, v),
+            FieldValue::F64(v) => std::ptr::write_unaligned(dst as *mut f64, v),
+            FieldValue::String(v) => {
+                // NUL bytes were rejected while reading, so this cannot fail.
+                let s_c = into_owned_c_string(v).unwrap_or(std::ptr::null_mut());
+                std::ptr::write_unaligned(dst as *mut *mut c_char, s_c);
+            }
+        }
+    }
+    ViaConfigStatus::Ok
+}
+
+/// Returns the status of the last error reported on the calling thread, or
+/// `Ok` if the most recent load succeeded.
+#[no_mangle]
+pub extern "C" fn via_config_last_error_status() -> ViaConfigStatus {
+    diagnostics::last_status()
+}
+
+/// Returns a description of the last error reported on the calling thread,
+/// or null if there is none.
+///
+/// The returned string is owned by the library and stays valid until the
+/// next call on this thread that reports or clears an error.
+#[no_mangle]
+pub extern "C" fn via_config_last_error_message() -> *const c_char {
+    diagnostics::last_message_ptr()
+}
+
+/// Freezes a configuration so that it can no longer be modified. Every later
+/// call that would change the configuration (such as `via_config_merge_into`
+/// or `via_config_save_file`) fails with `ViaConfigStatus::Frozen`. Freezing
+/// cannot be undone.
+///
+/// # Safety
+/// `config` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_freeze(config: *mut ViaConfig) -> ViaConfigStatus {
+    if config.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    (*config).frozen = true;
+    ViaConfigStatus::Ok
+}
+
+/// Interprets a value as a ratio in [0, 1]: either a number in that range or
+/// a percentage string such as `"75%"`.
+fn value_as_ratio(val: &Value) -> Option<f64> {
+    let ratio = match val {
+        Value::Number(n) => n.as_f64()?,
+        Value::String(s) => s.trim().strip_suffix('%')?.trim_end().parse::<f64>().ok()? / 100.0,
+        _ => return None,
+    };
+    (0.0..=1.0).contains(&ratio).then_some(ratio)
+}
+
+/// Retrieves a ratio normalized to [0, 1]. The value may be written either as
+/// a number in [0, 1] (e.g. `0.75`) or as a percentage string (e.g. `"75%"`).
+///
+/// # Safety
+/// All pointers must be valid, and `key_c` null-terminated. Returns
+/// `TypeError` for unparseable or out-of-range values.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_get_ratio(
+    config: *const ViaConfig,
+    key_c: *const c_char,
+    out_value: *mut f64,
+) -> ViaConfigStatus {
+    if config.is_null() || key_c.is_null() || out_value.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
+
+    match config.lookup(key) {
+        Some(val) => {
+            if let Some(ratio) = value_as_ratio(val) {
+                *out_value = ratio;
+                ViaConfigStatus::Ok
+            } else {
+                type_mismatch(key, "ratio in [0, 1] or percentage", val)
+            }
+        }
+        None => ViaConfigStatus::KeyNotFound,
+    }
+}
+
+/// Serializes the configuration into a compact binary buffer that can be
+/// passed to another process and restored with `via_config_from_bytes`.
+///
+/// # Safety
+/// All pointers must be valid. On success the buffer must be freed with
+/// `via_config_free_bytes`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_to_bytes(
+    config: *const ViaConfig,
+    out_buf: *mut *mut u8,
+    out_len: *mut usize,
+) -> ViaConfigStatus {
+    if config.is_null() || out_buf.is_null() || out_len.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+
+    let Some(bytes) = binary::encode(&config.merged_value, config.flags, &config.provenance) else {
+        return ViaConfigStatus::InternalError;
+    };
+    let bytes = bytes.into_boxed_slice();
+    *out_len = bytes.len();
+    *out_buf = Box::into_raw(bytes) as *mut u8;
+    ViaConfigStatus::Ok
+}
+
+/// Restores a configuration from a buffer produced by `via_config_to_bytes`.
+/// Registered validators are not part of the buffer and must be registered
+/// again. Returns null if the buffer is not a valid encoding.
+///
+/// # Safety
+/// `buf` must point to `len` readable bytes. The returned pointer must be
+/// freed with `via_config_free`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_from_bytes(buf: *const u8, len: usize) -> *mut ViaConfig {
+    diagnostics::clear();
+    if buf.is_null() {
+        diagnostics::report(ViaConfigStatus::NullArgument, "The buffer argument is null".to_string());
+        return std::ptr::null_mut();
+    }
+    let decoded = match binary::decode(std::slice::from_raw_parts(buf, len), limits::max_depth()) {
+        Ok(decoded) => decoded,
+        Err(binary::DecodeError::Invalid) => {
+            diagnostics::report(ViaConfigStatus::ParseError, "The buffer is not a valid binary configuration".to_string());
+            return std::ptr::null_mut();
+        }
+        Err(binary::DecodeError::TooDeep) => {
+            diagnostics::report(
+                ViaConfigStatus::InternalError,
+                format!("The buffer holds a tree nested more than the maximum of {} levels deep", limits::max_depth()),
+            );
+            return std::ptr::null_mut();
+        }
+    };
+
+    let mut config = ViaConfig::new(decoded.tree, decoded.flags);
+    config.provenance = decoded.provenance;
+    Box::into_raw(Box::new(config))
+}
+
+/// Frees a buffer returned by `via_config_to_bytes`.
+///
+/// # Safety
+/// `buf` and `len` must be exactly as returned by `via_config_to_bytes`, and
+/// the buffer must not have been freed yet. Passing a null pointer is safe.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_free_bytes(buf: *mut u8, len: usize) {
+    if !buf.is_null() {
+        drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(buf, len)));
+    }
+}
+
+/// Returns true if the dot-key `key` matches a prefix pattern. A pattern
+/// ending in `*` matches any key starting with the text before it; otherwise
+/// the pattern matches the key itself and every key nested below it. An
+/// empty pattern matches everything.
+fn key_matches_prefix(key: &str, pattern: &str) -> bool {
+    if let Some(literal) = pattern.strip_suffix('*') {
+        return key.starts_with(literal);
+    }
+    pattern.is_empty()
+        || key.strip_prefix(pattern).is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
+
+/// Retrieves every leaf key under a prefix together with its value serialized
+/// as YAML, as two parallel arrays.
+///
+/// `hardware` (or `hardware.*`) matches every key nested under `hardware`;
+/// a trailing `*` after other text, as in `hardware.cam*`, matches by plain
+/// string prefix. Values of secret keys are returned as they are.
+///
+/// # Safety
+/// All pointers must be valid, and `prefix_c` null-terminated. On success
+/// both arrays must be freed with `via_config_free_string_array`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_query_prefix(
+    config: *const ViaConfig,
+    prefix_c: *const c_char,
+    out_keys: *mut *mut *mut c_char,
+    out_values_yaml: *mut *mut *mut c_char,
+    out_len: *mut usize,
+) -> ViaConfigStatus {
+    if config.is_null() || prefix_c.is_null() || out_keys.is_null() || out_values_yaml.is_null() || out_len.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let Ok(prefix) = CStr::from_ptr(prefix_c).to_str() else { return ViaConfigStatus::InternalError; };
+    let pattern = prefix.strip_suffix(".*").unwrap_or(prefix);
+
+    let mut keys = Vec::new();
+    let mut values = Vec::new();
+    for_each_leaf(&config.merged_value, "", &mut |key, val| {
+        if key_matches_prefix(key, pattern) {
+            keys.push(key.to_string());
+            values.push(value_to_yaml(val));
+        }
+    });
+
+    let Some((keys_c, len)) = into_owned_c_string_array(keys) else { return ViaConfigStatus::InternalError; };
+    let Some((values_c, _)) = into_owned_c_string_array(values) else {
+        via_config_free_string_array(keys_c, len);
+        return ViaConfigStatus::InternalError;
+    };
+    *out_keys = keys_c;
+    *out_values_yaml = values_c;
+    *out_len = len;
+    ViaConfigStatus::Ok
+}
+
+/// Checks that the thread counts under `threads` (perception, reasoning and
+/// audio), as set by the system file, do not add up to more than the number
+/// of logical CPUs available to the process.
+///
+/// Returns `ValidationFailed` if the budget is exceeded; `out_warning` then
+/// receives a message naming each count and the CPU total. On `Ok`,
+/// `out_warning` is set to null.
+///
+/// # Safety
+/// All pointers must be valid. A non-null warning must be freed with
+/// `via_config_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_check_thread_budget(
+    config: *const ViaConfig,
+    out_warning: *mut *mut c_char,
+) -> ViaConfigStatus {
+    if config.is_null() || out_warning.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+
+    let mut counts = Vec::new();
+    for subsystem in ["perception", "reasoning", "audio"] {
+        let key = format!("threads.{}", subsystem);
+        match config.lookup(&key) {
+            Some(val) => match val.as_u64() {
+                Some(count) => counts.push((subsystem, count)),
+                None => return type_mismatch(&key, "non-negative integer", val),
+            },
+            None => return diagnostics::report(ViaConfigStatus::KeyNotFound, format!("Key '{}' not found", key)),
+        }
+    }
+
+    let total: u64 = counts.iter().map(|(_, count)| count).sum();
+    let cpus = std::thread::available_parallelism().map_or(1, |n| n.get()) as u64;
+    if total <= cpus {
+        *out_warning = std::ptr::null_mut();
+        return ViaConfigStatus::Ok;
+    }
+
+    let breakdown: Vec<String> = counts.iter().map(|(subsystem, count)| format!("{}={}", subsystem, count)).collect();
+    let warning = format!(
+        "Thread budget exceeded: {} threads configured ({}) but only {} logical CPUs are available",
+        total,
+        breakdown.join(", "),
+        cpus
+    );
+    let Some(warning_c) = into_owned_c_string(warning) else { return ViaConfigStatus::InternalError; };
+    *out_warning = warning_c;
+    ViaConfigStatus::ValidationFailed
+}
+
+/// The relative difference between a configured aspect ratio and an accepted
+/// one that `via_config_check_aspect_ratio` still considers a match. It
+/// absorbs rounding in common resolutions, e.g. 854x480 for 16:9.
+const ASPECT_RATIO_TOLERANCE: f64 = 0.01;
+
+/// Parses an aspect ratio written as `W:H` (e.g. `16:9`) or as a decimal
+/// number (e.g. `1.85`).
+fn parse_aspect_ratio(text: &str) -> Option<f64> {
+    let ratio = match text.split_once(':') {
+        Some((w, h)) => w.trim().parse::<f64>().ok()? / h.trim().parse::<f64>().ok()?,
+        None => text.trim().parse().ok()?,
+    };
+    (ratio.is_finite() && ratio > 0.0).then_some(ratio)
+}
+
+/// Checks that the aspect ratio of the width and height at `width_key_c` and
+/// `height_key_c` (e.g. `hardware.camera.resolution.width`/`height`) is one of
+/// `ratios`, written as `W:H` or as a decimal number, within a relative
+/// tolerance of 1%. `out_ok` receives the result.
+///
+/// Returns `TypeError` if either dimension is not a positive number, and
+/// `ParseError` if one of `ratios` is malformed, with details in
+/// `via_config_last_error_message`.
+///
+/// # Safety
+/// All pointers must be valid and all strings null-terminated; `ratios` must
+/// point to `count` strings.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_check_aspect_ratio(
+    config: *const ViaConfig,
+    width_key_c: *const c_char,
+    height_key_c: *const c_char,
+    ratios: *const *const c_char,
+    count: usize,
+    out_ok: *mut bool,
+) -> ViaConfigStatus {
+    if config.is_null() || width_key_c.is_null() || height_key_c.is_null() || out_ok.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let Ok(width_key) = CStr::from_ptr(width_key_c).to_str() else { return ViaConfigStatus::InternalError; };
+    let Ok(height_key) = CStr::from_ptr(height_key_c).to_str() else { return ViaConfigStatus::InternalError; };
+    let ratios = match borrow_c_string_array(ratios, count) {
+        Ok(ratios) => ratios,
+        Err(status) => return status,
+    };
+
+    let mut dimensions = [0.0; 2];
+    for (dimension, key) in dimensions.iter_mut().zip([width_key, height_key]) {
+        match config.lookup_numeric(key).as_deref() {
+            Some(Value::Number(n)) if number_to_f64(n) > 0.0 => *dimension = number_to_f64(n),
+            Some(_) => {
+                return diagnostics::report(ViaConfigStatus::TypeError, format!("Key '{}' is not a positive number", key))
+            }
+            None => return ViaConfigStatus::KeyNotFound,
+        }
+    }
+    let actual = dimensions[0] / dimensions[1];
+
+    let mut ok = false;
+    for ratio in ratios {
+        let Some(expected) = parse_aspect_ratio(ratio) else {
+            return diagnostics::report(ViaConfigStatus::ParseError, format!("Invalid aspect ratio {:?}", ratio));
+        };
+        ok |= ((actual - expected) / expected).abs() <= ASPECT_RATIO_TOLERANCE;
+    }
+    *out_ok = ok;
+    ViaConfigStatus::Ok
+}
+
+/// Shared implementation of the setters: validates the arguments and sets
+/// (or stages) the value produced by `make_value`.
+unsafe fn set_with(config: *mut ViaConfig, key_c: *const c_char, make_value: impl FnOnce() -> Value) -> ViaConfigStatus {
+    if config.is_null() || key_c.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &mut *config;
+    let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
+    if key.is_empty() || key_segments(key).iter().any(|segment| segment.is_empty()) {
+        return ViaConfigStatus::KeyNotFound;
+    }
+
+    match config.set(key, make_value()) {
+        Ok(()) => ViaConfigStatus::Ok,
+        Err(status) => status,
+    }
+}
+
+/// Sets a string value, creating intermediate mappings as needed. Inside an
+/// update opened with `via_config_begin_update`, the change is staged until
+/// `via_config_commit_update`.
+///
+/// # Safety
+/// All pointers must be valid, and all strings null-terminated. Strings
+/// previously returned by `via_config_get_string` for this key become invalid.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_set_string(
+    config: *mut ViaConfig,
+    key_c: *const c_char,
+    value_c: *const c_char,
+) -> ViaConfigStatus {
+    if value_c.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let Ok(value) = CStr::from_ptr(value_c).to_str() else { return ViaConfigStatus::InternalError; };
+    set_with(config, key_c, || Value::String(value.to_string()))
+}
+
+/// Sets an integer value. See `via_config_set_string`.
+///
+/// # Safety
+/// All pointers must be valid, and `key_c` null-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_set_integer(config: *mut ViaConfig, key_c: *const c_char, value: i64) -> ViaConfigStatus {
+    set_with(config, key_c, || Value::Number(value.into()))
+}
+
+/// Sets a floating-point value. See `via_config_set_string`.
+///
+/// # Safety
+/// All pointers must be valid, and `key_c` null-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_set_float(config: *mut ViaConfig, key_c: *const c_char, value: f64) -> ViaConfigStatus {
+    set_with(config, key_c, || Value::Number(value.into()))
+}
+
+/// Sets a boolean value. See `via_config_set_string`.
+///
+/// # Safety
+/// All pointers must be valid, and `key_c` null-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_set_boolean(config: *mut ViaConfig, key_c: *const c_char, value: bool) -> ViaConfigStatus {
+    set_with(config, key_c, || Value::Bool(value))
+}
+
+/// Removes a key and the subtree below it, so that modules reading it fall
+/// back to their built-in defaults. Parent mappings left empty by the removal
+/// are removed as well. Inside an update opened with
+/// `via_config_begin_update`, the removal is staged until
+/// `via_config_commit_update`.
+///
+/// # Safety
+/// All pointers must be valid, and `key_c` null-terminated. Strings
+/// previously returned by `via_config_get_string` for this key become invalid.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_remove_key(config: *mut ViaConfig, key_c: *const c_char) -> ViaConfigStatus {
+    if config.is_null() || key_c.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &mut *config;
+    let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
+    if key.is_empty() {
+        return ViaConfigStatus::KeyNotFound;
+    }
+
+    match config.remove(key) {
+        Ok(()) => ViaConfigStatus::Ok,
+        Err(status) => status,
+    }
+}
+
+/// Opens a multi-key update. Until `via_config_commit_update` or
+/// `via_config_abort_update` is called, the `via_config_set_*` functions and
+/// `via_config_remove_key` only stage their changes and readers keep seeing the previous values.
+///
+/// A handle is meant to be used from one thread at a time and has no internal
+/// lock, so the update is not atomic with respect to other threads: callers
+/// sharing a handle must serialize access themselves, including around the
+/// commit. Within that discipline the commit applies every staged change in
+/// one step, so no reader observes a partially applied update. A removal is
+/// checked against the configuration with the earlier staged changes applied.
+///
+/// # Safety
+/// `config` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_begin_update(config: *mut ViaConfig) -> ViaConfigStatus {
+    if config.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &mut *config;
+    if let Err(status) = config.ensure_mutable() {
+        return status;
+    }
+    if config.pending_update.is_some() {
+        return diagnostics::report(ViaConfigStatus::InternalError, "An update is already in progress".to_string());
+    }
+    config.pending_update = Some(Vec::new());
+    ViaConfigStatus::Ok
+}
+
+/// Applies every change staged since `via_config_begin_update`, in order,
+/// and closes the update. If the handle was frozen after the update began,
+/// nothing is applied and `ViaConfigStatus::Frozen` is returned; the update
+/// stays open so that `via_config_abort_update` can discard it.
+///
+/// # Safety
+/// `config` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_commit_update(config: *mut ViaConfig) -> ViaConfigStatus {
+    if config.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &mut *config;
+    if let Err(status) = config.ensure_mutable() {
+        return status;
+    }
+    let Some(pending) = config.pending_update.take() else {
+        return diagnostics::report(ViaConfigStatus::InternalError, "No update is in progress".to_string());
+    };
+    for (key, change) in pending {
+        match change {
+            StagedChange::Set(val) => {
+                set_value_by_key(&mut config.merged_value, &key, val);
+                config.mark_runtime(&key);
+            }
+            StagedChange::Remove => config.apply_removal(&key),
+            StagedChange::Reset => config.apply_reset(&key),
+        }
+    }
+    ViaConfigStatus::Ok
+}
+
+/// Discards every change staged since `via_config_begin_update` and closes
+/// the update.
+///
+/// # Safety
+/// `config` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_abort_update(config: *mut ViaConfig) -> ViaConfigStatus {
+    if config.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &mut *config;
+    if config.pending_update.take().is_none() {
+        return diagnostics::report(ViaConfigStatus::InternalError, "No update is in progress".to_string());
+    }
+    ViaConfigStatus::Ok
+}
+
+/// Retrieves a file path, resolving it against `base_dir_c` when it is
+/// relative, and returns the canonicalized absolute path as an owned string.
+/// The path must exist. The returned string must be released with
+/// `via_config_free_string`.
+///
+/// # Safety
+/// All pointers must be valid, and `key_c` and `base_dir_c` null-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_get_path(
+    config: *const ViaConfig,
+    key_c: *const c_char,
+    base_dir_c: *const c_char,
+    out_value: *mut *mut c_char,
+) -> ViaConfigStatus {
+    if config.is_null() || key_c.is_null() || base_dir_c.is_null() || out_value.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
+    let Ok(base_dir) = CStr::from_ptr(base_dir_c).to_str() else { return ViaConfigStatus::InternalError; };
+
+    let raw = match config.lookup(key) {
+        Some(val) => match val.as_str() {
+            Some(s) if !s.is_empty() => s,
+            _ => return type_mismatch(key, "non-empty path", val),
+        },
+        None => return ViaConfigStatus::KeyNotFound,
+    };
+    let resolved = match resolve_existing_path(key, raw, Path::new(base_dir)) {
+        Ok(resolved) => resolved,
+        Err(status) => return status,
+    };
+    let Some(resolved) = resolved.to_str() else {
+        return diagnostics::report(
+            ViaConfigStatus::TypeError,
+            format!("Path '{}' for key '{}' does not resolve to valid UTF-8", raw, key),
+        );
+    };
+    let Some(value_c) = into_owned_c_string(resolved.to_string()) else { return ViaConfigStatus::InternalError; };
+    *out_value = value_c;
+    ViaConfigStatus::Ok
+}
+
+/// Resolves the path `raw` read from `key` against `base_dir` when it is
+/// relative, and canonicalizes it. Fails with `FileNotFound` if the path does
+/// not exist or lies outside the root jail.
+fn resolve_existing_path(key: &str, raw: &str, base_dir: &Path) -> Result<PathBuf, ViaConfigStatus> {
+    // `join` keeps an absolute value unchanged.
+    let joined = base_dir.join(raw);
+    let resolved = fs::canonicalize(&joined).map_err(|e| {
+        diagnostics::report(
+            ViaConfigStatus::FileNotFound,
+            format!("Path '{}' for key '{}' could not be resolved: {}", joined.display(), key, e),
+        )
+    })?;
+    jail::check(&resolved).map_err(|message| diagnostics::report(ViaConfigStatus::FileNotFound, message))?;
+    Ok(resolved)
+}
+
+/// The key of the table mapping perception model names to their files.
+const MODEL_PATHS_KEY: &str = "perception.model-paths";
+
+/// Returns every entry of `perception.model-paths` with its path resolved
+/// against `base_dir_c` when relative, as the perception module needs them.
+/// `out_names` and `out_paths` receive parallel arrays of `out_len` entries
+/// in document order. With `check_exists`, each path is canonicalized as by
+/// `via_config_get_path` and a missing file fails with `FileNotFound`;
+/// otherwise paths are only made absolute, without touching the file system.
+///
+/// # Safety
+/// All pointers must be valid, and `base_dir_c` null-terminated. On success
+/// `out_names` and `out_paths` must each be freed with
+/// `via_config_free_string_array`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_resolve_model_paths(
+    config: *const ViaConfig,
+    base_dir_c: *const c_char,
+    check_exists: bool,
+    out_names: *mut *mut *mut c_char,
+    out_paths: *mut *mut *mut c_char,
+    out_len: *mut usize,
+) -> ViaConfigStatus {
+    diagnostics::clear();
+    if config.is_null() || base_dir_c.is_null() || out_names.is_null() || out_paths.is_null() || out_len.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let Ok(base_dir) = CStr::from_ptr(base_dir_c).to_str() else { return ViaConfigStatus::InternalError; };
+    let base_dir = Path::new(base_dir);
+
+    let Some(val) = config.lookup(MODEL_PATHS_KEY) else { return ViaConfigStatus::KeyNotFound; };
+    let Some(map) = val.as_mapping() else { return type_mismatch(MODEL_PATHS_KEY, "mapping", val) };
+    let mut names = Vec::with_capacity(map.len());
+    let mut paths = Vec::with_capacity(map.len());
+    for (name, raw) in map {
+        let name = value_to_text(name);
+        let key = child_key(MODEL_PATHS_KEY, &name);
+        let raw = match raw.as_str() {
+            Some(raw) if !raw.is_empty() => raw,
+            _ => return type_mismatch(&key, "path", raw),
+        };
+        let resolved = if check_exists {
+            match resolve_existing_path(&key, raw, base_dir) {
+                Ok(resolved) => resolved,
+                Err(status) => return status,
+            }
+        } else {
+            match std::path::absolute(base_dir.join(raw)) {
+                Ok(resolved) => resolved,
+                Err(e) => {
+                    return diagnostics::report(
+                        ViaConfigStatus::InternalError,
+                        format!("Path '{}' for key '{}' could not be made absolute: {}", raw, key, e),
+                    )
+                }
+            }
+        };
+        let Some(resolved) = resolved.to_str() else { return ViaConfigStatus::TypeError; };
+        names.push(name);
+        paths.push(resolved.to_string());
+    }
+
+    let Some((names_array, len)) = into_owned_c_string_array(names) else { return ViaConfigStatus::InternalError; };
+    let Some((paths_array, _)) = into_owned_c_string_array(paths) else {
+        via_config_free_string_array(names_array, len);
+        return ViaConfigStatus::InternalError;
+    };
+    *out_names = names_array;
+    *out_paths = paths_array;
+    *out_len = len;
+    ViaConfigStatus::Ok
+}
+
+/// Serializes the subtree at `key_c` (the whole configuration if empty) as
+/// YAML. With `VIA_CONFIG_DUMP_FLAG_ROUND_FLOATS`, floats are rounded to
+/// `float_precision` decimal places in the output only; the getters keep
+/// returning the original values. Values of keys registered with
+/// `via_config_add_secret_key` are replaced with `***`. The returned string
+/// must be released with `via_config_free_string`.
+///
+/// # Safety
+/// All pointers must be valid, and `key_c` null-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_dump_yaml(
+    config: *const ViaConfig,
+    key_c: *const c_char,
+    dump_flags: u32,
+    float_precision: u32,
+    out_yaml: *mut *mut c_char,
+) -> ViaConfigStatus {
+    if config.is_null() || key_c.is_null() || out_yaml.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
+
+    let Some(node) = get_node(&config.merged_value, key) else { return ViaConfigStatus::KeyNotFound; };
+    let mut node = node.clone();
+    dump::redact(&mut node, key, &config.secret_keys);
+    if dump_flags & VIA_CONFIG_DUMP_FLAG_ROUND_FLOATS != 0 {
+        dump::round_floats(&mut node, float_precision as usize);
+    }
+    if dump_flags & VIA_CONFIG_DUMP_FLAG_SORT_KEYS != 0 {
+        dump::sort_keys(&mut node);
+    }
+    let Ok(yaml) = serde_yaml::to_string(&node) else { return ViaConfigStatus::InternalError; };
+    let Some(yaml_c) = into_owned_c_string(yaml) else { return ViaConfigStatus::InternalError; };
+    *out_yaml = yaml_c;
+    ViaConfigStatus::Ok
+}
+
+/// Serializes the subtree at `key_c` (the whole configuration if empty) as
+/// compact JSON, for consumers that do not speak YAML. Values of keys
+/// registered with `via_config_add_secret_key` are replaced with `***`, as in
+/// `via_config_dump_yaml`. Fails with `TypeError` if the subtree has no JSON
+/// equivalent, e.g. a mapping key that is itself a collection. The returned
+/// string must be released with `via_config_free_string`.
+///
+/// # Safety
+/// All pointers must be valid, and `key_c` null-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_get_subtree_json(
+    config: *const ViaConfig,
+    key_c: *const c_char,
+    out_json: *mut *mut c_char,
+) -> ViaConfigStatus {
+    if config.is_null() || key_c.is_null() || out_json.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
+
+    let Some(node) = get_node(&config.merged_value, key) else { return ViaConfigStatus::KeyNotFound; };
+    let mut node = node.clone();
+    dump::redact(&mut node, key, &config.secret_keys);
+    let json = match serde_json::to_string(&node) {
+        Ok(json) => json,
+        Err(e) => {
+            return diagnostics::report(
+                ViaConfigStatus::TypeError,
+                format!("Key '{}' cannot be represented as JSON: {}", key, e),
+            )
+        }
+    };
+    let Some(json_c) = into_owned_c_string(json) else { return ViaConfigStatus::InternalError; };
+    *out_json = json_c;
+    ViaConfigStatus::Ok
+}
+
+/// Returns the type of the value at `key_c` together with the value
+/// serialized as YAML, so that a binding can read any key in one call and
+/// parse it on its side. Unlike `via_config_dump_yaml`, secret keys are not
+/// redacted. The returned string must be released with
+/// `via_config_free_string`.
+///
+/// # Safety
+/// All pointers must be valid, and `key_c` null-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_get_any(
+    config: *const ViaConfig,
+    key_c: *const c_char,
+    out_type: *mut ViaConfigValueType,
+    out_value_yaml: *mut *mut c_char,
+) -> ViaConfigStatus {
+    if config.is_null() || key_c.is_null() || out_type.is_null() || out_value_yaml.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
+
+    let Some(val) = config.lookup(key) else { return ViaConfigStatus::KeyNotFound; };
+    let Ok(yaml) = serde_yaml::to_string(val) else { return ViaConfigStatus::InternalError; };
+    let Some(yaml_c) = into_owned_c_string(yaml) else { return ViaConfigStatus::InternalError; };
+    *out_type = ViaConfigValueType::of(val);
+    *out_value_yaml = yaml_c;
+    ViaConfigStatus::Ok
+}
+
+/// Looks up `count` keys in one call. `out_values_yaml` receives an array of
+/// `count` strings holding each value serialized as YAML, as
+/// `via_config_get_any` does, and `out_statuses[i]` the status of the lookup
+/// of `keys[i]`. A key that could not be read (e.g. `KeyNotFound`) has a null
+/// entry in the value array.
+///
+/// The call itself only fails for invalid arguments, in which case the
+/// outputs are untouched.
+///
+/// # Safety
+/// `config` must be valid, `keys` must point to `count` valid, null-terminated
+/// C strings and `out_statuses` to room for `count` statuses. On success the
+/// value array must be freed with `via_config_free_string_array` and `count`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_get_batch(
+    config: *const ViaConfig,
+    keys: *const *const c_char,
+    count: usize,
+    out_values_yaml: *mut *mut *mut c_char,
+    out_statuses: *mut ViaConfigStatus,
+) -> ViaConfigStatus {
+    if config.is_null() || out_values_yaml.is_null() || (count > 0 && out_statuses.is_null()) {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let keys = match borrow_c_string_array(keys, count) {
+        Ok(keys) => keys,
+        Err(status) => return status,
+    };
+
+    let mut values = Vec::with_capacity(count);
+    for (i, key) in keys.iter().enumerate() {
+        let value = match config.lookup(key) {
+            Some(val) => serde_yaml::to_string(val).ok().and_then(into_owned_c_string).ok_or(ViaConfigStatus::InternalError),
+            None => Err(ViaConfigStatus::KeyNotFound),
+        };
+        *out_statuses.add(i) = value.err().unwrap_or(ViaConfigStatus::Ok);
+        values.push(value.unwrap_or(std::ptr::null_mut()));
+    }
+    *out_values_yaml = Box::into_raw(values.into_boxed_slice()) as *mut *mut c_char;
+    ViaConfigStatus::Ok
+}
+
+/// Reports whether the configuration was restored from the cache by
+/// `via_config_load_cached` rather than parsed from the YAML files.
+///
+/// # Safety
+/// All pointers must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_was_cached(config: *const ViaConfig, out_cached: *mut bool) -> ViaConfigStatus {
+    if config.is_null() || out_cached.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    *out_cached = (*config).cached;
+    ViaConfigStatus::Ok
+}
+
+/// Collects every string in a value, including inside collections.
+fn collect_strings<'a>(val: &'a Value, out: &mut Vec<&'a str>) {
+    match val {
+        Value::String(s) => out.push(s),
+        Value::Sequence(seq) => seq.iter().for_each(|v| collect_strings(v, out)),
+        Value::Mapping(map) => map.values().for_each(|v| collect_strings(v, out)),
+        Value::Tagged(tagged) => collect_strings(&tagged.value, out),
+        _ => {}
+    }
+}
+
+/// Audits every `${VAR}` reference in the configuration without substituting
+/// anything, and reports all referenced environment variables that are not
+/// set. References with a fallback (`${VAR:-default}`) are never missing.
+///
+/// Returns `ValidationFailed` if any variable is missing; `out_missing` then
+/// receives a newline-separated report with one line per variable, naming
+/// the keys that reference it. On `Ok`, `out_missing` is set to null.
+///
+/// # Safety
+/// All pointers must be valid. A non-null report must be freed with
+/// `via_config_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_check_interpolation(
+    config: *const ViaConfig,
+    out_missing: *mut *mut c_char,
+) -> ViaConfigStatus {
+    if config.is_null() || out_missing.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+
+    // Variable name -> referencing keys, in order of first reference.
+    let mut missing: Vec<(String, Vec<String>)> = Vec::new();
+    for_each_leaf(&config.merged_value, "", &mut |key, val| {
+        let mut strings = Vec::new();
+        collect_strings(val, &mut strings);
+        for reference in strings.into_iter().flat_map(interpolate::references) {
+            if reference.has_fallback || std::env::var_os(reference.name).is_some() {
+                continue;
+            }
+            let keys = match missing.iter_mut().find(|(name, _)| name == reference.name) {
+                Some((_, keys)) => keys,
+                None => {
+                    missing.push((reference.name.to_string(), Vec::new()));
+                    &mut missing.last_mut().unwrap().1
+                }
+            };
+            if !keys.iter().any(|k| k == key) {
+                keys.push(key.to_string());
+            }
+        }
+    });
+
+    if missing.is_empty() {
+        *out_missing = std::ptr::null_mut();
+        return ViaConfigStatus::Ok;
+    }
+    let report: Vec<String> = missing
+        .into_iter()
+        .map(|(name, keys)| format!("{}: environment variable is not set (referenced by {})", name, keys.join(", ")))
+        .collect();
+    let Some(report_c) = into_owned_c_string(report.join("\n")) else { return ViaConfigStatus::InternalError; };
+    *out_missing = report_c;
+    ViaConfigStatus::ValidationFailed
+}
+
+/// The YAML type of a configuration value, as reported by `via_config_list_all`,
+/// `via_config_get_any` and `via_config_visit`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViaConfigValueType {
+    Null = 0,
+    Bool = 1,
+    Integer = 2,
+    Float = 3,
+    String = 4,
+    Sequence = 5,
+    /// Only reported by `via_config_list_all` for an empty mapping, since it
+    /// flattens non-empty ones. `via_config_visit` reports every mapping.
+    Mapping = 6,
+}
+
+impl ViaConfigValueType {
+    /// The type's name as used in error messages.
+    fn name(self) -> &'static str {
+        match self {
+            ViaConfigValueType::Null => "null",
+            ViaConfigValueType::Bool => "boolean",
+            ViaConfigValueType::Integer => "integer",
+            ViaConfigValueType::Float => "float",
+            ViaConfigValueType::String => "string",
+            ViaConfigValueType::Sequence => "sequence",
+            ViaConfigValueType::Mapping => "mapping",
+        }
+    }
+
+    fn of(val: &Value) -> Self {
+        match val {
+            Value::Null => ViaConfigValueType::Null,
+            Value::Bool(_) => ViaConfigValueType::Bool,
+            Value::Number(n) if n.is_f64() => ViaConfigValueType::Float,
+            Value::Number(_) => ViaConfigValueType::Integer,
+            Value::String(_) => ViaConfigValueType::String,
+            Value::Sequence(_) => ViaConfigValueType::Sequence,
+            Value::Mapping(_) => ViaConfigValueType::Mapping,
+            Value::Tagged(tagged) => ViaConfigValueType::of(&tagged.value),
+        }
+    }
+}
+
+/// Lists every leaf of the merged configuration as a dot-separated key and
+/// its type, in document/merge order. `out_keys` and `out_types` receive
+/// parallel arrays of `out_len` entries.
+///
+/// # Safety
+/// All pointers must be valid. On success `out_keys` must be freed with
+/// `via_config_free_string_array` and `out_types` with
+/// `via_config_free_type_array`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_list_all(
+    config: *const ViaConfig,
+    out_keys: *mut *mut *mut c_char,
+    out_types: *mut *mut ViaConfigValueType,
+    out_len: *mut usize,
+) -> ViaConfigStatus {
+    if config.is_null() || out_keys.is_null() || out_types.is_null() || out_len.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+
+    let mut keys = Vec::new();
+    let mut types = Vec::new();
+    for_each_leaf(&config.merged_value, "", &mut |key, val| {
+        keys.push(key.to_string());
+        types.push(ViaConfigValueType::of(val));
+    });
+
+    let Some((keys_array, len)) = into_owned_c_string_array(keys) else { return ViaConfigStatus::InternalError; };
+    *out_keys = keys_array;
+    *out_types = Box::into_raw(types.into_boxed_slice()) as *mut ViaConfigValueType;
+    *out_len = len;
+    ViaConfigStatus::Ok
+}
+
+/// Calls `visitor` for every node of the merged configuration, depth-first in
+/// document order: each mapping entry with its dot-key, a mapping before its
+/// children. Mappings are passed with a null value, since their children
+/// follow; sequences and scalars are passed as YAML and not descended into,
+/// as in `via_config_list_all`. Values of secret keys are passed as they are.
+///
+/// Returns `InternalError` without visiting further nodes if a key or value
+/// contains a null byte.
+///
+/// # Safety
+/// `config` must be valid. `user_data` is passed to `visitor` unchanged, and
+/// `visitor` must not free or modify the handle.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_visit(
+    config: *const ViaConfig,
+    visitor: Option<ViaConfigVisitor>,
+    user_data: *mut c_void,
+) -> ViaConfigStatus {
+    if config.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let Some(visitor) = visitor else { return ViaConfigStatus::NullArgument; };
+    let config = &*config;
+
+    fn visit(val: &Value, prefix: &str, visitor: ViaConfigVisitor, user_data: *mut c_void) -> Result<(), ViaConfigStatus> {
+        let Some(map) = val.as_mapping() else { return Ok(()) };
+        for (k, v) in map {
+            let key = child_key(prefix, &value_to_text(k));
+            let key_c = CString::new(key.as_str()).map_err(|_| ViaConfigStatus::InternalError)?;
+            let value_type = ViaConfigValueType::of(v);
+            if value_type == ViaConfigValueType::Mapping {
+                visitor(key_c.as_ptr(), value_type, std::ptr::null(), user_data);
+                visit(v, &key, visitor, user_data)?;
+            } else {
+                let yaml_c = CString::new(value_to_yaml(v)).map_err(|_| ViaConfigStatus::InternalError)?;
+                visitor(key_c.as_ptr(), value_type, yaml_c.as_ptr(), user_data);
+            }
+        }
+        Ok(())
+    }
+    match visit(&config.merged_value, "", visitor, user_data) {
+        Ok(()) => ViaConfigStatus::Ok,
+        Err(status) => status,
+    }
+}
+
+/// Frees an array of types returned by `via_config_list_all`.
+///
+/// # Safety
+/// `array` and `len` must be exactly as returned by this library, and must not
+/// have been freed yet. Passing a null pointer is safe.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_free_type_array(array: *mut ViaConfigValueType, len: usize) {
+    if !array.is_null() {
+        drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(array, len)));
+    }
+}
+
+/// Counts the leaves of the merged configuration, i.e. the keys
+/// `via_config_list_all` would return, without building the list.
+///
+/// # Safety
+/// All pointers must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_count_keys(config: *const ViaConfig, out_count: *mut usize) -> ViaConfigStatus {
+    if config.is_null() || out_count.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    *out_count = count_leaves(&(*config).merged_value);
+    ViaConfigStatus::Ok
+}
+
+/// Registers a key whose value, including any subtree below it, is replaced
+/// with `***` in dumps. `key_c` may be a glob pattern where `*` matches
+/// within one key segment and `**` across segments (e.g. `**.api_key`).
+///
+/// # Safety
+/// All pointers must be valid, and `key_c` null-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_add_secret_key(config: *mut ViaConfig, key_c: *const c_char) -> ViaConfigStatus {
+    if config.is_null() || key_c.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &mut *config;
+    let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
+    if key.is_empty() {
+        return ViaConfigStatus::KeyNotFound;
+    }
+
+    if !config.secret_keys.iter().any(|k| k == key) {
+        config.secret_keys.push(key.to_string());
+    }
+    ViaConfigStatus::Ok
+}
+
+/// Returns the read+parse duration of each source file, recorded when the
+/// handle was loaded with `VIA_CONFIG_FLAG_RECORD_TIMINGS`. `out_files` and
+/// `out_micros` receive parallel arrays of `out_len` entries in load order;
+/// without the flag (or for a cached load) the arrays are empty.
+///
+/// # Safety
+/// All pointers must be valid. On success `out_files` must be freed with
+/// `via_config_free_string_array` and `out_micros` with
+/// `via_config_free_u64_array`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_get_load_timings(
+    config: *const ViaConfig,
+    out_files: *mut *mut *mut c_char,
+    out_micros: *mut *mut u64,
+    out_len: *mut usize,
+) -> ViaConfigStatus {
+    if config.is_null() || out_files.is_null() || out_micros.is_null() || out_len.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+
+    let (files, micros): (Vec<String>, Vec<u64>) = config.load_timings.iter().cloned().unzip();
+    let Some((files_array, len)) = into_owned_c_string_array(files) else { return ViaConfigStatus::InternalError; };
+    *out_files = files_array;
+    *out_micros = Box::into_raw(micros.into_boxed_slice()) as *mut u64;
+    *out_len = len;
+    ViaConfigStatus::Ok
+}
+
+/// Frees an array of integers returned by `via_config_get_load_timings`.
+///
+/// # Safety
+/// `array` and `len` must be exactly as returned by this library, and must not
+/// have been freed yet. Passing a null pointer is safe.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_free_u64_array(array: *mut u64, len: usize) {
+    if !array.is_null() {
+        drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(array, len)));
+    }
+}
+
+/// Returns the overrides recorded when the handle was loaded with
+/// `VIA_CONFIG_FLAG_RECORD_OVERRIDES`, one per line as
+/// `key: old -> new (source N)` in merge order, where `N` is the overriding
+/// source as reported by `via_config_get_source`. Unlike provenance, which
+/// only tells which source won, this lists every replacement, including
+/// values that a still later source replaced again. Values of secret keys
+/// are shown as `***`. `out_report` is set to null if nothing was
+/// overridden, or if the flag was not set.
+///
+/// # Safety
+/// All pointers must be valid. A non-null `out_report` must be freed with
+/// `via_config_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_get_override_log(config: *const ViaConfig, out_report: *mut *mut c_char) -> ViaConfigStatus {
+    if config.is_null() || out_report.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    if config.overrides.is_empty() {
+        *out_report = std::ptr::null_mut();
+        return ViaConfigStatus::Ok;
+    }
+
+    let show = |o: &Override, val| shown_value(config, &o.key, val);
+    let report: Vec<String> = config
+        .overrides
+        .iter()
+        .map(|o| format!("{}: {} -> {} (source {})", o.key, show(o, &o.old), show(o, &o.new), o.source))
+        .collect();
+    let Some(report_c) = into_owned_c_string(report.join("\n")) else { return ViaConfigStatus::InternalError; };
+    *out_report = report_c;
+    ViaConfigStatus::Ok
+}
+
+/// Reports how often each key was looked up by the typed getters of a handle
+/// loaded with `VIA_CONFIG_FLAG_RECORD_METRICS`, one key per line as
+/// `key: N reads, M not found`, most read first. Every leaf of the
+/// configuration that was never read is listed with `0 reads`, which finds
+/// settings nothing uses. `out_report` is set to null if the flag was not set.
+///
+/// # Safety
+/// All pointers must be valid. A non-null `out_report` must be freed with
+/// `via_config_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_dump_metrics(config: *const ViaConfig, out_report: *mut *mut c_char) -> ViaConfigStatus {
+    if config.is_null() || out_report.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let Some(metrics) = &config.metrics else {
+        *out_report = std::ptr::null_mut();
+        return ViaConfigStatus::Ok;
+    };
+
+    let mut leaf_keys = Vec::new();
+    for_each_leaf(&config.merged_value, "", &mut |key, _| leaf_keys.push(key.to_string()));
+    let Some(report_c) = into_owned_c_string(metrics.report(&leaf_keys)) else { return ViaConfigStatus::InternalError; };
+    *out_report = report_c;
+    ViaConfigStatus::Ok
+}
+
+/// Formats the value of `key` on one line for a diagnostic report, as `***`
+/// if the key is secret and with any secrets nested in it redacted.
+fn shown_value(config: &ViaConfig, key: &str, val: &Value) -> String {
+    redacted_text(key, val, &config.secret_keys)
+}
+
+/// Formats the value at `key` inline, with `***` for a secret key and for
+/// the secret keys below it.
+fn redacted_text(key: &str, val: &Value, secret_keys: &[String]) -> String {
+    if dump::is_secret(key, secret_keys) {
+        return dump::REDACTED.to_string();
+    }
+    let mut val = val.clone();
+    dump::redact(&mut val, key, secret_keys);
+    value_to_inline_text(&val)
+}
+
+/// Names a source layer in reports, with its file when it was loaded from one.
+fn source_label(config: &ViaConfig, index: usize) -> String {
+    match config.source_paths.get(index) {
+        Some(path) => format!("source {} ({})", index, path.display()),
+        None => format!("source {}", index),
+    }
+}
+
+/// Explains how a leaf key got its value, as one line listing each step in
+/// order, e.g. `source 0 (system.yaml)=info, overridden by source 2
+/// (profile.yaml)=debug, overridden by command-line override=trace`.
+///
+/// Every source that sets the key is listed, whichever flags the handle was
+/// loaded with. A change made after load is named by what made it: a
+/// command-line override (`via_config_apply_overrides`), a merged handle
+/// (`via_config_merge_into`) or any other runtime change. If the final value
+/// differs from the last step, e.g. after template resolution, it is given
+/// as `resolved to ...`. Values of secret keys are shown as `***`.
+///
+/// Returns `TypeError` if the key is a mapping rather than a leaf.
+///
+/// # Safety
+/// All pointers must be valid, and `key_c` null-terminated. On success
+/// `out_explanation` must be freed with `via_config_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_explain_key(
+    config: *const ViaConfig,
+    key_c: *const c_char,
+    out_explanation: *mut *mut c_char,
+) -> ViaConfigStatus {
+    if config.is_null() || key_c.is_null() || out_explanation.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
+
+    let Some(current) = get_value_by_key(&config.merged_value, key) else { return ViaConfigStatus::KeyNotFound; };
+    if current.is_mapping() {
+        return ViaConfigStatus::TypeError;
+    }
+
+    // As for provenance, a null in a source does not count as setting the key.
+    let mut steps: Vec<(String, &Value)> = config
+        .layers
+        .iter()
+        .enumerate()
+        .filter_map(|(index, layer)| {
+            get_value_by_key(layer, key).filter(|v| !v.is_null()).map(|v| (source_label(config, index), v))
+        })
+        .collect();
+    match config.provenance.get(key) {
+        Some(&SOURCE_RUNTIME) => {
+            let origin = config.runtime_origins.get(key).copied().unwrap_or(ORIGIN_RUNTIME);
+            steps.push((origin.to_string(), current));
+        }
+        // A handle restored from a cache or binary buffer keeps no layers.
+        Some(&source) if steps.is_empty() && source >= 0 => steps.push((source_label(config, source as usize), current)),
+        _ => {}
+    }
+
+    let mut explanation: Vec<String> = steps
+        .iter()
+        .enumerate()
+        .map(|(i, (label, val))| {
+            let verb = if i == 0 { "" } else { "overridden by " };
+            format!("{}{}={}", verb, label, shown_value(config, key, val))
+        })
+        .collect();
+    match steps.last() {
+        Some((_, last)) if *last == current => {}
+        Some(_) => explanation.push(format!("resolved to {}", shown_value(config, key, current))),
+        None => explanation.push(format!("no source sets it; the value is {}", shown_value(config, key, current))),
+    }
+    let Some(explanation_c) = into_owned_c_string(explanation.join(", ")) else { return ViaConfigStatus::InternalError; };
+    *out_explanation = explanation_c;
+    ViaConfigStatus::Ok
+}
+
+/// Returns a mapping of numbers, such as `hardware.perception.thresholds`, as
+/// parallel arrays of its keys and values in document/merge order. Integer
+/// and float values are both promoted to `f64`.
+///
+/// Returns `TypeError` if the value is not a mapping, or if any of its keys is
+/// not a string or any of its values is not a number.
+///
+/// # Safety
+/// All pointers must be valid, and `key_c` null-terminated. On success
+/// `out_keys` must be freed with `via_config_free_string_array` and
+/// `out_values` with `via_config_free_f64_array`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_get_numeric_map(
+    config: *const ViaConfig,
+    key_c: *const c_char,
+    out_keys: *mut *mut *mut c_char,
+    out_values: *mut *mut f64,
+    out_len: *mut usize,
+) -> ViaConfigStatus {
+    if config.is_null() || key_c.is_null() || out_keys.is_null() || out_values.is_null() || out_len.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
+
+    let Some(val) = config.lookup(key) else { return ViaConfigStatus::KeyNotFound; };
+    let Some(map) = val.as_mapping() else { return type_mismatch(key, "mapping", val) };
+    let mut keys = Vec::with_capacity(map.len());
+    let mut values = Vec::with_capacity(map.len());
+    for (k, v) in map {
+        let Some(k) = k.as_str() else { return type_mismatch(key, "mapping with string keys", val) };
+        let v = config.coerce_numeric(v);
+        let Value::Number(n) = v.as_ref() else {
+            return type_mismatch(&format!("{}.{}", key, k), "number", &v);
+        };
+        keys.push(k.to_string());
+        values.push(number_to_f64(n));
+    }
+
+    let Some((keys_array, len)) = into_owned_c_string_array(keys) else { return ViaConfigStatus::InternalError; };
+    *out_keys = keys_array;
+    *out_values = Box::into_raw(values.into_boxed_slice()) as *mut f64;
+    *out_len = len;
+    ViaConfigStatus::Ok
+}
+
+/// Frees an array of numbers returned by `via_config_get_numeric_map`.
+///
+/// # Safety
+/// `array` and `len` must be exactly as returned by this library, and must not
+/// have been freed yet. Passing a null pointer is safe.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_free_f64_array(array: *mut f64, len: usize) {
+    if !array.is_null() {
+        drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(array, len)));
+    }
+}
+
+/// Parses a command-line style `key=value` override. The value is read as a
+/// YAML scalar, so `4096` becomes an integer and `true` a boolean; anything
+/// that is not a scalar (e.g. `a: b`) is kept as a plain string.
+fn parse_override(arg: &str) -> Option<(&str, Value)> {
+    let (key, raw) = arg.split_once('=')?;
+    let key = key.trim();
+    if key.is_empty() || key_segments(key).iter().any(|segment| segment.is_empty()) {
+        return None;
+    }
+    let val = match serde_yaml::from_str::<Value>(raw) {
+        Ok(val @ (Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_))) => val,
+        _ => Value::String(raw.to_string()),
+    };
+    Some((key, val))
+}
+
+/// Applies command-line style overrides such as
+/// `reasoning.llm.context-size=4096`, creating paths as needed. All entries
+/// are checked before any is applied, so a failing entry leaves the
+/// configuration unchanged: a malformed entry is reported as `ParseError`
+/// and a key deeper than the depth limit as `InternalError`, with details
+/// through `via_config_last_error_message`. Inside an update opened with
+/// `via_config_begin_update`, the changes are staged.
+///
+/// # Safety
+/// `config` must be valid, and `args` must point to `count` valid,
+/// null-terminated C strings.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_apply_overrides(
+    config: *mut ViaConfig,
+    args: *const *const c_char,
+    count: usize,
+) -> ViaConfigStatus {
+    diagnostics::clear();
+    if config.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &mut *config;
+    if let Err(status) = config.ensure_mutable() {
+        return status;
+    }
+    let args = match borrow_c_string_array(args, count) {
+        Ok(args) => args,
+        Err(status) => return status,
+    };
+
+    let mut overrides = Vec::with_capacity(args.len());
+    for (i, arg) in args.iter().enumerate() {
+        let Some((key, val)) = parse_override(arg) else {
+            return diagnostics::report(
+                ViaConfigStatus::ParseError,
+                format!("Override #{} {:?} is not of the form key=value", i + 1, arg),
+            );
+        };
+        // `set` checks the same, but by then earlier entries would already be applied.
+        if let Err(message) = limits::check_key(key) {
+            return diagnostics::report(ViaConfigStatus::InternalError, message);
+        }
+        overrides.push((key, val));
+    }
+    for (key, val) in overrides {
+        if let Err(status) = config.set(key, val) {
+            return status;
+        }
+        config.record_origin(key, ORIGIN_OVERRIDE);
+    }
+    ViaConfigStatus::Ok
+}
+
+/// Applies a JSON Patch (RFC 6902) with `add`, `remove`, `replace`, `move`
+/// and `test` operations to the merged tree. The operations are applied to a copy in
+/// order, and the configuration is only changed if all of them succeed: a
+/// malformed patch is a `ParseError`, a path that does not exist is
+/// `KeyNotFound`, and a failed `test` is `ValidationFailed`, with details
+/// through `via_config_last_error_message`. Inside an update opened with
+/// `via_config_begin_update`, the patch is applied to the committed values
+/// and the resulting changes are staged.
+///
+/// # Safety
+/// `config` must be valid, and `patch_json_c` a valid, null-terminated C
+/// string.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_apply_json_patch(config: *mut ViaConfig, patch_json_c: *const c_char) -> ViaConfigStatus {
+    diagnostics::clear();
+    if config.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &mut *config;
+    if let Err(status) = config.ensure_mutable() {
+        return status;
+    }
+    let Some(text) = str_arg(patch_json_c, "patch") else { return diagnostics::last_status(); };
+
+    let operations = match patch::parse(text) {
+        Ok(operations) => operations,
+        Err(message) => return diagnostics::report(ViaConfigStatus::ParseError, message),
+    };
+    let mut patched = config.merged_value.clone();
+    let mut changed = match patch::apply(&mut patched, &operations, &config.secret_keys) {
+        Ok(changed) => changed,
+        Err((status, message)) => return diagnostics::report(status, message),
+    };
+    if let Err(message) = limits::check_tree(&patched, "The patched configuration") {
+        return diagnostics::report(ViaConfigStatus::InternalError, message);
+    }
+
+    // Copy each changed entry once, skipping those inside another changed entry.
+    changed.sort();
+    changed.dedup();
+    let inside = |key: &str, other: &str| key.strip_prefix(other).is_some_and(|rest| rest.starts_with('.'));
+    let outermost: Vec<&String> = changed.iter().filter(|key| !changed.iter().any(|other| inside(key, other))).collect();
+    for key in outermost {
+        let result = match get_value_by_key(&patched, key) {
+            Some(val) => config.set(key, val.clone()),
+            None if get_value_by_key(&config.merged_value, key).is_some() => config.remove(key),
+            None => Ok(()),
+        };
+        if let Err(status) = result {
+            return status;
+        }
+        config.record_origin(key, ORIGIN_PATCH);
+    }
+    ViaConfigStatus::Ok
+}
+
+/// Routes the library's log messages to `callback` instead of stderr, for
+/// every thread. Passing null restores logging to stderr.
+///
+/// # Safety
+/// `callback`, if not null, must remain callable for as long as it is
+/// installed, and may be called from any thread that uses the library.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_set_log_callback(callback: Option<ViaConfigLogCallback>) {
+    diagnostics::set_log_callback(callback);
+}
+
+/// Retrieves a string value that must be one of the `count` strings in
+/// `allowed`, and stores the index of the matching variant in `out_index`.
+/// Matching is exact; a string outside the allowed set is a `TypeError`.
+///
+/// # Safety
+/// All pointers must be valid, `key_c` null-terminated, and `allowed` must
+/// point to `count` valid, null-terminated C strings.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_get_enum(
+    config: *const ViaConfig,
+    key_c: *const c_char,
+    allowed: *const *const c_char,
+    count: usize,
+    out_index: *mut i32,
+) -> ViaConfigStatus {
+    if config.is_null() || key_c.is_null() || out_index.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
+    let allowed = match borrow_c_string_array(allowed, count) {
+        Ok(allowed) => allowed,
+        Err(status) => return status,
+    };
+
+    match config.lookup(key) {
+        Some(val) => match val.as_str().and_then(|s| allowed.iter().position(|a| *a == s)) {
+            Some(index) => {
+                *out_index = index as i32;
+                ViaConfigStatus::Ok
+            }
+            None => type_mismatch(key, &format!("one of {}", allowed.join(", ")), val),
+        },
+        None => ViaConfigStatus::KeyNotFound,
+    }
+}
+
+/// Retrieves a string value that must be one of the `count` strings in
+/// `names`, like `via_config_get_enum`, and stores the entry of `values` at
+/// the same position in `out_value`, e.g. the C enum constant of the variant.
+///
+/// # Safety
+/// All pointers must be valid, `key_c` null-terminated, `names` must point
+/// to `count` valid, null-terminated C strings, and `values` to `count`
+/// integers.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_get_enum_mapped(
+    config: *const ViaConfig,
+    key_c: *const c_char,
+    names: *const *const c_char,
+    values: *const i32,
+    count: usize,
+    out_value: *mut i32,
+) -> ViaConfigStatus {
+    if (values.is_null() && count > 0) || out_value.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let mut index = 0;
+    let status = via_config_get_enum(config, key_c, names, count, &mut index);
+    if status == ViaConfigStatus::Ok {
+        *out_value = *values.add(index as usize);
+    }
+    status
+}
+
+/// Reads, lints and parses one file for `via_config_validate_files`, then
+/// checks it against the model `T`. Problems are appended to `issues`, one
+/// line each.
+fn validate_file<T: serde::de::DeserializeOwned>(path: &str, issues: &mut Vec<String>) {
+    if let Err(message) = jail::check(Path::new(path)) {
+        issues.push(message);
+        return;
+    }
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => {
+            issues.push(format!("{}: cannot be read: {}", path, e));
+            return;
+        }
+    };
+    let content = &*lint::normalize_text(&content);
+    let document = match formats::for_path(Path::new(path)).parse(content, path, 0) {
+        Ok(document) => document,
+        Err(message) => {
+            issues.push(message);
+            return;
+        }
+    };
+    // An empty document only means "no overrides" and is never a schema violation.
+    if !document.is_null() {
+        if let Err(e) = serde_yaml::from_value::<T>(document) {
+            issues.push(format!("{}: does not match the schema: {}", path, e));
+        }
+    }
+}
+
+/// Validates the system, hardware and profile files without keeping a
+/// handle: each file is read, linted, parsed and checked against its model
+/// in `models`. Every file is checked even if an earlier one has problems, so
+/// the report is complete. Merging parsed documents cannot fail, so when this
+/// returns `Ok`, loading the same files succeeds.
+///
+/// Returns `ValidationFailed` if any problem was found; `out_report` then
+/// receives a newline-separated report, one line per problem, each naming
+/// the file. On `Ok`, `out_report` is set to null.
+///
+/// # Safety
+/// All strings must be valid and null-terminated, and `out_report` valid. A
+/// non-null report must be freed with `via_config_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_validate_files(
+    system_path_c: *const c_char,
+    hardware_path_c: *const c_char,
+    profile_path_c: *const c_char,
+    out_report: *mut *mut c_char,
+) -> ViaConfigStatus {
+    diagnostics::clear();
+    if out_report.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let Some(system_path) = str_arg(system_path_c, "system path") else { return diagnostics::last_status(); };
+    let Some(hardware_path) = str_arg(hardware_path_c, "hardware path") else { return diagnostics::last_status(); };
+    let Some(profile_path) = str_arg(profile_path_c, "profile path") else { return diagnostics::last_status(); };
+
+    let mut issues = Vec::new();
+    validate_file::<models::SystemConfig>(system_path, &mut issues);
+    validate_file::<models::HardwareConfig>(hardware_path, &mut issues);
+    validate_file::<models::ProfileConfig>(profile_path, &mut issues);
+
+    if issues.is_empty() {
+        *out_report = std::ptr::null_mut();
+        return ViaConfigStatus::Ok;
+    }
+    let Some(report_c) = into_owned_c_string(issues.join("\n")) else { return ViaConfigStatus::InternalError; };
+    *out_report = report_c;
+    ViaConfigStatus::ValidationFailed
+}
+
+/// Loads the three files, reads one string value and frees everything again,
+/// returning an owned copy of the value. Meant for scripts and tooling that
+/// need a single value; anything reading several keys should keep a handle.
+///
+/// On a load failure, the status of the failure is returned (e.g.
+/// `FileNotFound` or `ParseError`), with details in
+/// `via_config_last_error_message`.
+///
+/// # Safety
+/// All strings must be valid and null-terminated, and `out_value` valid. The
+/// returned string must be freed with `via_config_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_read_string(
+    system_path_c: *const c_char,
+    hardware_path_c: *const c_char,
+    profile_path_c: *const c_char,
+    key_c: *const c_char,
+    out_value: *mut *mut c_char,
+) -> ViaConfigStatus {
+    if key_c.is_null() || out_value.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
+    let config = via_config_load(system_path_c, hardware_path_c, profile_path_c);
+    if config.is_null() {
+        return diagnostics::last_status();
+    }
+    let config = Box::from_raw(config);
+
+    match config.lookup(key) {
+        Some(val) => match val.as_str() {
+            Some(s) => {
+                let Some(value_c) = into_owned_c_string(s.to_string()) else { return ViaConfigStatus::InternalError; };
+                *out_value = value_c;
+                ViaConfigStatus::Ok
+            }
+            None => type_mismatch(key, "string", val),
+        },
+        None => ViaConfigStatus::KeyNotFound,
+    }
+}
+
+/// Sets the maximum nesting depth of configuration trees, for every thread.
+/// Documents, binary buffers and keys that would nest deeper are rejected
+/// with `InternalError` instead of risking a stack overflow in the recursive
+/// tree operations. Passing 0 restores the default of 128, which matches the
+/// recursion limit of the YAML parser.
+#[no_mangle]
+pub extern "C" fn via_config_set_max_depth(depth: usize) {
+    limits::set_max_depth(depth);
+}
+
+/// Sets limits on configuration sources, for every thread: the size of a
+/// document in bytes, checked before it is parsed, and the number of keys
+/// and the length of any string or key in bytes, checked after. A source
+/// that exceeds a limit is rejected with `InternalError` and a message
+/// naming the limit. Passing 0 removes a limit; there are none by default.
+#[no_mangle]
+pub extern "C" fn via_config_set_size_limits(max_document_size: usize, max_keys: usize, max_string_length: usize) {
+    limits::set_size_limits(max_document_size, max_keys, max_string_length);
+}
+
+/// Restores a key to the value the system layer specified, discarding any
+/// hardware, profile or runtime override. A key that the system layer does
+/// not define is removed. The provenance of the affected leaves is updated
+/// accordingly. Inside an update opened with `via_config_begin_update`, the
+/// reset is staged until the update is committed.
+///
+/// Returns `InternalError` for handles that do not retain their source
+/// layers, i.e. those restored with `via_config_from_bytes` or from a cache.
+///
+/// # Safety
+/// All pointers must be valid, and `key_c` null-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_reset_key(config: *mut ViaConfig, key_c: *const c_char) -> ViaConfigStatus {
+    if config.is_null() || key_c.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &mut *config;
+    let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
+    if let Err(status) = config.ensure_mutable() {
+        return status;
+    }
+    let Some(system_layer) = config.layers.first() else {
+        return diagnostics::report(
+            ViaConfigStatus::InternalError,
+            "The configuration does not retain its source files and cannot be reset".to_string(),
+        );
+    };
+    if key.is_empty() {
+        return ViaConfigStatus::KeyNotFound;
+    }
+    if get_value_by_key(system_layer, key).is_none() && get_value_by_key(&config.staged_tree(), key).is_none() {
+        return ViaConfigStatus::KeyNotFound;
+    }
+
+    match &mut config.pending_update {
+        Some(pending) => pending.push((key.to_string(), StagedChange::Reset)),
+        None => config.apply_reset(key),
+    }
+    ViaConfigStatus::Ok
+}
+
+/// Compares the merged trees of two configurations for equality. Mapping key
+/// order is ignored; sequences must match element by element. Provenance,
+/// flags and registered callbacks are not compared.
+///
+/// # Safety
+/// All pointers must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_equals(
+    a: *const ViaConfig,
+    b: *const ViaConfig,
+    out_equal: *mut bool,
+) -> ViaConfigStatus {
+    if a.is_null() || b.is_null() || out_equal.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    // `Mapping` equality is that of a map: entries are matched by key, not position.
+    *out_equal = (*a).merged_value == (*b).merged_value;
+    ViaConfigStatus::Ok
+}
+
+/// The longest duration accepted, in milliseconds: the most that still fits
+/// a `u64` count of nanoseconds (about 584 years), so callers can convert
+/// any duration to a `timespec` or `Duration` without overflowing.
+const MAX_DURATION_MS: u64 = u64::MAX / 1_000_000;
+
+/// Parses a duration such as `500ms`, `30s`, `1h30m` or `2d` into
+/// milliseconds. Units are `ms`, `s`, `m`, `h` and `d`; components may be
+/// separated by spaces. All arithmetic is checked against
+/// `MAX_DURATION_MS`, so an absurd value such as `9999999999h` is an error
+/// rather than a wrapped result.
+fn parse_duration_ms(text: &str) -> Result<u64, String> {
+    let mut rest = text.trim();
+    if rest.is_empty() {
+        return Err("empty duration".to_string());
+    }
+    let overflow = || format!("duration {:?} is too large", text);
+    let mut total: u64 = 0;
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        if digits == 0 {
+            return Err(format!("expected a number in duration {:?}", text));
+        }
+        let amount: u64 = rest[..digits].parse().map_err(|_| overflow())?;
+        rest = &rest[digits..];
+
+        let (unit_ms, unit_len) = if rest.starts_with("ms") {
+            (1, 2)
+        } else {
+            match rest.chars().next() {
+                Some('s') => (1_000, 1),
+                Some('m') => (60_000, 1),
+                Some('h') => (3_600_000, 1),
+                Some('d') => (86_400_000, 1),
+                _ => return Err(format!("expected a unit (ms, s, m, h or d) in duration {:?}", text)),
+            }
+        };
+        rest = rest[unit_len..].trim_start();
+
+        let component = amount.checked_mul(unit_ms).ok_or_else(overflow)?;
+        total = total.checked_add(component).filter(|&total| total <= MAX_DURATION_MS).ok_or_else(overflow)?;
+    }
+    Ok(total)
+}
+
+/// Retrieves a duration in milliseconds. The value may be a string with unit
+/// suffixes (e.g. `"30s"`, `"1h30m"`) or a non-negative integer, which is
+/// read as milliseconds. Durations longer than `MAX_DURATION_MS` are
+/// rejected.
+///
+/// # Safety
+/// All pointers must be valid, and `key_c` null-terminated. Returns
+/// `TypeError` for malformed or overflowing durations, with details in
+/// `via_config_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_get_duration_ms(
+    config: *const ViaConfig,
+    key_c: *const c_char,
+    out_ms: *mut u64,
+) -> ViaConfigStatus {
+    if config.is_null() || key_c.is_null() || out_ms.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
+
+    let parsed = match config.lookup(key) {
+        Some(Value::Number(n)) => match n.as_u64() {
+            Some(ms) if ms > MAX_DURATION_MS => Err(format!("duration {} is too large", n)),
+            Some(ms) => Ok(ms),
+            None => Err(format!("duration {} is not a non-negative integer", n)),
+        },
+        Some(Value::String(s)) => parse_duration_ms(s),
+        Some(val) => return type_mismatch(key, "duration", val),
+        None => return ViaConfigStatus::KeyNotFound,
+    };
+    match parsed {
+        Ok(ms) => {
+            *out_ms = ms;
+            ViaConfigStatus::Ok
+        }
+        Err(message) => diagnostics::report(ViaConfigStatus::TypeError, format!("Invalid value for key '{}': {}", key, message)),
+    }
+}
+
+/// Creates an independent handle whose root is a copy of the mapping at
+/// `key_c`, so a module can be given just its own section and query it with
+/// relative keys (e.g. `camera.device-id` for the `hardware` section). The
+/// load flags, provenance and source layers are carried over for that
+/// section; validators and secret keys are not.
+///
+/// Returns null if the key is missing or not a mapping, with the reason
+/// available through `via_config_last_error_status`.
+///
+/// # Safety
+/// All pointers must be valid, and `key_c` null-terminated. The returned
+/// pointer must be freed with `via_config_free`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_subconfig(config: *const ViaConfig, key_c: *const c_char) -> *mut ViaConfig {
+    diagnostics::clear();
+    if config.is_null() {
+        diagnostics::report(ViaConfigStatus::NullArgument, "The config argument is null".to_string());
+        return std::ptr::null_mut();
+    }
+    let config = &*config;
+    let Some(key) = str_arg(key_c, "key") else { return std::ptr::null_mut(); };
+
+    let section = match get_value_by_key(&config.merged_value, key) {
+        Some(section @ Value::Mapping(_)) => section.clone(),
+        Some(_) => {
+            diagnostics::report(ViaConfigStatus::TypeError, format!("Key '{}' is not a mapping", key));
+            return std::ptr::null_mut();
+        }
+        None => {
+            diagnostics::report(ViaConfigStatus::KeyNotFound, format!("Key '{}' not found", key));
+            return std::ptr::null_mut();
+        }
+    };
+
+    let mut sub = ViaConfig::new(section, config.flags);
+    let prefix = format!("{}.", key);
+    sub.provenance = config
+        .provenance
+        .iter()
+        .filter_map(|(leaf_key, source)| Some((leaf_key.strip_prefix(&prefix)?.to_string(), *source)))
+        .collect();
+    sub.layers = config
+        .layers
+        .iter()
+        .map(|layer| get_value_by_key(layer, key).cloned().unwrap_or(Value::Null))
+        .collect();
+    Box::into_raw(Box::new(sub))
+}
+
+/// Checks that the files named by the given keys exist on disk. A key may
+/// hold a single path, or a mapping or sequence of paths (such as
+/// `perception.model-paths`), in which case every path in it is checked.
+/// Relative paths are resolved against the current working directory.
+///
+/// Returns `ValidationFailed` if any path is missing, or a key is missing or
+/// holds something other than paths; `out_report` then receives a
+/// newline-separated report, one line per problem. On `Ok`, `out_report` is
+/// set to null.
+///
+/// # Safety
+/// `config` and `out_report` must be valid, and `keys` must point to `count`
+/// valid, null-terminated C strings. A non-null report must be freed with
+/// `via_config_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_check_paths_exist(
+    config: *const ViaConfig,
+    keys: *const *const c_char,
+    count: usize,
+    out_report: *mut *mut c_char,
+) -> ViaConfigStatus {
+    if config.is_null() || out_report.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let keys = match borrow_c_string_array(keys, count) {
+        Ok(keys) => keys,
+        Err(status) => return status,
+    };
+
+    let mut problems = Vec::new();
+    for key in keys {
+        let Some(val) = get_value_by_key(&config.merged_value, key) else {
+            problems.push(format!("{}: key is missing", key));
+            continue;
+        };
+        let mut check = |leaf_key: &str, leaf: &Value| match leaf.as_str() {
+            Some(path) if Path::new(path).exists() => {}
+            Some(path) => problems.push(format!("{}: path {:?} does not exist", leaf_key, path)),
+            None => problems.push(format!("{}: value is not a path", leaf_key)),
+        };
+        match val {
+            Value::Mapping(_) => for_each_leaf(val, key, &mut check),
+            Value::Sequence(seq) => {
+                for (i, item) in seq.iter().enumerate() {
+                    check(&format!("{}[{}]", key, i), item);
+                }
+            }
+            _ => check(key, val),
+        }
+    }
+
+    if problems.is_empty() {
+        *out_report = std::ptr::null_mut();
+        return ViaConfigStatus::Ok;
+    }
+    let Some(report_c) = into_owned_c_string(problems.join("\n")) else { return ViaConfigStatus::InternalError; };
+    *out_report = report_c;
+    ViaConfigStatus::ValidationFailed
+}
+
+/// Rewrites every path separator in the strings of `val` to `separator`, and
+/// records in `issues` each path that mixes `/` and `\`, starts with a drive
+/// letter while normalizing to `/`, or shows signs of a lossy UTF-8 decode.
+/// `key` is the dot-key of `val`, used to name the offending value.
+fn normalize_path_value(val: &mut Value, key: &str, separator: char, issues: &mut Vec<String>) {
+    match val {
+        Value::String(path) => {
+            if path.contains('/') && path.contains('\\') {
+                issues.push(format!("{}: path {:?} mixes '/' and '\\' separators", key, path));
+            }
+            if separator == '/' && path.as_bytes().get(1) == Some(&b':') && path.as_bytes()[0].is_ascii_alphabetic() {
+                issues.push(format!("{}: path {:?} starts with a Windows drive letter", key, path));
+            }
+            if path.contains(['\u{fffd}', '\0']) {
+                issues.push(format!("{}: path {:?} is not valid UTF-8", key, path));
+            }
+            *path = path.replace(['/', '\\'], separator.encode_utf8(&mut [0; 4]));
+        }
+        Value::Sequence(seq) => {
+            for (i, item) in seq.iter_mut().enumerate() {
+                normalize_path_value(item, &format!("{}[{}]", key, i), separator, issues);
+            }
+        }
+        Value::Mapping(map) => {
+            for (k, v) in map.iter_mut() {
+                normalize_path_value(v, &child_key(key, &value_to_text(k)), separator, issues);
+            }
+        }
+        Value::Tagged(tagged) => normalize_path_value(&mut tagged.value, key, separator, issues),
+        _ => issues.push(format!("{}: value is not a path", key)),
+    }
+}
+
+/// Normalizes the path separators of the paths at the given keys to
+/// `separator` (`/` or `\`), so that Windows-authored values such as
+/// `models\yolo.onnx` work on every platform. A key may hold a single path,
+/// or a mapping or sequence of paths (such as `perception.model-paths`).
+/// Changed values are set like `via_config_set_string` does.
+///
+/// Returns `ValidationFailed` if a key is missing or holds something other
+/// than paths, or if a path mixes separators, starts with a drive letter
+/// while normalizing to `/`, or is not valid UTF-8; `out_report` then receives
+/// a newline-separated report, one line per problem. Every path is normalized
+/// either way. On `Ok`, `out_report` is set to null.
+///
+/// # Safety
+/// `config` and `out_report` must be valid, and `keys` must point to `count`
+/// valid, null-terminated C strings. A non-null report must be freed with
+/// `via_config_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_normalize_paths(
+    config: *mut ViaConfig,
+    keys: *const *const c_char,
+    count: usize,
+    separator: c_char,
+    out_report: *mut *mut c_char,
+) -> ViaConfigStatus {
+    if config.is_null() || out_report.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &mut *config;
+    let keys = match borrow_c_string_array(keys, count) {
+        Ok(keys) => keys,
+        Err(status) => return status,
+    };
+    let separator = separator as u8 as char;
+    if !matches!(separator, '/' | '\\') {
+        return diagnostics::report(ViaConfigStatus::InternalError, format!("Invalid path separator {:?}", separator));
+    }
+    if let Err(status) = config.ensure_mutable() {
+        return status;
+    }
+
+    let mut problems = Vec::new();
+    for key in keys {
+        let Some(original) = get_value_by_key(&config.merged_value, key) else {
+            problems.push(format!("{}: key is missing", key));
+            continue;
+        };
+        let mut normalized = original.clone();
+        normalize_path_value(&mut normalized, key, separator, &mut problems);
+        if normalized != *original {
+            if let Err(status) = config.set(key, normalized) {
+                return status;
+            }
+        }
+    }
+
+    if problems.is_empty() {
+        *out_report = std::ptr::null_mut();
+        return ViaConfigStatus::Ok;
+    }
+    let Some(report_c) = into_owned_c_string(problems.join("\n")) else { return ViaConfigStatus::InternalError; };
+    *out_report = report_c;
+    ViaConfigStatus::ValidationFailed
+}
+
+/// Describes the rules currently registered on the handle, one per line, for
+/// debugging: each validator (`validator: <key> at <address>`) in
+/// registration order, then each dependency rule
+/// (`dependency: <key> requires <key> [<bounds>] when <value>`), then each secret key
+/// (`secret: <pattern>`). The report is an empty string if no rule is
+/// registered.
+///
+/// # Safety
+/// All pointers must be valid. The returned string must be freed with
+/// `via_config_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_list_constraints(
+    config: *const ViaConfig,
+    out_report: *mut *mut c_char,
+) -> ViaConfigStatus {
+    if config.is_null() || out_report.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+
+    let validators = config.validators.iter().map(|(key, validator)| format!("validator: {} at {:p}", key, *validator as *const c_void));
+    let dependencies = config.dependency_rules.iter().map(|rule| {
+        match &rule.range {
+            Some(range) => format!(
+                "dependency: {} requires {} {} when {}",
+                rule.if_key,
+                rule.require_key,
+                range.describe(),
+                rule.if_equals
+            ),
+            None => format!("dependency: {} requires {} when {}", rule.if_key, rule.require_key, rule.if_equals),
+        }
+    });
+    let secrets = config.secret_keys.iter().map(|pattern| format!("secret: {}", pattern));
+    let report: Vec<String> = validators.chain(dependencies).chain(secrets).collect();
+
+    let Some(report_c) = into_owned_c_string(report.join("\n")) else { return ViaConfigStatus::InternalError; };
+    *out_report = report_c;
+    ViaConfigStatus::Ok
+}
+
+/// Parses an RFC 3339 timestamp (e.g. `2024-03-01T10:00:00Z`) or a plain date
+/// (e.g. `2024-03-01`, taken as midnight UTC) into Unix epoch seconds.
+fn parse_timestamp(text: &str) -> Option<i64> {
+    let text = text.trim();
+    if let Ok(timestamp) = chrono::DateTime::parse_from_rfc3339(text) {
+        return Some(timestamp.timestamp());
+    }
+    let date = chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d").ok()?;
+    Some(date.and_hms_opt(0, 0, 0)?.and_utc().timestamp())
+}
+
+/// Retrieves a timestamp as Unix epoch seconds. The value must be a string in
+/// RFC 3339 format (e.g. `2024-03-01T10:00:00Z`) or a plain date (e.g.
+/// `2024-03-01`, taken as midnight UTC).
+///
+/// # Safety
+/// All pointers must be valid, and `key_c` null-terminated. Returns
+/// `TypeError` for values that are not strings or cannot be parsed.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_get_timestamp(
+    config: *const ViaConfig,
+    key_c: *const c_char,
+    out_epoch_secs: *mut i64,
+) -> ViaConfigStatus {
+    if config.is_null() || key_c.is_null() || out_epoch_secs.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
+
+    match config.lookup(key) {
+        Some(val) => match val.as_str().and_then(parse_timestamp) {
+            Some(secs) => {
+                *out_epoch_secs = secs;
+                ViaConfigStatus::Ok
+            }
+            None => type_mismatch(key, "RFC 3339 timestamp or date", val),
+        },
+        None => ViaConfigStatus::KeyNotFound,
+    }
+}
+
+/// Confines every file the library reads or writes to `root_c` and the
+/// directories below it, for every thread. Paths are canonicalized before
+/// the check, so `..` components and symbolic links cannot escape the root.
+/// A path outside the root fails with `FileNotFound`. Passing null removes
+/// the confinement.
+///
+/// # Safety
+/// `root_c` must be null or a valid, null-terminated C string.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_set_root_jail(root_c: *const c_char) -> ViaConfigStatus {
+    let root = if root_c.is_null() {
+        None
+    } else {
+        let Ok(root) = CStr::from_ptr(root_c).to_str() else { return ViaConfigStatus::InternalError; };
+        Some(Path::new(root))
+    };
+    match jail::set_root(root) {
+        Ok(()) => ViaConfigStatus::Ok,
+        Err(e) => diagnostics::report(ViaConfigStatus::FileNotFound, format!("Root {:?} cannot be resolved: {}", root.unwrap_or(Path::new("")), e)),
+    }
+}
+
+/// Registers a fact about the device for `$when` conditions, e.g. `has_gpu`
+/// = `"true"` or `cpu_count` = `"8"`, for every thread. The value is read as
+/// a YAML scalar. Passing a null value removes the fact. Facts only affect
+/// files parsed afterwards.
+///
+/// Returns `InternalError` if the name cannot be used in an expression.
+///
+/// # Safety
+/// `name_c` must be a valid, null-terminated C string, and `value_c` null or
+/// one.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_set_fact(name_c: *const c_char, value_c: *const c_char) -> ViaConfigStatus {
+    if name_c.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let Ok(name) = CStr::from_ptr(name_c).to_str() else { return ViaConfigStatus::InternalError; };
+    let value = if value_c.is_null() {
+        None
+    } else {
+        let Ok(value) = CStr::from_ptr(value_c).to_str() else { return ViaConfigStatus::InternalError; };
+        Some(value)
+    };
+    if !conditions::is_fact_name(name) {
+        return diagnostics::report(ViaConfigStatus::InternalError, format!("{:?} is not a valid fact name", name));
+    }
+    conditions::set_fact(name, value);
+    ViaConfigStatus::Ok
+}
+
+/// Parses a color written as `"#RRGGBB"`/`"#RRGGBBAA"` or as a sequence of 3
+/// or 4 components in `0..=255`, and packs it as `0xRRGGBBAA`. The alpha
+/// channel defaults to 255 (opaque).
+fn parse_color(val: &Value) -> Result<u32, String> {
+    let components: Vec<u8> = match val {
+        Value::String(s) => {
+            let hex = s.trim().strip_prefix('#').ok_or_else(|| format!("color {:?} does not start with '#'", s))?;
+            if !matches!(hex.len(), 6 | 8) || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(format!("color {:?} is not of the form #RRGGBB or #RRGGBBAA", s));
+            }
+            (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap_or(0)).collect()
+        }
+        Value::Sequence(seq) if matches!(seq.len(), 3 | 4) => seq
+            .iter()
+            .map(|c| {
+                c.as_u64()
+                    .and_then(|c| u8::try_from(c).ok())
+                    .ok_or_else(|| format!("color component {:?} is not an integer in 0..=255", c))
+            })
+            .collect::<Result<_, _>>()?,
+        Value::Sequence(seq) => return Err(format!("color has {} components instead of 3 or 4", seq.len())),
+        _ => return Err("color is neither a string nor a sequence".to_string()),
+    };
+    let alpha = components.get(3).copied().unwrap_or(u8::MAX);
+    Ok(u32::from_be_bytes([components[0], components[1], components[2], alpha]))
+}
+
+/// Retrieves a color, written either as a hex string (`"#FF8800"`,
+/// `"#FF880080"`) or as a sequence of 3 or 4 components (`[255, 136, 0]`),
+/// packed into `0xRRGGBBAA`. A missing alpha component is 255.
+///
+/// # Safety
+/// All pointers must be valid, and `key_c` null-terminated. Returns
+/// `TypeError` for malformed colors, with details in
+/// `via_config_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_get_color(
+    config: *const ViaConfig,
+    key_c: *const c_char,
+    out_rgba: *mut u32,
+) -> ViaConfigStatus {
+    if config.is_null() || key_c.is_null() || out_rgba.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let Ok(key) = CStr::from_ptr(key_c).to_str() else { return ViaConfigStatus::InternalError; };
+
+    let Some(val) = config.lookup(key) else { return ViaConfigStatus::KeyNotFound; };
+    match parse_color(val) {
+        Ok(rgba) => {
+            *out_rgba = rgba;
+            ViaConfigStatus::Ok
+        }
+        Err(message) => diagnostics::report(ViaConfigStatus::TypeError, format!("Invalid value for key '{}': {}", key, message)),
+    }
+}
+
+/// An iterator over the entries of one mapping, created by
+/// `via_config_iter_create`. Exposed to C as an opaque pointer
+/// `ViaConfigIter*`.
+///
+/// The iterator holds a snapshot of the keys and value types of the mapping,
+/// not the values themselves, so it does not borrow the handle and stays
+/// valid if the handle is modified or freed.
+pub struct ViaConfigIter {
+    /// The entries not returned yet.
+    entries: std::vec::IntoIter<(CString, ViaConfigValueType)>,
+    /// The key most recently returned by `via_config_iter_next`.
+    current_key: CString,
+}
+
+/// Creates an iterator over the direct entries of the mapping at `key_c` (the
+/// root if empty), in document/merge order.
+///
+/// Returns null on failure: `KeyNotFound` if the key does not exist and
+/// `TypeError` if it is not a mapping, through `via_config_last_error_status`.
+///
+/// The entries are those of the mapping when the iterator is created; later
+/// changes to the handle do not affect it.
+///
+/// # Safety
+/// All pointers must be valid, and `key_c` null-terminated. The returned
+/// pointer must be freed with `via_config_iter_free`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_iter_create(config: *const ViaConfig, key_c: *const c_char) -> *mut ViaConfigIter {
+    diagnostics::clear();
+    if config.is_null() {
+        diagnostics::report(ViaConfigStatus::NullArgument, "The config argument is null".to_string());
+        return std::ptr::null_mut();
+    }
+    let config = &*config;
+    let Some(key) = str_arg(key_c, "key") else { return std::ptr::null_mut(); };
+
+    let map = match get_node(&config.merged_value, key) {
+        Some(Value::Mapping(map)) => map,
+        Some(_) => {
+            diagnostics::report(ViaConfigStatus::TypeError, format!("Key '{}' is not a mapping", key));
+            return std::ptr::null_mut();
+        }
+        None => {
+            diagnostics::report(ViaConfigStatus::KeyNotFound, format!("Key '{}' not found", key));
+            return std::ptr::null_mut();
+        }
+    };
+    // Same conversion as `to_c_message`: a key with a NUL byte cannot be returned whole.
+    let entries: Vec<(CString, ViaConfigValueType)> = map
+        .iter()
+        .map(|(k, v)| (CString::new(value_to_text(k).replace('\0', "")).unwrap_or_default(), ViaConfigValueType::of(v)))
+        .collect();
+    Box::into_raw(Box::new(ViaConfigIter { entries: entries.into_iter(), current_key: CString::default() }))
+}
+
+/// Advances the iterator. On success, `out_key` receives the entry's key
+/// (relative to the iterated mapping) and `out_value_type` the type of its
+/// value, which is `Mapping` for any nested mapping. Returns false once every
+/// entry has been returned, or if an argument is null.
+///
+/// # Safety
+/// All pointers must be valid. The string written to `out_key` is owned by
+/// the iterator and stays valid until the next call or `via_config_iter_free`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_iter_next(
+    iter: *mut ViaConfigIter,
+    out_key: *mut *const c_char,
+    out_value_type: *mut ViaConfigValueType,
+) -> bool {
+    if iter.is_null() || out_key.is_null() || out_value_type.is_null() {
+        return false;
+    }
+    let iter = &mut *iter;
+    let Some((key, value_type)) = iter.entries.next() else { return false };
+
+    iter.current_key = key;
+    *out_key = iter.current_key.as_ptr();
+    *out_value_type = value_type;
+    true
+}
+
+/// Frees an iterator created by `via_config_iter_create`.
+///
+/// # Safety
+/// `iter` must be a pointer returned by `via_config_iter_create` that has not
+/// been freed yet. Passing a null pointer is safe.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_iter_free(iter: *mut ViaConfigIter) {
+    if !iter.is_null() {
+        drop(Box::from_raw(iter));
+    }
+}
+
+/// Returns the leaf dot-keys whose value differs between two trees: those
+/// changed or added in `new`, in its order, followed by those only in `old`.
+fn changed_keys(old: &Value, new: &Value) -> Vec<String> {
+    let mut old_leaves = HashMap::new();
+    for_each_leaf(old, "", &mut |key, val| {
+        old_leaves.insert(key.to_string(), val.clone());
+    });
+    let mut changed = Vec::new();
+    for_each_leaf(new, "", &mut |key, val| {
+        if old_leaves.remove(key).as_ref() != Some(val) {
+            changed.push(key.to_string());
+        }
+    });
+    let mut removed: Vec<String> = old_leaves.into_keys().collect();
+    removed.sort();
+    changed.extend(removed);
+    changed
+}
+
+/// Re-reads the files the handle was loaded from and replaces its tree with
+/// the result. `out_changed_keys` receives the leaf dot-keys whose value was
+/// changed, added or removed, so that only the affected subsystems need to be
+/// reconfigured. Load flags, validators and secret keys are kept; values set
+/// at runtime are discarded.
+///
+/// If the files cannot be loaded, the handle is left unchanged and the error
+/// is returned, with details through `via_config_last_error_message`. Handles
+/// that were not loaded from the system, hardware and profile files (e.g.
+/// from strings or bytes) fail with `InternalError`.
+///
+/// # Safety
+/// All pointers must be valid. On success `out_changed_keys` must be freed
+/// with `via_config_free_string_array`. Strings previously returned by
+/// `via_config_get_string` become invalid.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_reload_with_changes(
+    config: *mut ViaConfig,
+    out_changed_keys: *mut *mut *mut c_char,
+    out_len: *mut usize,
+) -> ViaConfigStatus {
+    diagnostics::clear();
+    if config.is_null() || out_changed_keys.is_null() || out_len.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &mut *config;
+    if let Err(status) = config.ensure_mutable() {
+        return status;
+    }
+    if config.pending_update.is_some() {
+        return diagnostics::report(ViaConfigStatus::InternalError, "An update is in progress".to_string());
+    }
+    if config.source_paths.is_empty() {
+        return diagnostics::report(
+            ViaConfigStatus::InternalError,
+            "The configuration was not loaded from files and cannot be reloaded".to_string(),
+        );
+    }
+
+    let paths: Vec<&Path> = config.source_paths.iter().map(PathBuf::as_path).collect();
+    let reloaded = match load_layers(&paths, config.flags) {
+        Ok(reloaded) => reloaded,
+        Err(status) => return status,
+    };
+    let changed = changed_keys(&config.merged_value, &reloaded.merged_value);
+    let Some((keys_array, len)) = into_owned_c_string_array(changed) else { return ViaConfigStatus::InternalError; };
+
+    config.merged_value = reloaded.merged_value;
+    config.provenance = reloaded.provenance;
+    config.layers = reloaded.layers;
+    config.cached = false;
+    config.load_timings = reloaded.load_timings;
+    config.overrides = reloaded.overrides;
+    config.runtime_origins.clear();
+    config.parent_profiles = reloaded.parent_profiles;
+    for key in reloaded.secret_keys {
+        if !config.secret_keys.contains(&key) {
+            config.secret_keys.push(key);
+        }
+    }
+    *out_changed_keys = keys_array;
+    *out_len = len;
+    ViaConfigStatus::Ok
+}
+
+/// Loads two sets of system, hardware and profile files, e.g. from two
+/// branches, and reports how the merged configurations differ, one line per
+/// leaf key as `key: a -> b`, with `(unset)` for a side that lacks the key.
+/// Changed and added keys come first, in the order of the second set, then
+/// keys only the first set has. The values of keys that are secret in either
+/// set, such as those read with `$secret_file`, are shown as `***`.
+/// `out_report` is set to null if the merged configurations are equal. Both
+/// configurations are freed before returning.
+///
+/// If either set cannot be loaded, its error is returned, with details
+/// through `via_config_last_error_message`.
+///
+/// # Safety
+/// All path arguments must be valid, null-terminated C strings, and
+/// `out_report` a valid pointer. A non-null report must be freed with
+/// `via_config_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_diff_files(
+    system_a_c: *const c_char,
+    hardware_a_c: *const c_char,
+    profile_a_c: *const c_char,
+    system_b_c: *const c_char,
+    hardware_b_c: *const c_char,
+    profile_b_c: *const c_char,
+    out_report: *mut *mut c_char,
+) -> ViaConfigStatus {
+    diagnostics::clear();
+    if out_report.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let load = |system_c, hardware_c, profile_c| {
+        let system_path = str_arg(system_c, "system path").ok_or_else(diagnostics::last_status)?;
+        let hardware_path = str_arg(hardware_c, "hardware path").ok_or_else(diagnostics::last_status)?;
+        let profile_path = str_arg(profile_c, "profile path").ok_or_else(diagnostics::last_status)?;
+        load_files(Path::new(system_path), Path::new(hardware_path), Path::new(profile_path), 0)
+    };
+    let a = match load(system_a_c, hardware_a_c, profile_a_c) {
+        Ok(config) => config,
+        Err(status) => return status,
+    };
+    let b = match load(system_b_c, hardware_b_c, profile_b_c) {
+        Ok(config) => config,
+        Err(status) => return status,
+    };
+
+    // A key that is secret in either set is redacted on both sides.
+    let secret_keys: Vec<String> = a.secret_keys.iter().chain(&b.secret_keys).cloned().collect();
+    let show = |config: &ViaConfig, key: &str| {
+        get_value_by_key(&config.merged_value, key).map_or_else(|| "(unset)".to_string(), |val| redacted_text(key, val, &secret_keys))
+    };
+    let report: Vec<String> = changed_keys(&a.merged_value, &b.merged_value)
+        .iter()
+        .map(|key| format!("{}: {} -> {}", key, show(&a, key), show(&b, key)))
+        .collect();
+    if report.is_empty() {
+        *out_report = std::ptr::null_mut();
+        return ViaConfigStatus::Ok;
+    }
+    let Some(report_c) = into_owned_c_string(report.join("\n")) else { return ViaConfigStatus::InternalError; };
+    *out_report = report_c;
+    ViaConfigStatus::Ok
+}
+
+/// Writes the effective configuration, i.e. the merged tree including
+/// interpolated values and runtime overrides, to `out_path_c` as YAML, for
+/// audit logs and for reproducing a device's state. Loading the file as the
+/// system file (with empty hardware and profile files) gives back an equal
+/// configuration, so values of secret keys are written too; the file is
+/// created with mode 0600 on Unix and replaced atomically. Use
+/// `via_config_dump_yaml` for a redacted copy to share.
+///
+/// # Safety
+/// All pointers must be valid, and `out_path_c` null-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_write_effective(config: *const ViaConfig, out_path_c: *const c_char) -> ViaConfigStatus {
+    diagnostics::clear();
+    if config.is_null() || out_path_c.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+    let Ok(path) = CStr::from_ptr(out_path_c).to_str() else { return ViaConfigStatus::InternalError; };
+    let path = Path::new(path);
+
+    // The file usually does not exist yet, so confine its directory instead.
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    if let Err(message) = jail::check(dir) {
+        return diagnostics::report(ViaConfigStatus::FileNotFound, message);
+    }
+    let Ok(yaml) = serde_yaml::to_string(&config.merged_value) else { return ViaConfigStatus::InternalError; };
+    if let Err(e) = write_private_file_atomically(path, yaml.as_bytes()) {
+        return diagnostics::report(ViaConfigStatus::InternalError, format!("Failed to write file {:?}: {}", path, e));
+    }
+    ViaConfigStatus::Ok
+}
+
+/// Upgrades a configuration written for an older schema to `target_version`
+/// (0 for the current schema), applying the migrations in `migrate.rs` in
+/// order and updating `schema-version`. A configuration without
+/// `schema-version` is version 1. `out_report` receives one line per change,
+/// e.g. `2: renamed 'max-threads' to 'threads.perception'`, or null if the
+/// configuration was already at the target version.
+///
+/// Only the handle is changed; the files on disk are not. Migrated keys are
+/// reported by `via_config_get_source` as set at runtime.
+///
+/// # Safety
+/// All pointers must be valid. A non-null `out_report` must be freed with
+/// `via_config_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_migrate(config: *mut ViaConfig, target_version: u32, out_report: *mut *mut c_char) -> ViaConfigStatus {
+    diagnostics::clear();
+    if config.is_null() || out_report.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &mut *config;
+    if let Err(status) = config.ensure_mutable() {
+        return status;
+    }
+    if config.pending_update.is_some() {
+        return diagnostics::report(ViaConfigStatus::InternalError, "An update is in progress".to_string());
+    }
+    let version = match migrate::version_of(&config.merged_value) {
+        Ok(version) => version,
+        Err(message) => return diagnostics::report(ViaConfigStatus::TypeError, message),
+    };
+    let target = if target_version == 0 { migrate::CURRENT_VERSION } else { target_version };
+    if target > migrate::CURRENT_VERSION {
+        return diagnostics::report(
+            ViaConfigStatus::InternalError,
+            format!("Schema version {} is newer than the supported version {}", target, migrate::CURRENT_VERSION),
+        );
+    }
+    if target < version {
+        return diagnostics::report(
+            ViaConfigStatus::InternalError,
+            format!("The configuration is at schema version {}; it cannot be downgraded to {}", version, target),
+        );
+    }
+    if target == version {
+        *out_report = std::ptr::null_mut();
+        return ViaConfigStatus::Ok;
+    }
+
+    let mut report = Vec::new();
+    for migration in migrate::between(version, target) {
+        for step in migration.steps {
+            match *step {
+                migrate::Step::Rename { from, to } => {
+                    let Some(val) = get_value_by_key(&config.merged_value, from).cloned() else { continue };
+                    config.apply_removal(from);
+                    if get_value_by_key(&config.merged_value, to).is_some() {
+                        report.push(format!("{}: removed '{}' ('{}' is already set)", migration.version, from, to));
+                    } else {
+                        set_value_by_key(&mut config.merged_value, to, val);
+                        config.mark_runtime(to);
+                        report.push(format!("{}: renamed '{}' to '{}'", migration.version, from, to));
+                    }
+                }
+            }
+        }
+    }
+    set_value_by_key(&mut config.merged_value, migrate::VERSION_KEY, Value::from(target));
+    config.mark_runtime(migrate::VERSION_KEY);
+
+    let report = if report.is_empty() { format!("{}: no changes", target) } else { report.join("\n") };
+    let Some(report_c) = into_owned_c_string(report) else { return ViaConfigStatus::InternalError; };
+    *out_report = report_c;
+    ViaConfigStatus::Ok
+}
+
+/// Checks whether this library can read a configuration as it is, by
+/// comparing its `schema-version` with the supported versions. `out_report`
+/// always receives a description:
+///
+/// - `Ok`: the configuration is at the current version.
+/// - `ValidationFailed`: it is older and needs `via_config_migrate`; the
+///   report lists each step of the pending migrations.
+/// - `InternalError`: it is newer than this library supports, so it cannot
+///   be read correctly until the library is upgraded.
+/// - `TypeError`: `schema-version` is not a positive integer.
+///
+/// # Safety
+/// All pointers must be valid. `out_report` must be freed with
+/// `via_config_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn via_config_check_compatibility(config: *const ViaConfig, out_report: *mut *mut c_char) -> ViaConfigStatus {
+    if config.is_null() || out_report.is_null() {
+        return ViaConfigStatus::NullArgument;
+    }
+    let config = &*config;
+
+    let current = migrate::CURRENT_VERSION;
+    let (status, report) = match migrate::version_of(&config.merged_value) {
+        Err(message) => (ViaConfigStatus::TypeError, message),
+        Ok(version) if version == current => {
+            (ViaConfigStatus::Ok, format!("Schema version {} is the current version; the configuration is compatible", version))
+        }
+        Ok(version) if version > current => (
+            ViaConfigStatus::InternalError,
+            format!("Schema version {} is newer than {}, the newest this library supports; upgrade the library", version, current),
+        ),
+        Ok(version) => {
+            let mut lines = vec![format!("Schema version {} is older than the current version {}; migrate it with:", version, current)];
+            for migration in migrate::between(version, current) {
+                for step in migration.steps {
+                    match *step {
+                        migrate::Step::Rename { from, to } => {
+                            lines.push(format!("{}: rename '{}' to '{}'", migration.version, from, to));
+                        }
+                    }
+                }
+            }
+            (ViaConfigStatus::ValidationFailed, lines.join("\n"))
+        }
+    };
+    let Some(report_c) = into_owned_c_string(report) else { return ViaConfigStatus::InternalError; };
+    *out_report = report_c;
+    status
 }
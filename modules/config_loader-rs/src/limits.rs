@@ -0,0 +1,144 @@
+/**
+ * @file limits.rs
+ * @author TrackieLLM Rust Team
+ * @brief Limits on the size and shape of configuration sources.
+ *
+ * @copyright Copyright (c) 2024
+ *
+ * Merging, flattening, dumping and most other operations on the tree are
+ * recursive, so an extremely deep tree from a malformed or adversarial source
+ * could overflow the stack. Every way a tree can enter a handle (parsing,
+ * decoding a binary buffer, setting a key) is therefore checked against a
+ * process-wide maximum depth, and everything downstream can recurse safely.
+ *
+ * The check itself is iterative, so it is safe on trees of any depth.
+ *
+ * Configuration may also come from semi-trusted sources, so the size of a
+ * document, the number of keys in a tree and the length of its strings can
+ * be limited as well. These limits are off by default. The document size is
+ * checked before parsing, which bounds the memory the parser can use; the
+ * others are checked along with the depth.
+ */
+
+use serde_yaml::Value;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The default maximum depth. It matches the recursion limit of the YAML
+/// parser, so the check never rejects a document the parser accepts.
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+static MAX_DEPTH: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_DEPTH);
+/// The maximum size of a document in bytes, or 0 for no limit.
+static MAX_DOCUMENT_SIZE: AtomicUsize = AtomicUsize::new(0);
+/// The maximum number of mapping entries in a tree, or 0 for no limit.
+static MAX_KEYS: AtomicUsize = AtomicUsize::new(0);
+/// The maximum length in bytes of a string or key, or 0 for no limit.
+static MAX_STRING_LENGTH: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns the current maximum depth.
+pub fn max_depth() -> usize {
+    MAX_DEPTH.load(Ordering::Relaxed)
+}
+
+/// Sets the maximum depth; 0 restores the default.
+pub fn set_max_depth(depth: usize) {
+    let depth = if depth == 0 { DEFAULT_MAX_DEPTH } else { depth };
+    MAX_DEPTH.store(depth, Ordering::Relaxed);
+}
+
+/// Sets the size limits; 0 removes a limit.
+pub fn set_size_limits(document_size: usize, keys: usize, string_length: usize) {
+    MAX_DOCUMENT_SIZE.store(document_size, Ordering::Relaxed);
+    MAX_KEYS.store(keys, Ordering::Relaxed);
+    MAX_STRING_LENGTH.store(string_length, Ordering::Relaxed);
+}
+
+/// Feeds the current limits to `hasher`, so that a cached configuration
+/// checked under other limits is not reused.
+pub fn hash_limits<H: Hasher>(hasher: &mut H) {
+    for limit in [&MAX_DEPTH, &MAX_DOCUMENT_SIZE, &MAX_KEYS, &MAX_STRING_LENGTH] {
+        limit.load(Ordering::Relaxed).hash(hasher);
+    }
+}
+
+/// Fails with a descriptive message if a document of `len` bytes is larger
+/// than the maximum. `origin` names the source in the message.
+pub fn check_document_size(len: usize, origin: &str) -> Result<(), String> {
+    let max = MAX_DOCUMENT_SIZE.load(Ordering::Relaxed);
+    if max != 0 && len > max {
+        return Err(format!("{} is {} bytes long, more than the maximum of {}", origin, len, max));
+    }
+    Ok(())
+}
+
+/// Returns the nesting depth of a tree: 0 for a scalar, plus one for each
+/// level of sequence, mapping or tag.
+pub fn depth_of(val: &Value) -> usize {
+    let mut deepest = 0;
+    let mut pending = vec![(val, 0)];
+    while let Some((val, depth)) = pending.pop() {
+        deepest = deepest.max(depth);
+        match val {
+            Value::Sequence(seq) => pending.extend(seq.iter().map(|v| (v, depth + 1))),
+            Value::Mapping(map) => {
+                pending.extend(map.iter().flat_map(|(k, v)| [(k, depth + 1), (v, depth + 1)]));
+            }
+            Value::Tagged(tagged) => pending.push((&tagged.value, depth + 1)),
+            _ => {}
+        }
+    }
+    deepest
+}
+
+/// Fails with a descriptive message if `val` is nested deeper than the
+/// maximum, or exceeds the key count or string length limits. `origin` names
+/// the source in the message.
+pub fn check_tree(val: &Value, origin: &str) -> Result<(), String> {
+    let depth = depth_of(val);
+    if depth > max_depth() {
+        return Err(format!("{} is nested {} levels deep, more than the maximum of {}", origin, depth, max_depth()));
+    }
+    let max_keys = MAX_KEYS.load(Ordering::Relaxed);
+    let max_string_length = MAX_STRING_LENGTH.load(Ordering::Relaxed);
+    if max_keys == 0 && max_string_length == 0 {
+        return Ok(());
+    }
+    let mut keys = 0;
+    let mut pending = vec![val];
+    while let Some(val) = pending.pop() {
+        match val {
+            Value::String(s) if max_string_length != 0 && s.len() > max_string_length => {
+                let start: String = s.chars().take(20).collect();
+                return Err(format!(
+                    "{} holds a string of {} bytes starting with {:?}, more than the maximum of {}",
+                    origin,
+                    s.len(),
+                    start,
+                    max_string_length
+                ));
+            }
+            Value::Sequence(seq) => pending.extend(seq),
+            Value::Mapping(map) => {
+                keys += map.len();
+                if max_keys != 0 && keys > max_keys {
+                    return Err(format!("{} has more than the maximum of {} keys", origin, max_keys));
+                }
+                pending.extend(map.iter().flat_map(|(k, v)| [k, v]));
+            }
+            Value::Tagged(tagged) => pending.push(&tagged.value),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Fails with a descriptive message if setting the dot-separated `key` would
+/// nest the tree deeper than the maximum.
+pub fn check_key(key: &str) -> Result<(), String> {
+    let depth = crate::key_segments(key).len();
+    if depth > max_depth() {
+        return Err(format!("Key '{}' has {} segments, more than the maximum depth of {}", key, depth, max_depth()));
+    }
+    Ok(())
+}
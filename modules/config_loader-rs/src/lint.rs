@@ -0,0 +1,126 @@
+/**
+ * @file lint.rs
+ * @author TrackieLLM Rust Team
+ * @brief Pre-parse checks and normalization of raw YAML configuration text.
+ *
+ * @copyright Copyright (c) 2024
+ *
+ * `serde_yaml` reports most authoring mistakes as a generic parse failure.
+ * The checks in this module run on the raw file content before parsing so
+ * that common mistakes can be reported with a specific, actionable message.
+ */
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+/// Strips a leading UTF-8 byte order mark and converts CRLF line endings to
+/// LF, so that files authored on Windows parse the same as any other.
+pub fn normalize_text(text: &str) -> Cow<'_, str> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    if text.contains('\r') {
+        Cow::Owned(text.replace("\r\n", "\n"))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Returns true for characters that terminate an anchor or alias name.
+fn ends_name(c: char) -> bool {
+    c.is_whitespace() || matches!(c, ',' | '[' | ']' | '{' | '}')
+}
+
+/// Returns true if a block scalar indicator (`|` or `>`, with optional
+/// chomping/indentation modifiers) ends the given line content.
+fn opens_block_scalar(content: &str) -> bool {
+    let last_token = content.split_whitespace().last().unwrap_or("");
+    last_token.starts_with(['|', '>'])
+        && last_token[1..].chars().all(|c| matches!(c, '+' | '-') || c.is_ascii_digit())
+}
+
+/// Scans a YAML document for aliases (`*name`) that reference an anchor
+/// (`&name`) which has not been defined earlier in the same file.
+///
+/// Returns the 1-based line number and the alias name of the first dangling
+/// alias, if any. Quoted strings, comments and block scalars are skipped.
+pub fn find_dangling_alias(text: &str) -> Option<(usize, String)> {
+    let mut anchors = HashSet::new();
+    let mut block_scalar_indent: Option<usize> = None;
+
+    for (index, line) in text.lines().enumerate() {
+        let indent = line.len() - line.trim_start().len();
+        if let Some(block_indent) = block_scalar_indent {
+            if line.trim().is_empty() || indent > block_indent {
+                continue;
+            }
+            block_scalar_indent = None;
+        }
+
+        let mut in_single = false;
+        let mut in_double = false;
+        let mut prev = ' ';
+        let mut content_end = line.len();
+        let mut chars = line.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\'' if !in_double => in_single = !in_single,
+                '"' if !in_single && prev != '\\' => in_double = !in_double,
+                '#' if !in_single && !in_double && prev.is_whitespace() => {
+                    content_end = i;
+                    break;
+                }
+                '&' | '*' if !in_single && !in_double && (prev.is_whitespace() || matches!(prev, '[' | '{' | ',')) => {
+                    let name: String = line[i + 1..].chars().take_while(|&n| !ends_name(n)).collect();
+                    if !name.is_empty() {
+                        if c == '&' {
+                            anchors.insert(name.clone());
+                        } else if !anchors.contains(&name) {
+                            return Some((index + 1, name));
+                        }
+                        for _ in 0..name.chars().count() {
+                            chars.next();
+                        }
+                    }
+                }
+                _ => {}
+            }
+            prev = c;
+        }
+
+        if !in_single && !in_double && opens_block_scalar(&line[..content_end]) {
+            block_scalar_indent = Some(indent);
+        }
+    }
+    None
+}
+
+/// Scans a YAML document for tab characters in the indentation of a line,
+/// which YAML forbids and `serde_yaml` reports only as a generic scanner error.
+///
+/// Returns the 1-based line number of the first offending line, if any.
+/// Comment-only lines, blank lines and block scalar content are skipped.
+pub fn find_tab_indentation(text: &str) -> Option<usize> {
+    let mut block_scalar_indent: Option<usize> = None;
+
+    for (index, line) in text.lines().enumerate() {
+        let body = line.trim_start();
+        let leading = &line[..line.len() - body.len()];
+        if let Some(block_indent) = block_scalar_indent {
+            if body.is_empty() || leading.len() > block_indent {
+                continue;
+            }
+            block_scalar_indent = None;
+        }
+        if body.is_empty() || body.starts_with('#') {
+            continue;
+        }
+        if leading.contains('\t') {
+            return Some(index + 1);
+        }
+
+        let content = body.split(" #").next().unwrap_or(body);
+        if opens_block_scalar(content) {
+            block_scalar_indent = Some(leading.len());
+        }
+    }
+    None
+}
@@ -0,0 +1,73 @@
+/**
+ * @file messages.rs
+ * @author TrackieLLM Rust Team
+ * @brief Localized, human-readable messages for `ViaConfigStatus` codes.
+ *
+ * @copyright Copyright (c) 2024
+ *
+ * The C++ UI surfaces status strings directly in user-facing dialogs, so they
+ * must follow the user's language. The active locale is stored per thread and
+ * defaults to English when it has never been set.
+ */
+
+use crate::ViaConfigStatus;
+use std::cell::Cell;
+
+/// The languages for which status messages are available.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    En,
+    PtBr,
+}
+
+impl Locale {
+    /// Maps a locale tag such as `"pt-BR"`, `"pt_BR"` or `"en-US"` to a
+    /// supported locale. Unknown tags fall back to English.
+    pub fn from_tag(tag: &str) -> Locale {
+        let language = tag.split(['-', '_']).next().unwrap_or("");
+        if language.eq_ignore_ascii_case("pt") {
+            Locale::PtBr
+        } else {
+            Locale::En
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT_LOCALE: Cell<Locale> = const { Cell::new(Locale::En) };
+}
+
+/// Sets the locale used for status messages on the calling thread.
+pub fn set_locale(locale: Locale) {
+    CURRENT_LOCALE.with(|l| l.set(locale));
+}
+
+/// Returns the locale used for status messages on the calling thread.
+pub fn current_locale() -> Locale {
+    CURRENT_LOCALE.with(|l| l.get())
+}
+
+/// Returns the null-terminated message for `status` in the given locale.
+pub fn status_message(status: ViaConfigStatus, locale: Locale) -> &'static [u8] {
+    match (locale, status) {
+        (Locale::En, ViaConfigStatus::Ok) => b"Ok\0",
+        (Locale::En, ViaConfigStatus::FileNotFound) => b"Error: File not found\0",
+        (Locale::En, ViaConfigStatus::ParseError) => b"Error: Could not parse YAML file\0",
+        (Locale::En, ViaConfigStatus::KeyNotFound) => b"Error: The requested key was not found\0",
+        (Locale::En, ViaConfigStatus::TypeError) => b"Error: Value has an unexpected type\0",
+        (Locale::En, ViaConfigStatus::NullArgument) => b"Error: A null argument was provided\0",
+        (Locale::En, ViaConfigStatus::InternalError) => b"Error: An internal error occurred in the Rust library\0",
+        (Locale::En, ViaConfigStatus::ValidationFailed) => b"Error: The configuration failed validation\0",
+        (Locale::En, ViaConfigStatus::Frozen) => b"Error: The configuration is frozen and cannot be modified\0",
+
+        (Locale::PtBr, ViaConfigStatus::Ok) => b"Ok\0",
+        (Locale::PtBr, ViaConfigStatus::FileNotFound) => "Erro: Arquivo não encontrado\0".as_bytes(),
+        (Locale::PtBr, ViaConfigStatus::ParseError) => "Erro: Não foi possível interpretar o arquivo YAML\0".as_bytes(),
+        (Locale::PtBr, ViaConfigStatus::KeyNotFound) => "Erro: A chave solicitada não foi encontrada\0".as_bytes(),
+        (Locale::PtBr, ViaConfigStatus::TypeError) => "Erro: O valor tem um tipo inesperado\0".as_bytes(),
+        (Locale::PtBr, ViaConfigStatus::NullArgument) => "Erro: Um argumento nulo foi fornecido\0".as_bytes(),
+        (Locale::PtBr, ViaConfigStatus::InternalError) => "Erro: Ocorreu um erro interno na biblioteca Rust\0".as_bytes(),
+        (Locale::PtBr, ViaConfigStatus::ValidationFailed) => "Erro: A configuração não passou na validação\0".as_bytes(),
+        (Locale::PtBr, ViaConfigStatus::Frozen) => "Erro: A configuração está congelada e não pode ser modificada\0".as_bytes(),
+    }
+}
@@ -0,0 +1,61 @@
+/**
+ * @file metrics.rs
+ * @author TrackieLLM Rust Team
+ * @brief Per-key read counts, to see how the configuration is used.
+ *
+ * @copyright Copyright (c) 2024
+ *
+ * With `VIA_CONFIG_FLAG_RECORD_METRICS`, every lookup made by a typed getter
+ * is counted per key, along with how many of those lookups found no value.
+ * The report shows the hot keys worth caching on the C side, and lists the
+ * settings nobody reads with a count of zero.
+ *
+ * Getters take a shared handle and may run on several threads at once, so
+ * the counts are kept behind a mutex. Handles loaded without the flag never
+ * touch it.
+ */
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// The lookups of one key.
+#[derive(Debug, Default, Clone, Copy)]
+struct Counts {
+    reads: u64,
+    not_found: u64,
+}
+
+/// The counts of a handle loaded with `VIA_CONFIG_FLAG_RECORD_METRICS`.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    counts: Mutex<HashMap<String, Counts>>,
+}
+
+impl Metrics {
+    /// Counts one lookup of `key`.
+    pub fn record(&self, key: &str, found: bool) {
+        let mut counts = self.counts.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = match counts.get_mut(key) {
+            Some(entry) => entry,
+            None => counts.entry(key.to_string()).or_default(),
+        };
+        entry.reads += 1;
+        entry.not_found += u64::from(!found);
+    }
+
+    /// Formats the counts one key per line as `key: N reads, M not found`,
+    /// most read first. Every key of `leaf_keys` that was never read is
+    /// included with a count of zero.
+    pub fn report(&self, leaf_keys: &[String]) -> String {
+        let mut counts = self.counts.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        for key in leaf_keys {
+            counts.entry(key.clone()).or_default();
+        }
+        let mut rows: Vec<(String, Counts)> = counts.into_iter().collect();
+        rows.sort_by(|(a_key, a), (b_key, b)| b.reads.cmp(&a.reads).then_with(|| a_key.cmp(b_key)));
+        rows.iter()
+            .map(|(key, c)| format!("{}: {} reads, {} not found", key, c.reads, c.not_found))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
@@ -0,0 +1,72 @@
+/**
+ * @file migrate.rs
+ * @author TrackieLLM Rust Team
+ * @brief Versioned upgrades of configurations written for an older schema.
+ *
+ * @copyright Copyright (c) 2024
+ *
+ * A configuration records the schema it was written for in the top-level
+ * `schema-version` key; one without it predates versioning and is version 1.
+ * Each entry of `MIGRATIONS` upgrades a configuration by one version, and
+ * `via_config_migrate` applies them in order up to the requested version.
+ *
+ * A schema change that moves or renames settings is recorded here as a new
+ * migration with the next version number, and `CURRENT_VERSION` is bumped to
+ * match. Migrations are never edited once released, since deployed
+ * configurations may be at any earlier version.
+ */
+
+use serde_yaml::Value;
+
+/// The top-level key holding the schema version of a configuration.
+pub const VERSION_KEY: &str = "schema-version";
+
+/// The version of configurations without a `schema-version` key.
+const UNVERSIONED: u32 = 1;
+
+/// The schema version this library reads.
+pub const CURRENT_VERSION: u32 = 3;
+
+/// A single change made by a migration.
+pub enum Step {
+    /// Moves the value (or subtree) at `from` to `to`. If `to` is already
+    /// set, it is kept and the value at `from` is dropped.
+    Rename { from: &'static str, to: &'static str },
+}
+
+/// Upgrades a configuration from `version - 1` to `version`.
+pub struct Migration {
+    pub version: u32,
+    pub steps: &'static [Step],
+}
+
+/// Every migration, in version order.
+pub const MIGRATIONS: &[Migration] = &[
+    // The thread count of the perception pipeline used to be the only one.
+    Migration { version: 2, steps: &[Step::Rename { from: "max-threads", to: "threads.perception" }] },
+    // The camera resolution moved into its own section.
+    Migration {
+        version: 3,
+        steps: &[
+            Step::Rename { from: "camera.width", to: "camera.resolution.width" },
+            Step::Rename { from: "camera.height", to: "camera.resolution.height" },
+        ],
+    },
+];
+
+/// Returns the schema version of a configuration tree.
+pub fn version_of(root: &Value) -> Result<u32, String> {
+    match root.get(VERSION_KEY) {
+        None | Some(Value::Null) => Ok(UNVERSIONED),
+        Some(val) => val
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|&v| v >= UNVERSIONED)
+            .ok_or_else(|| format!("`{}` must be a positive integer", VERSION_KEY)),
+    }
+}
+
+/// Returns the migrations that upgrade a configuration from `from` to `to`.
+pub fn between(from: u32, to: u32) -> impl Iterator<Item = &'static Migration> {
+    MIGRATIONS.iter().filter(move |m| m.version > from && m.version <= to)
+}
@@ -12,21 +12,30 @@
  *
  * While the main `lib.rs` uses a dynamic `serde_yaml::Value` for flexible key
  * lookups, these models ensure that the underlying configuration format is
- * well-defined and consistent.
+ * well-defined and consistent. They also derive `JsonSchema`, so the same
+ * definitions back the JSON Schema exported by `via_config_export_schema`.
  */
 
+use schemars::JsonSchema;
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 // ============================================================================
 // Top-Level Merged Configuration Structure
 // ============================================================================
 
 /// Represents the final, merged configuration from all source files.
-#[derive(Debug, Deserialize)]
+///
+/// The files are merged side by side, so the settings of each one sit at the
+/// root of the merged tree (`log-level`, `camera`, `user-name`, ...) rather
+/// than under a section named after the file.
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct MergedConfig {
+    #[serde(flatten)]
     pub system: SystemConfig,
+    #[serde(flatten)]
     pub hardware: HardwareConfig,
+    #[serde(flatten)]
     pub profile: ProfileConfig,
 }
 
@@ -34,14 +43,14 @@ pub struct MergedConfig {
 // Structure for `system.default.yml`
 // ============================================================================
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub struct SystemConfig {
     pub log_level: String,
     pub threads: ThreadConfig,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct ThreadConfig {
     pub perception: u32,
     pub reasoning: u32,
@@ -52,7 +61,7 @@ pub struct ThreadConfig {
 // Structure for `hardware.default.yml`
 // ============================================================================
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub struct HardwareConfig {
     pub camera: CameraConfig,
@@ -61,19 +70,20 @@ pub struct HardwareConfig {
     pub reasoning: ReasoningHardwareConfig,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
 pub struct CameraConfig {
     pub device_id: i32,
     pub resolution: Resolution,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct Resolution {
     pub width: u32,
     pub height: u32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub struct MicrophoneConfig {
     pub device_id: i32,
@@ -81,25 +91,29 @@ pub struct MicrophoneConfig {
     pub noise_filter: NoiseFilterConfig,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
 pub struct NoiseFilterConfig {
     pub enabled: bool,
     pub window_size: u32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
 pub struct PerceptionHardwareConfig {
-    pub model_paths: HashMap<String, String>,
-    pub thresholds: HashMap<String, f32>,
+    // Ordered maps, so that anything derived from these tables (debug output,
+    // schemas, hashes) does not depend on a per-process hash seed.
+    pub model_paths: BTreeMap<String, String>,
+    pub thresholds: BTreeMap<String, f32>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub struct ReasoningHardwareConfig {
     pub llm: LlmConfig,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub struct LlmConfig {
     pub model_path: String,
@@ -110,7 +124,7 @@ pub struct LlmConfig {
 // Structure for `profiles/joao.default.yml` (ATAD)
 // ============================================================================
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub struct ProfileConfig {
     pub user_name: String,
@@ -118,7 +132,7 @@ pub struct ProfileConfig {
     pub alert_preferences: AlertPreferences,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub struct AlertPreferences {
     pub dangerous_objects: Vec<String>,
@@ -0,0 +1,80 @@
+/**
+ * @file nonblocking.rs
+ * @author TrackieLLM Rust Team
+ * @brief Loading configuration from async Rust code without blocking the executor.
+ *
+ * @copyright Copyright (c) 2024
+ *
+ * `via_config_load` reads and parses the files on the calling thread, which
+ * stalls a Tokio executor when called from a task. `load_async` reads the
+ * files with `tokio::fs` and does the parsing and merging on a blocking task,
+ * producing the same configuration as the C loaders. It is only built with
+ * the `async` feature.
+ *
+ * The C ABI is unaffected and stays blocking.
+ */
+
+use crate::{diagnostics, load_layers_with, ViaConfig, ViaConfigStatus};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// A failed load: the status a C loader would have reported, with the
+/// message it would have left in `via_config_last_error_message`.
+#[derive(Debug, Clone)]
+pub struct ViaConfigError {
+    pub status: ViaConfigStatus,
+    pub message: String,
+}
+
+impl fmt::Display for ViaConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({:?})", self.message, self.status)
+    }
+}
+
+impl std::error::Error for ViaConfigError {}
+
+/// Loads and merges configuration files without blocking the async executor.
+/// `paths` holds the system and hardware files followed by any profiles, as
+/// in `via_config_load_with_profiles`.
+///
+/// The result can be handed to the C API with `Box::into_raw(Box::new(config))`
+/// and must then be freed with `via_config_free`.
+pub async fn load_async(paths: &[PathBuf]) -> Result<ViaConfig, ViaConfigError> {
+    load_async_with_flags(paths, 0).await
+}
+
+/// Loads like `load_async`, applying the `VIA_CONFIG_FLAG_*` bits in `flags`
+/// as `via_config_load_with_flags` does.
+pub async fn load_async_with_flags(paths: &[PathBuf], flags: u32) -> Result<ViaConfig, ViaConfigError> {
+    // Files that cannot be read here (or are outside the root jail) are left
+    // to the blocking load, which reports them exactly as the C loaders do.
+    let mut contents = HashMap::new();
+    for path in paths {
+        if crate::jail::check(path).is_ok() {
+            if let Ok(text) = tokio::fs::read_to_string(path).await {
+                contents.insert(path.clone(), text);
+            }
+        }
+    }
+
+    let paths = paths.to_vec();
+    let task = tokio::task::spawn_blocking(move || {
+        diagnostics::clear();
+        let paths: Vec<&Path> = paths.iter().map(PathBuf::as_path).collect();
+        // A parent named by `extends` is only known after parsing, so it is read here.
+        let mut read = |path: &Path| match contents.remove(path) {
+            Some(text) => Ok(text),
+            None => std::fs::read_to_string(path),
+        };
+        load_layers_with(&paths, flags, &mut read).map_err(|status| ViaConfigError {
+            status,
+            message: diagnostics::last_message().unwrap_or_default(),
+        })
+    });
+    task.await.map_err(|e| ViaConfigError {
+        status: ViaConfigStatus::InternalError,
+        message: format!("The configuration load task failed: {}", e),
+    })?
+}
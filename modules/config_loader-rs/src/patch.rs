@@ -0,0 +1,238 @@
+/**
+ * @file patch.rs
+ * @author TrackieLLM Rust Team
+ * @brief JSON Patch (RFC 6902) documents applied to the merged tree.
+ *
+ * @copyright Copyright (c) 2024
+ *
+ * The management plane sends partial updates as a JSON Patch, e.g.
+ * `[{"op": "test", "path": "/camera/fps", "value": 30},
+ *   {"op": "replace", "path": "/camera/fps", "value": 15}]`.
+ * The `add`, `remove`, `replace`, `move` and `test` operations are
+ * supported. Paths are JSON Pointers (RFC 6901): `/` separates the segments,
+ * and `~1` and `~0` stand for a `/` and a `~` within one.
+ *
+ * A patch is applied to a copy of the tree, so that a failing operation,
+ * such as a `test` whose value does not match, leaves the configuration
+ * unchanged. The caller then copies every changed key into the handle.
+ */
+
+use crate::ViaConfigStatus;
+use serde_yaml::Value;
+
+/// An operation of a patch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Add,
+    Remove,
+    Replace,
+    Move,
+    Test,
+}
+
+/// One parsed entry of a patch.
+#[derive(Debug)]
+pub struct Operation {
+    op: Op,
+    /// The JSON Pointer as written, for messages.
+    pointer: String,
+    /// The decoded segments of the pointer.
+    path: Vec<String>,
+    /// The decoded `from` pointer of a `move`, empty for other operations.
+    from: Vec<String>,
+    /// The `value` member, for every operation except `remove` and `move`.
+    value: Value,
+}
+
+impl Operation {
+    fn describe(&self, index: usize) -> String {
+        let op = match self.op {
+            Op::Add => "add",
+            Op::Remove => "remove",
+            Op::Replace => "replace",
+            Op::Move => "move",
+            Op::Test => "test",
+        };
+        format!("Patch operation #{} ({} {:?})", index + 1, op, self.pointer)
+    }
+}
+
+/// Parses the text of a JSON Patch document.
+pub fn parse(text: &str) -> Result<Vec<Operation>, String> {
+    let document: serde_json::Value = serde_json::from_str(text).map_err(|e| format!("The patch is not valid JSON: {}", e))?;
+    let serde_json::Value::Array(entries) = document else {
+        return Err("The patch must be a JSON array of operations".to_string());
+    };
+    entries.iter().enumerate().map(|(i, entry)| parse_operation(entry).map_err(|e| format!("Patch operation #{}: {}", i + 1, e))).collect()
+}
+
+fn parse_operation(entry: &serde_json::Value) -> Result<Operation, String> {
+    let member = |name: &str| entry.get(name);
+    let op = match member("op").and_then(serde_json::Value::as_str) {
+        Some("add") => Op::Add,
+        Some("remove") => Op::Remove,
+        Some("replace") => Op::Replace,
+        Some("move") => Op::Move,
+        Some("test") => Op::Test,
+        Some(other) => {
+            return Err(format!("unsupported op {:?}; only add, remove, replace, move and test are supported", other))
+        }
+        None => return Err("missing \"op\"".to_string()),
+    };
+    let pointer = member("path").and_then(serde_json::Value::as_str).ok_or("missing \"path\"")?;
+    let path = decode_pointer(pointer)?;
+    let from = match op {
+        Op::Move => {
+            let from = decode_pointer(member("from").and_then(serde_json::Value::as_str).ok_or("missing \"from\"")?)?;
+            if path.len() > from.len() && path.starts_with(&from) {
+                return Err(format!("cannot move a value into itself at {:?}", pointer));
+            }
+            from
+        }
+        _ => Vec::new(),
+    };
+    let value = match (op, member("value")) {
+        (Op::Remove | Op::Move, _) => Value::Null,
+        (_, Some(value)) => serde_yaml::to_value(value).map_err(|e| e.to_string())?,
+        (_, None) => return Err("missing \"value\"".to_string()),
+    };
+    Ok(Operation { op, pointer: pointer.to_string(), path, from, value })
+}
+
+/// Decodes a JSON Pointer into its segments.
+fn decode_pointer(pointer: &str) -> Result<Vec<String>, String> {
+    if pointer.is_empty() {
+        return Err("the path \"\" names the whole configuration, which cannot be patched".to_string());
+    }
+    let Some(segments) = pointer.strip_prefix('/') else {
+        return Err(format!("the path {:?} does not start with '/'", pointer));
+    };
+    Ok(segments.split('/').map(|segment| segment.replace("~1", "/").replace("~0", "~")).collect())
+}
+
+/// Applies the operations to `root` in order, stopping at the first one that
+/// fails. Returns the dot-key of the nearest mapping entry enclosing each
+/// change, in order, or the status and message of the failure: `KeyNotFound`
+/// for a path that does not exist, `ValidationFailed` for a failed `test`.
+/// The message of a failed `test` on one of `secret_keys` omits the values.
+pub fn apply(root: &mut Value, operations: &[Operation], secret_keys: &[String]) -> Result<Vec<String>, (ViaConfigStatus, String)> {
+    let mut changed = Vec::new();
+    for (i, operation) in operations.iter().enumerate() {
+        let fail = |status, reason: &str| (status, format!("{}: {}", operation.describe(i), reason));
+        let not_found = || fail(ViaConfigStatus::KeyNotFound, "the path does not exist");
+        let key = enclosing_key(root, &operation.path);
+        match operation.op {
+            Op::Test => {
+                let Some(actual) = get_mut(root, &operation.path) else { return Err(not_found()) };
+                if equal(actual, &operation.value) {
+                    continue;
+                }
+                if crate::dump::is_secret(&key, secret_keys) {
+                    return Err(fail(ViaConfigStatus::ValidationFailed, "the value does not match"));
+                }
+                let reason =
+                    format!("the value is {}, not {}", crate::value_to_text(actual), crate::value_to_text(&operation.value));
+                return Err(fail(ViaConfigStatus::ValidationFailed, &reason));
+            }
+            Op::Add => add(root, &operation.path, operation.value.clone()).ok_or_else(not_found)?,
+            Op::Remove => drop(remove(root, &operation.path).ok_or_else(not_found)?),
+            Op::Replace => *get_mut(root, &operation.path).ok_or_else(not_found)? = operation.value.clone(),
+            Op::Move => {
+                changed.push(enclosing_key(root, &operation.from));
+                let from_not_found = || fail(ViaConfigStatus::KeyNotFound, "the \"from\" path does not exist");
+                let val = remove(root, &operation.from).ok_or_else(from_not_found)?;
+                add(root, &operation.path, val).ok_or_else(not_found)?;
+            }
+        }
+        changed.push(key);
+    }
+    Ok(changed)
+}
+
+/// Returns the value `path` names, if it exists.
+fn get_mut<'a>(root: &'a mut Value, path: &[String]) -> Option<&'a mut Value> {
+    let (last, parents) = path.split_last()?;
+    match walk_mut(root, parents)? {
+        Value::Mapping(map) => map.get_mut(last.as_str()),
+        Value::Sequence(seq) => index(last, seq.len()).map(|at| &mut seq[at]),
+        _ => None,
+    }
+}
+
+/// Adds `val` at `path`: sets a mapping entry, or inserts into a sequence
+/// before the given index (`-` appends). Returns `None` if the parent does
+/// not exist.
+fn add(root: &mut Value, path: &[String], val: Value) -> Option<()> {
+    let (last, parents) = path.split_last()?;
+    match walk_mut(root, parents)? {
+        Value::Mapping(map) => {
+            map.insert(Value::String(last.clone()), val);
+        }
+        Value::Sequence(seq) => {
+            let at = if last == "-" { seq.len() } else { index(last, seq.len() + 1)? };
+            seq.insert(at, val);
+        }
+        _ => return None,
+    }
+    Some(())
+}
+
+/// Removes and returns the value at `path`, if it exists.
+fn remove(root: &mut Value, path: &[String]) -> Option<Value> {
+    let (last, parents) = path.split_last()?;
+    match walk_mut(root, parents)? {
+        Value::Mapping(map) => map.shift_remove(last.as_str()),
+        Value::Sequence(seq) => index(last, seq.len()).map(|at| seq.remove(at)),
+        _ => None,
+    }
+}
+
+/// Follows `path` down from `root`, returning the value it names.
+fn walk_mut<'a>(mut node: &'a mut Value, path: &[String]) -> Option<&'a mut Value> {
+    for segment in path {
+        node = match node {
+            Value::Mapping(map) => map.get_mut(segment.as_str())?,
+            Value::Sequence(seq) => {
+                let at = index(segment, seq.len())?;
+                &mut seq[at]
+            }
+            _ => return None,
+        };
+    }
+    Some(node)
+}
+
+/// Parses an array index below `len`. RFC 6901 forbids leading zeros.
+fn index(segment: &str, len: usize) -> Option<usize> {
+    if segment.len() > 1 && segment.starts_with('0') {
+        return None;
+    }
+    segment.parse().ok().filter(|&i| i < len)
+}
+
+/// Returns the dot-key of the deepest mapping entry on `path` in `root`,
+/// i.e. the path up to the first sequence index.
+fn enclosing_key(root: &Value, path: &[String]) -> String {
+    let mut key = String::new();
+    let mut node = Some(root);
+    for segment in path {
+        let Some(Value::Mapping(map)) = node else { break };
+        key = crate::child_key(&key, segment);
+        node = map.get(segment.as_str());
+    }
+    key
+}
+
+/// Compares values as JSON does, where `1` and `1.0` are the same number.
+fn equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x == y || crate::number_to_f64(x) == crate::number_to_f64(y),
+        (Value::Sequence(x), Value::Sequence(y)) => x.len() == y.len() && x.iter().zip(y).all(|(x, y)| equal(x, y)),
+        (Value::Mapping(x), Value::Mapping(y)) => {
+            x.len() == y.len() && x.iter().all(|(k, v)| y.get(k).is_some_and(|w| equal(v, w)))
+        }
+        (Value::Tagged(x), _) => equal(&x.value, b),
+        (_, Value::Tagged(y)) => equal(a, &y.value),
+        _ => a == b,
+    }
+}
@@ -0,0 +1,89 @@
+/**
+ * @file secrets.rs
+ * @author TrackieLLM Rust Team
+ * @brief Secret values read from separate files.
+ *
+ * @copyright Copyright (c) 2024
+ *
+ * Credentials should not be committed with the configuration. Instead, a
+ * value may be written as `api-key: { $secret_file: /run/secrets/api-key }`,
+ * and the contents of that file, with surrounding whitespace trimmed, become
+ * the value when the configuration file is loaded. This matches how Docker
+ * and Kubernetes mount secrets into a container. A relative path is resolved
+ * against the directory of the configuration file, and the root jail applies
+ * to secret files like to any other file.
+ *
+ * Keys whose value came from a secret file are registered as secret keys of
+ * the loaded configuration, so they are redacted in dumps.
+ */
+
+use crate::ViaConfigStatus;
+use serde_yaml::Value;
+use std::fs;
+use std::path::Path;
+
+/// The key of the mapping that stands for the contents of a secret file.
+pub const SECRET_FILE_KEY: &str = "$secret_file";
+
+/// Replaces every `{ $secret_file: path }` mapping in the document of the file
+/// at `origin` with the trimmed contents of the file it names, and appends the
+/// dot-key of each replaced value to `keys`. Returns the status and message of
+/// the first failure: `FileNotFound` for a file that cannot be read or lies
+/// outside the root jail, `ParseError` for a malformed reference.
+pub fn resolve(root: &mut Value, origin: &Path, keys: &mut Vec<String>) -> Result<(), (ViaConfigStatus, String)> {
+    let base_dir = origin.parent().unwrap_or(Path::new(""));
+    resolve_value(root, "", origin, base_dir, keys)
+}
+
+fn resolve_value(
+    val: &mut Value,
+    key: &str,
+    origin: &Path,
+    base_dir: &Path,
+    keys: &mut Vec<String>,
+) -> Result<(), (ViaConfigStatus, String)> {
+    match val {
+        Value::Mapping(map) if map.contains_key(SECRET_FILE_KEY) => {
+            let path = match (map.len(), map.get(SECRET_FILE_KEY)) {
+                (1, Some(Value::String(path))) => base_dir.join(path),
+                _ => {
+                    return Err((
+                        ViaConfigStatus::ParseError,
+                        format!(
+                            "Key '{}' in {:?} must be written as `{{ {}: path }}`, with a path and no other keys",
+                            key, origin, SECRET_FILE_KEY
+                        ),
+                    ))
+                }
+            };
+            crate::jail::check(&path).map_err(|message| (ViaConfigStatus::FileNotFound, message))?;
+            let contents = fs::read_to_string(&path).map_err(|e| {
+                (
+                    ViaConfigStatus::FileNotFound,
+                    format!("Secret file {:?} for key '{}' in {:?} cannot be read: {}", path, key, origin, e),
+                )
+            })?;
+            *val = Value::String(contents.trim().to_string());
+            keys.push(key.to_string());
+        }
+        Value::Mapping(map) => {
+            for (k, v) in map.iter_mut() {
+                resolve_value(v, &crate::child_key(key, &crate::value_to_text(k)), origin, base_dir, keys)?;
+            }
+        }
+        Value::Sequence(seq) => {
+            let before = keys.len();
+            for (i, item) in seq.iter_mut().enumerate() {
+                resolve_value(item, &format!("{}[{}]", key, i), origin, base_dir, keys)?;
+            }
+            // Dumps redact by key, so a secret inside a sequence hides the whole sequence.
+            if keys.len() > before {
+                keys.truncate(before);
+                keys.push(key.to_string());
+            }
+        }
+        Value::Tagged(tagged) => resolve_value(&mut tagged.value, key, origin, base_dir, keys)?,
+        _ => {}
+    }
+    Ok(())
+}
@@ -0,0 +1,118 @@
+/**
+ * @file template.rs
+ * @author TrackieLLM Rust Team
+ * @brief Resolution of `{{ key }}` references between configuration values.
+ *
+ * @copyright Copyright (c) 2024
+ *
+ * With `VIA_CONFIG_FLAG_RESOLVE_TEMPLATES`, a string value may embed other
+ * values of the merged configuration, e.g.
+ * `greeting: "Hello {{ profile.user-name }}"`. Each reference is replaced with
+ * the plain-text form of the scalar it names. A referenced string may itself
+ * contain references; a chain that leads back to a value being resolved is
+ * reported as a cycle instead of recursing forever.
+ *
+ * This is unrelated to `${VAR}` environment references (see
+ * `interpolate.rs`): templates only ever read the configuration itself.
+ */
+
+use crate::{child_key, get_value_by_key, value_to_text};
+use serde_yaml::Value;
+use std::collections::HashMap;
+
+/// Opens a template reference.
+const OPEN: &str = "{{";
+/// Closes a template reference.
+const CLOSE: &str = "}}";
+
+/// Resolves references against a snapshot of the tree taken before any
+/// substitution, memoizing each referenced key's resolved text.
+struct Resolver<'a> {
+    snapshot: &'a Value,
+    resolved: HashMap<String, String>,
+    /// The keys currently being resolved, outermost first, to detect cycles.
+    in_progress: Vec<String>,
+}
+
+impl Resolver<'_> {
+    /// Replaces every reference in `text`.
+    fn expand(&mut self, text: &str) -> Result<String, String> {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find(OPEN) {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + OPEN.len()..];
+            let end = after.find(CLOSE).ok_or_else(|| format!("unterminated reference in {:?}", text))?;
+            out.push_str(&self.resolve_key(after[..end].trim())?);
+            rest = &after[end + CLOSE.len()..];
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+
+    /// Returns the fully resolved text of the scalar at `key`.
+    fn resolve_key(&mut self, key: &str) -> Result<String, String> {
+        if let Some(text) = self.resolved.get(key) {
+            return Ok(text.clone());
+        }
+        if self.in_progress.iter().any(|k| k == key) {
+            let mut chain = self.in_progress.clone();
+            chain.push(key.to_string());
+            return Err(format!("reference cycle: {}", chain.join(" -> ")));
+        }
+        let text = match get_value_by_key(self.snapshot, key) {
+            None => return Err(format!("reference to unknown key '{}'", key)),
+            Some(Value::Mapping(_) | Value::Sequence(_)) => {
+                return Err(format!("reference to '{}', which is not a scalar", key))
+            }
+            Some(Value::String(s)) if s.contains(OPEN) => {
+                self.in_progress.push(key.to_string());
+                let text = self.expand(s);
+                self.in_progress.pop();
+                text?
+            }
+            Some(val) => value_to_text(val),
+        };
+        self.resolved.insert(key.to_string(), text.clone());
+        Ok(text)
+    }
+
+    /// Replaces the references in every string under `val`. `key` is the
+    /// dot-key of `val` (empty for the root), or `None` for values inside a
+    /// sequence, which cannot be referenced.
+    fn resolve_tree(&mut self, val: &mut Value, key: Option<&str>) -> Result<(), String> {
+        match val {
+            Value::String(s) if s.contains(OPEN) => {
+                // Going through the key shares the memoized result and cycle detection.
+                *s = match key {
+                    Some(key) if get_value_by_key(self.snapshot, key).is_some() => self.resolve_key(key),
+                    _ => self.expand(s),
+                }
+                .map_err(|e| format!("{}: {}", key.unwrap_or("sequence item"), e))?;
+            }
+            Value::Sequence(seq) => {
+                for item in seq {
+                    self.resolve_tree(item, None)?;
+                }
+            }
+            Value::Mapping(map) => {
+                for (k, v) in map.iter_mut() {
+                    let child_key = key.map(|key| child_key(key, &value_to_text(k)));
+                    self.resolve_tree(v, child_key.as_deref())?;
+                }
+            }
+            Value::Tagged(tagged) => self.resolve_tree(&mut tagged.value, key)?,
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Replaces every `{{ key }}` reference in the string values of `root`.
+/// Returns a message naming the offending value if a reference names an
+/// unknown key or a collection, is unterminated, or is part of a cycle.
+pub fn resolve(root: &mut Value) -> Result<(), String> {
+    let snapshot = root.clone();
+    let mut resolver = Resolver { snapshot: &snapshot, resolved: HashMap::new(), in_progress: Vec::new() };
+    resolver.resolve_tree(root, Some(""))
+}
@@ -0,0 +1,114 @@
+//! Encoding a configuration into a binary buffer and restoring it.
+
+mod support;
+
+use std::ptr;
+use support::*;
+use via_config::*;
+
+/// Encodes `config` and returns a copy of the buffer.
+fn to_bytes(config: *const ViaConfig) -> Vec<u8> {
+    let (mut buf, mut len) = (ptr::null_mut(), 0);
+    assert_eq!(unsafe { via_config_to_bytes(config, &mut buf, &mut len) }, ViaConfigStatus::Ok);
+    let bytes = unsafe { std::slice::from_raw_parts(buf, len) }.to_vec();
+    unsafe { via_config_free_bytes(buf, len) };
+    bytes
+}
+
+fn from_bytes(bytes: &[u8]) -> *mut ViaConfig {
+    unsafe { via_config_from_bytes(bytes.as_ptr(), bytes.len()) }
+}
+
+const SYSTEM: &str = "log-level: info\nthreads:\n  perception: 2\n  audio: 1\n";
+const HARDWARE: &str = "\
+camera:
+  device-id: 0
+  resolution: {width: 1280, height: 720}
+perception:
+  thresholds: {person: 0.6}
+  labels: [person, stairs]
+";
+
+#[test]
+fn decoded_buffer_answers_queries_like_the_original() {
+    let config = load_strings(SYSTEM, HARDWARE, "threads:\n  audio: 2\n");
+    assert!(!config.is_null(), "{}", last_error());
+    let restored = from_bytes(&to_bytes(config));
+    assert!(!restored.is_null(), "{}", last_error());
+
+    let mut equal = false;
+    assert_eq!(unsafe { via_config_equals(config, restored, &mut equal) }, ViaConfigStatus::Ok);
+    assert!(equal);
+    for handle in [config, restored] {
+        assert_eq!(get_str(handle, "log-level").as_deref(), Ok("info"));
+        assert_eq!(get_int(handle, "threads.audio"), Ok(2));
+        assert_eq!(get_int(handle, "camera.resolution.width"), Ok(1280));
+        assert_eq!(get_float(handle, "perception.thresholds.person"), Ok(0.6));
+        assert_eq!(get_str(handle, "perception.labels"), Err(ViaConfigStatus::TypeError));
+        assert_eq!(get_int(handle, "camera.fps"), Err(ViaConfigStatus::KeyNotFound));
+    }
+    assert_eq!(dump(config), dump(restored));
+    // Provenance travels with the tree.
+    let (mut source, mut restored_source) = (-1, -1);
+    unsafe {
+        assert_eq!(via_config_get_source(config, c("threads.audio").as_ptr(), &mut source), ViaConfigStatus::Ok);
+        assert_eq!(via_config_get_source(restored, c("threads.audio").as_ptr(), &mut restored_source), ViaConfigStatus::Ok);
+    }
+    assert_eq!((source, restored_source), (2, 2));
+    // Equal configurations encode to identical bytes.
+    assert_eq!(to_bytes(config), to_bytes(restored));
+    unsafe {
+        via_config_free(restored);
+        via_config_free(config);
+    }
+}
+
+#[test]
+fn truncated_buffer_is_rejected() {
+    let config = load_strings(SYSTEM, HARDWARE, "{}");
+    let bytes = to_bytes(config);
+    unsafe { via_config_free(config) };
+    for len in [0, 3, 5, bytes.len() / 2, bytes.len() - 1] {
+        assert!(from_bytes(&bytes[..len]).is_null(), "decoded {} of {} bytes", len, bytes.len());
+        assert_eq!(via_config_last_error_status(), ViaConfigStatus::ParseError);
+        assert!(last_error().contains("not a valid binary configuration"), "{}", last_error());
+    }
+}
+
+#[test]
+fn buffer_of_another_format_version_is_rejected() {
+    let config = load_strings(SYSTEM, HARDWARE, "{}");
+    let mut bytes = to_bytes(config);
+    unsafe { via_config_free(config) };
+    // The format version follows the four-byte magic.
+    bytes[4] = bytes[4].wrapping_add(1);
+    assert!(from_bytes(&bytes).is_null());
+    assert_eq!(via_config_last_error_status(), ViaConfigStatus::ParseError);
+
+    bytes[4] = bytes[4].wrapping_sub(1);
+    bytes[0] = b'X';
+    assert!(from_bytes(&bytes).is_null());
+    assert_eq!(via_config_last_error_status(), ViaConfigStatus::ParseError);
+}
+
+#[test]
+fn decoding_enforces_the_maximum_depth() {
+    // The other tests in this binary stay within four levels, so lowering the
+    // process-wide limit to that does not affect them.
+    let config = load_strings("a:\n  b:\n    c:\n      d:\n        e:\n          f: 1\n", "{}", "{}");
+    assert!(!config.is_null(), "{}", last_error());
+    let bytes = to_bytes(config);
+    unsafe { via_config_free(config) };
+
+    via_config_set_max_depth(4);
+    let restored = from_bytes(&bytes);
+    via_config_set_max_depth(0);
+    assert!(restored.is_null());
+    assert_eq!(via_config_last_error_status(), ViaConfigStatus::InternalError);
+    assert!(last_error().contains("more than the maximum of 4 levels"), "{}", last_error());
+
+    let restored = from_bytes(&bytes);
+    assert!(!restored.is_null(), "{}", last_error());
+    assert_eq!(get_int(restored, "a.b.c.d.e.f"), Ok(1));
+    unsafe { via_config_free(restored) };
+}
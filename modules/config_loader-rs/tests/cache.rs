@@ -0,0 +1,92 @@
+//! Loads through the on-disk cache. Facts and limits are process-wide, so
+//! these tests live in their own binary and take turns.
+
+mod support;
+
+use std::sync::Mutex;
+use support::*;
+use via_config::*;
+
+/// Held by each test, so that one test changing facts or limits does not
+/// invalidate the cache of another between its loads.
+static SERIAL: Mutex<()> = Mutex::new(());
+
+fn was_cached(config: *const ViaConfig) -> bool {
+    let mut cached = false;
+    assert_eq!(unsafe { via_config_was_cached(config, &mut cached) }, ViaConfigStatus::Ok);
+    cached
+}
+
+#[test]
+fn cache_is_not_reused_under_other_facts() {
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = scratch_dir("cache_facts");
+    let system = write_file(
+        &dir,
+        "system.yaml",
+        "camera:\n  fps: 30\n  low-power:\n    $when: board == \"pi\"\n    fps: 10\n",
+    );
+    let hardware = write_file(&dir, "hardware.yaml", "{}");
+    let profile = write_file(&dir, "profile.yaml", "{}");
+    let cache = c_path(&dir.join("config.cache"));
+    let load = || unsafe { via_config_load_cached(system.as_ptr(), hardware.as_ptr(), profile.as_ptr(), cache.as_ptr(), 0) };
+
+    unsafe {
+        assert_eq!(via_config_set_fact(c("board").as_ptr(), c("pi").as_ptr()), ViaConfigStatus::Ok);
+        let config = load();
+        assert!(!was_cached(config));
+        assert_eq!(get_int(config, "camera.low-power.fps"), Ok(10));
+        via_config_free(config);
+
+        let config = load();
+        assert!(was_cached(config));
+        via_config_free(config);
+
+        assert_eq!(via_config_set_fact(c("board").as_ptr(), c("jetson").as_ptr()), ViaConfigStatus::Ok);
+        let config = load();
+        assert!(!was_cached(config));
+        assert_eq!(get_int(config, "camera.low-power.fps"), Err(ViaConfigStatus::KeyNotFound));
+        via_config_free(config);
+
+        // The cache now holds the load for the new fact, so going back rebuilds it again.
+        assert_eq!(via_config_set_fact(c("board").as_ptr(), c("pi").as_ptr()), ViaConfigStatus::Ok);
+        let config = load();
+        assert!(!was_cached(config));
+        assert_eq!(get_int(config, "camera.low-power.fps"), Ok(10));
+        via_config_free(config);
+    }
+}
+
+#[test]
+fn cache_is_not_reused_under_other_limits() {
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = scratch_dir("cache_limits");
+    let system = write_file(&dir, "system.yaml", "camera:\n  fps: 30\n");
+    let hardware = write_file(&dir, "hardware.yaml", "{}");
+    let profile = write_file(&dir, "profile.yaml", "{}");
+    let cache = c_path(&dir.join("config.cache"));
+    let load = || unsafe { via_config_load_cached(system.as_ptr(), hardware.as_ptr(), profile.as_ptr(), cache.as_ptr(), 0) };
+    // Loads twice, returning whether each load came from the cache.
+    let load_twice = || {
+        let cached: Vec<bool> = (0..2)
+            .map(|_| {
+                let config = load();
+                assert!(!config.is_null(), "{}", last_error());
+                let cached = was_cached(config);
+                unsafe { via_config_free(config) };
+                cached
+            })
+            .collect();
+        (cached[0], cached[1])
+    };
+
+    assert_eq!(load_twice(), (false, true));
+    via_config_set_size_limits(1 << 20, 0, 0);
+    assert_eq!(load_twice(), (false, true));
+    via_config_set_max_depth(32);
+    assert_eq!(load_twice(), (false, true));
+
+    via_config_set_max_depth(0);
+    via_config_set_size_limits(0, 0, 0);
+    assert_eq!(load_twice(), (false, true));
+}
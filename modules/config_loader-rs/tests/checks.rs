@@ -0,0 +1,181 @@
+//! Domain-specific sanity checks over a loaded configuration.
+
+mod support;
+
+use std::ptr;
+use support::*;
+use via_config::*;
+
+fn thread_budget(config: *const ViaConfig) -> (ViaConfigStatus, Option<String>) {
+    let mut warning = ptr::null_mut();
+    let status = unsafe { via_config_check_thread_budget(config, &mut warning) };
+    (status, (!warning.is_null()).then(|| take_string(warning)))
+}
+
+#[test]
+fn thread_budget_within_the_cpu_count() {
+    let config = load_strings("threads:\n  perception: 0\n  reasoning: 1\n  audio: 0\n", "{}", "{}");
+    assert!(!config.is_null());
+    assert_eq!(thread_budget(config), (ViaConfigStatus::Ok, None));
+    unsafe { via_config_free(config) };
+}
+
+#[test]
+fn thread_budget_exceeded() {
+    let config = load_strings("threads:\n  perception: 40000\n  reasoning: 30000\n  audio: 2000\n", "{}", "{}");
+    assert!(!config.is_null());
+    let (status, warning) = thread_budget(config);
+    assert_eq!(status, ViaConfigStatus::ValidationFailed);
+    let warning = warning.unwrap();
+    assert!(warning.contains("72000 threads configured"), "{}", warning);
+    assert!(warning.contains("perception=40000, reasoning=30000, audio=2000"), "{}", warning);
+    assert!(warning.contains("logical CPUs"), "{}", warning);
+    unsafe { via_config_free(config) };
+}
+
+#[test]
+fn thread_budget_of_the_shipped_system_file() {
+    let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../config");
+    let system = c_path(&root.join("system.default.yml"));
+    let hardware = c_path(&root.join("hardware.default.yml"));
+    let profile = c_path(&root.join("profiles/default.yml"));
+    let config = unsafe { via_config_load(system.as_ptr(), hardware.as_ptr(), profile.as_ptr()) };
+    assert!(!config.is_null(), "{}", last_error());
+    let (status, warning) = thread_budget(config);
+    assert_ne!(status, ViaConfigStatus::KeyNotFound);
+    assert_eq!(status == ViaConfigStatus::ValidationFailed, warning.is_some());
+    unsafe { via_config_free(config) };
+}
+
+#[test]
+fn thread_budget_reports_a_count_of_the_wrong_type() {
+    let config = load_strings("threads:\n  perception: two\n  reasoning: 1\n  audio: 1\n", "{}", "{}");
+    assert!(!config.is_null());
+    assert_eq!(thread_budget(config), (ViaConfigStatus::TypeError, None));
+    assert_eq!(last_error(), "Expected non-negative integer for key 'threads.perception' but found string");
+    unsafe { via_config_free(config) };
+}
+
+fn dependencies(config: *const ViaConfig) -> (ViaConfigStatus, Option<String>) {
+    let mut report = ptr::null_mut();
+    let status = unsafe { via_config_check_dependencies(config, &mut report) };
+    (status, (!report.is_null()).then(|| take_string(report)))
+}
+
+fn noise_filter(window_size: &str) -> *mut ViaConfig {
+    let hardware = format!("microphone:\n  noise-filter:\n    enabled: true\n    window-size: {}\n", window_size);
+    let config = load_strings("{}", &hardware, "{}");
+    assert!(!config.is_null(), "{}", last_error());
+    let status = unsafe {
+        via_config_add_dependency_range(
+            config,
+            c("microphone.noise-filter.enabled").as_ptr(),
+            c("true").as_ptr(),
+            c("microphone.noise-filter.window-size").as_ptr(),
+            0.0,
+            true,
+            f64::INFINITY,
+            false,
+        )
+    };
+    assert_eq!(status, ViaConfigStatus::Ok);
+    config
+}
+
+#[test]
+fn dependency_range_excludes_its_exclusive_bound() {
+    let config = noise_filter("0");
+    let (status, report) = dependencies(config);
+    assert_eq!(status, ViaConfigStatus::ValidationFailed);
+    assert_eq!(
+        report.as_deref(),
+        Some("microphone.noise-filter.window-size: required because microphone.noise-filter.enabled is true, but it is 0, which is not > 0")
+    );
+    let mut constraints = ptr::null_mut();
+    assert_eq!(unsafe { via_config_list_constraints(config, &mut constraints) }, ViaConfigStatus::Ok);
+    assert_eq!(
+        take_string(constraints),
+        "dependency: microphone.noise-filter.enabled requires microphone.noise-filter.window-size > 0 when true"
+    );
+    unsafe { via_config_free(config) };
+
+    let config = noise_filter("256");
+    assert_eq!(dependencies(config), (ViaConfigStatus::Ok, None));
+    unsafe { via_config_free(config) };
+
+    let config = noise_filter("wide");
+    let (status, report) = dependencies(config);
+    assert_eq!(status, ViaConfigStatus::ValidationFailed);
+    assert!(report.unwrap().ends_with("but it is a string, not a number > 0"));
+    unsafe { via_config_free(config) };
+}
+
+#[test]
+fn dependency_range_bounds_are_inclusive_unless_marked() {
+    let config = load_strings("{}", "camera:\n  enabled: true\n  fps: 60\n", "{}");
+    let (enabled, when, fps) = (c("camera.enabled"), c("true"), c("camera.fps"));
+    let add = |min, max, max_exclusive| unsafe {
+        via_config_add_dependency_range(config, enabled.as_ptr(), when.as_ptr(), fps.as_ptr(), min, false, max, max_exclusive)
+    };
+    assert_eq!(add(1.0, 60.0, false), ViaConfigStatus::Ok);
+    assert_eq!(dependencies(config), (ViaConfigStatus::Ok, None));
+    assert_eq!(add(1.0, 60.0, true), ViaConfigStatus::Ok);
+    let (status, report) = dependencies(config);
+    assert_eq!(status, ViaConfigStatus::ValidationFailed);
+    assert!(report.unwrap().ends_with("it is 60, which is not >= 1 and < 60"));
+
+    assert_eq!(add(2.0, 1.0, false), ViaConfigStatus::InternalError);
+    unsafe { via_config_free(config) };
+}
+
+#[test]
+fn dependency_rule_requires_a_set_value() {
+    let config = load_strings("{}", "microphone:\n  noise-filter:\n    enabled: true\n", "{}");
+    let status = unsafe {
+        via_config_add_dependency_rule(
+            config,
+            c("microphone.noise-filter.enabled").as_ptr(),
+            c("true").as_ptr(),
+            c("microphone.noise-filter.window-size").as_ptr(),
+        )
+    };
+    assert_eq!(status, ViaConfigStatus::Ok);
+    let (status, report) = dependencies(config);
+    assert_eq!(status, ViaConfigStatus::ValidationFailed);
+    assert!(report.unwrap().ends_with("but it is not set"));
+    unsafe { via_config_free(config) };
+}
+
+fn compatibility(system: &str) -> (ViaConfigStatus, String) {
+    let config = load_strings(system, "{}", "{}");
+    assert!(!config.is_null(), "{}", last_error());
+    let mut report = ptr::null_mut();
+    let status = unsafe { via_config_check_compatibility(config, &mut report) };
+    unsafe { via_config_free(config) };
+    (status, take_string(report))
+}
+
+#[test]
+fn current_schema_version_is_compatible() {
+    let (status, report) = compatibility("schema-version: 3\nlog-level: info\n");
+    assert_eq!(status, ViaConfigStatus::Ok);
+    assert_eq!(report, "Schema version 3 is the current version; the configuration is compatible");
+}
+
+#[test]
+fn other_schema_versions_are_incompatible() {
+    // Without `schema-version`, a configuration is version 1.
+    let (status, report) = compatibility("max-threads: 4\ncamera:\n  width: 1280\n");
+    assert_eq!(status, ViaConfigStatus::ValidationFailed);
+    assert!(report.starts_with("Schema version 1 is older than the current version 3"), "{}", report);
+    assert!(report.contains("2: rename 'max-threads' to 'threads.perception'"), "{}", report);
+    assert!(report.contains("3: rename 'camera.width' to 'camera.resolution.width'"), "{}", report);
+
+    let (status, report) = compatibility("schema-version: 99\n");
+    assert_eq!(status, ViaConfigStatus::InternalError);
+    assert!(report.contains("newer than 3"), "{}", report);
+
+    let (status, report) = compatibility("schema-version: three\n");
+    assert_eq!(status, ViaConfigStatus::TypeError);
+    assert!(report.contains("must be a positive integer"), "{}", report);
+}
@@ -0,0 +1,242 @@
+//! Typed getters and the flags that relax their conversions.
+
+mod support;
+
+use support::*;
+use via_config::*;
+
+#[test]
+fn float_getter_reads_exponents_negatives_and_integers() {
+    let config = load_strings("audio:\n  gain: 1e-3\n  offset: -2.5\n  channels: 5\n  trim: -1\n", "{}", "{}");
+    assert!(!config.is_null());
+    assert_eq!(get_float(config, "audio.gain"), Ok(0.001));
+    assert_eq!(get_float(config, "audio.offset"), Ok(-2.5));
+    assert_eq!(get_float(config, "audio.channels"), Ok(5.0));
+    assert_eq!(get_float(config, "audio.trim"), Ok(-1.0));
+    unsafe { via_config_free(config) };
+}
+
+const NUMERIC_STRINGS: &str = "model:\n  context: \"4096\"\n  temperature: \"0.75\"\n  name: \"llama\"\n";
+
+#[test]
+fn numeric_strings_are_coerced_with_the_flag() {
+    let config = load_files("coerce_numeric", NUMERIC_STRINGS, "{}", "{}", VIA_CONFIG_FLAG_COERCE_NUMERIC_STRINGS);
+    assert!(!config.is_null(), "{}", last_error());
+    assert_eq!(get_int(config, "model.context"), Ok(4096));
+    assert_eq!(get_float(config, "model.temperature"), Ok(0.75));
+    assert_eq!(get_int(config, "model.name"), Err(ViaConfigStatus::TypeError));
+    assert_eq!(get_float(config, "model.name"), Err(ViaConfigStatus::TypeError));
+    unsafe { via_config_free(config) };
+}
+
+#[test]
+fn numeric_strings_are_not_coerced_by_default() {
+    let config = load_strings(NUMERIC_STRINGS, "{}", "{}");
+    assert!(!config.is_null());
+    assert_eq!(get_int(config, "model.context"), Err(ViaConfigStatus::TypeError));
+    assert_eq!(get_float(config, "model.temperature"), Err(ViaConfigStatus::TypeError));
+    assert_eq!(get_str(config, "model.context").as_deref(), Ok("4096"));
+    unsafe { via_config_free(config) };
+}
+
+const BOOLEANS: &str = "\
+features:
+  plain-true: true
+  plain-false: False
+  yes: Yes
+  no: NO
+  on: On
+  off: oFF
+  one: 1
+  zero: 0
+  quoted-one: \"1\"
+  quoted-zero: \"0\"
+  mixed-true: \"tRuE\"
+  mixed-false: \"FaLsE\"
+  maybe: maybe
+";
+
+#[test]
+fn lenient_booleans_accept_common_spellings() {
+    let config = load_files("lenient_booleans", BOOLEANS, "{}", "{}", VIA_CONFIG_FLAG_LENIENT_BOOLEANS);
+    assert!(!config.is_null(), "{}", last_error());
+    for key in ["plain-true", "yes", "on", "one", "quoted-one", "mixed-true"] {
+        assert_eq!(get_bool(config, &format!("features.{}", key)), Ok(true), "{}", key);
+    }
+    for key in ["plain-false", "no", "off", "zero", "quoted-zero", "mixed-false"] {
+        assert_eq!(get_bool(config, &format!("features.{}", key)), Ok(false), "{}", key);
+    }
+    assert_eq!(get_bool(config, "features.maybe"), Err(ViaConfigStatus::TypeError));
+    unsafe { via_config_free(config) };
+}
+
+#[test]
+fn booleans_are_strict_by_default() {
+    let config = load_strings(BOOLEANS, "{}", "{}");
+    assert!(!config.is_null());
+    assert_eq!(get_bool(config, "features.plain-true"), Ok(true));
+    assert_eq!(get_bool(config, "features.plain-false"), Ok(false));
+    for key in ["yes", "on", "one", "quoted-one", "mixed-true", "no", "off", "zero", "maybe"] {
+        assert_eq!(get_bool(config, &format!("features.{}", key)), Err(ViaConfigStatus::TypeError), "{}", key);
+    }
+    unsafe { via_config_free(config) };
+}
+
+#[test]
+fn integer_above_i64_max_is_out_of_range() {
+    let config = load_strings("counters:\n  frames: 18446744073709551615\n", "{}", "{}");
+    assert!(!config.is_null());
+    assert_eq!(get_int(config, "counters.frames"), Err(ViaConfigStatus::TypeError));
+    assert!(last_error().contains("out of range for i64"), "{}", last_error());
+    let mut value = 0;
+    assert_eq!(unsafe { via_config_get_u64(config, c("counters.frames").as_ptr(), &mut value) }, ViaConfigStatus::Ok);
+    assert_eq!(value, u64::MAX);
+    unsafe { via_config_free(config) };
+}
+
+#[test]
+fn scaled_float_promotes_integers() {
+    let config = load_strings("memory:\n  cache-kib: 512\n  rate: 44100\n", "{}", "{}");
+    assert!(!config.is_null());
+    let mut value = 0.0;
+    assert_eq!(
+        unsafe { via_config_get_scaled_float(config, c("memory.cache-kib").as_ptr(), 1.0 / 1024.0, &mut value) },
+        ViaConfigStatus::Ok
+    );
+    assert_eq!(value, 0.5);
+    assert_eq!(unsafe { via_config_get_scaled_float(config, c("memory.rate").as_ptr(), 0.001, &mut value) }, ViaConfigStatus::Ok);
+    assert!((value - 44.1).abs() < 1e-9, "{}", value);
+    unsafe { via_config_free(config) };
+}
+
+#[test]
+fn escaped_dots_name_a_single_segment() {
+    let config = load_strings("perception:\n  model-paths:\n    yolo.v8: assets/yolo.onnx\n", "{}", "{}");
+    assert!(!config.is_null());
+    assert_eq!(get_str(config, r"perception.model-paths.yolo\.v8").as_deref(), Ok("assets/yolo.onnx"));
+    assert_eq!(get_str(config, "perception.model-paths.yolo.v8"), Err(ViaConfigStatus::KeyNotFound));
+
+    assert_eq!(unsafe { via_config_set_integer(config, c(r"limits.a\.b").as_ptr(), 7) }, ViaConfigStatus::Ok);
+    assert_eq!(get_int(config, r"limits.a\.b"), Ok(7));
+    assert_eq!(get_int(config, "limits.a.b"), Err(ViaConfigStatus::KeyNotFound));
+    assert!(dump(config).contains("a.b: 7"), "{}", dump(config));
+    unsafe { via_config_free(config) };
+}
+
+/// Asserts that `status` is a `TypeError` whose message names `key` and the
+/// type that was found.
+fn assert_type_error(status: ViaConfigStatus, key: &str, found: &str) {
+    assert_eq!(status, ViaConfigStatus::TypeError);
+    let message = last_error();
+    assert!(message.contains(&format!("for key '{}' but found {}", key, found)), "{}", message);
+}
+
+const MISTYPED: &str = "\
+ratio: 1.5
+thresholds:
+  person: high
+enum-value: loud
+started-at: yesterday
+label: 12
+model: [a, b]
+timeout: [1, 2]
+";
+
+#[test]
+fn type_errors_are_described() {
+    let config = load_strings(MISTYPED, "{}", "{}");
+    assert!(!config.is_null(), "{}", last_error());
+    unsafe {
+        let mut f = 0.0;
+        assert_type_error(via_config_get_ratio(config, c("ratio").as_ptr(), &mut f), "ratio", "float");
+
+        let (mut keys, mut values, mut len) = (std::ptr::null_mut(), std::ptr::null_mut(), 0);
+        let status = via_config_get_numeric_map(config, c("thresholds").as_ptr(), &mut keys, &mut values, &mut len);
+        assert_type_error(status, "thresholds.person", "string");
+        let status = via_config_get_numeric_map(config, c("label").as_ptr(), &mut keys, &mut values, &mut len);
+        assert_type_error(status, "label", "integer");
+
+        let allowed = [c("quiet"), c("normal")];
+        let allowed: Vec<_> = allowed.iter().map(|s| s.as_ptr()).collect();
+        let mut index = -1;
+        let status = via_config_get_enum(config, c("enum-value").as_ptr(), allowed.as_ptr(), allowed.len(), &mut index);
+        assert_type_error(status, "enum-value", "string");
+        assert!(last_error().contains("one of quiet, normal"), "{}", last_error());
+
+        let mut secs = 0;
+        assert_type_error(via_config_get_timestamp(config, c("started-at").as_ptr(), &mut secs), "started-at", "string");
+
+        let mut s = std::ptr::null_mut();
+        let status = via_config_get_string_or_env(config, c("label").as_ptr(), c("VIA_CONFIG_TEST_UNSET_LABEL").as_ptr(), &mut s);
+        assert_type_error(status, "label", "integer");
+        assert_type_error(via_config_get_path(config, c("model").as_ptr(), c(".").as_ptr(), &mut s), "model", "sequence");
+
+        let mut ms = 0;
+        assert_type_error(via_config_get_duration_ms(config, c("timeout").as_ptr(), &mut ms), "timeout", "sequence");
+
+        let (mut names, mut count) = (std::ptr::null_mut(), 0);
+        assert_type_error(via_config_get_keys(config, c("label").as_ptr(), false, &mut names, &mut count), "label", "integer");
+
+        let label = c("label");
+        let field = ViaConfigField { key: label.as_ptr(), offset: 0, field_type: ViaConfigFieldType::String };
+        let mut out: *mut std::ffi::c_char = std::ptr::null_mut();
+        let status = via_config_fill_struct(
+            config,
+            &field,
+            1,
+            &mut out as *mut _ as *mut std::ffi::c_void,
+            std::mem::size_of_val(&out),
+        );
+        assert_type_error(status, "label", "integer");
+        assert!(out.is_null());
+        via_config_free(config);
+    }
+
+    let dir = scratch_dir("read_string_type_error");
+    let system = write_file(&dir, "system.yaml", MISTYPED);
+    let empty = write_file(&dir, "empty.yaml", "{}");
+    let mut s = std::ptr::null_mut();
+    let status = unsafe { via_config_read_string(system.as_ptr(), empty.as_ptr(), empty.as_ptr(), c("label").as_ptr(), &mut s) };
+    assert_type_error(status, "label", "integer");
+}
+
+fn duration(text: &str) -> Result<u64, ViaConfigStatus> {
+    let config = load_strings(&format!("timeout: {}\n", text), "{}", "{}");
+    assert!(!config.is_null(), "{}", last_error());
+    let mut ms = 0;
+    let status = unsafe { via_config_get_duration_ms(config, c("timeout").as_ptr(), &mut ms) };
+    unsafe { via_config_free(config) };
+    match status {
+        ViaConfigStatus::Ok => Ok(ms),
+        status => Err(status),
+    }
+}
+
+#[test]
+fn durations_accept_every_unit() {
+    assert_eq!(duration("250ms"), Ok(250));
+    assert_eq!(duration("30s"), Ok(30_000));
+    assert_eq!(duration("5m"), Ok(300_000));
+    assert_eq!(duration("2h"), Ok(7_200_000));
+    assert_eq!(duration("1d"), Ok(86_400_000));
+    assert_eq!(duration("1h 30m 5s 10ms"), Ok(5_405_010));
+    assert_eq!(duration("1500"), Ok(1500));
+}
+
+#[test]
+fn overflowing_durations_are_errors() {
+    for text in ["9999999999h", "99999999999999999999ms", "213504d", "18446744073709551615"] {
+        assert_eq!(duration(text), Err(ViaConfigStatus::TypeError), "{}", text);
+        assert!(last_error().contains("too large"), "{}: {}", text, last_error());
+    }
+    // The largest accepted duration still fits in nanoseconds.
+    assert_eq!(duration("18446744073709ms"), Ok(18_446_744_073_709));
+}
+
+#[test]
+fn malformed_durations_are_errors() {
+    for text in ["'5x'", "'ms'", "'1h30'", "'-5s'", "''"] {
+        assert_eq!(duration(text), Err(ViaConfigStatus::TypeError), "{}", text);
+        assert!(last_error().contains("Invalid value for key 'timeout'"), "{}: {}", text, last_error());
+    }
+}
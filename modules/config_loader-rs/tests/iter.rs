@@ -0,0 +1,29 @@
+//! Iterating over the entries of a mapping.
+
+mod support;
+
+use support::*;
+use via_config::*;
+
+#[test]
+fn iterator_is_a_snapshot_independent_of_the_handle() {
+    let config = load_strings("camera:\n  fps: 30\n  lens:\n    focal: 4\n", "{}", "{}");
+    assert!(!config.is_null());
+    unsafe {
+        let iter = via_config_iter_create(config, c("camera").as_ptr());
+        assert!(!iter.is_null());
+        // Neither changing nor freeing the handle affects the iterator.
+        assert_eq!(via_config_remove_key(config, c("camera.lens").as_ptr()), ViaConfigStatus::Ok);
+        assert_eq!(via_config_set_integer(config, c("camera.width").as_ptr(), 1280), ViaConfigStatus::Ok);
+        via_config_free(config);
+
+        let mut entries = Vec::new();
+        let mut key = std::ptr::null();
+        let mut value_type = ViaConfigValueType::Null;
+        while via_config_iter_next(iter, &mut key, &mut value_type) {
+            entries.push((std::ffi::CStr::from_ptr(key).to_str().unwrap().to_string(), value_type));
+        }
+        via_config_iter_free(iter);
+        assert_eq!(entries, [("fps".to_string(), ViaConfigValueType::Integer), ("lens".to_string(), ViaConfigValueType::Mapping)]);
+    }
+}
@@ -0,0 +1,351 @@
+//! Loading and merging of the system, hardware and profile files.
+
+mod support;
+
+use support::*;
+use via_config::*;
+
+/// Loads the hardware fixture `name` between an empty system and profile file.
+fn load_hardware_fixture(name: &str, flags: u32, profile: &str) -> *mut ViaConfig {
+    let dir = scratch_dir(&format!("hardware_{}", name.replace('.', "_")));
+    let system = write_file(&dir, "system.yaml", "{}");
+    let profile = write_file(&dir, "profile.yaml", profile);
+    unsafe { via_config_load_with_flags(system.as_ptr(), fixture(name).as_ptr(), profile.as_ptr(), flags) }
+}
+
+#[test]
+fn anchors_in_hardware_template_are_resolved_before_merge() {
+    let profile = "hardware:\n  cameras:\n    rear:\n      fps: 15\n";
+    let config = load_hardware_fixture("hardware_template.yaml", VIA_CONFIG_FLAG_RESOLVE_ANCHORS_STRICT, profile);
+    assert!(!config.is_null(), "{}", last_error());
+    assert_eq!(get_int(config, "hardware.cameras.front.width"), Ok(1280));
+    assert_eq!(get_int(config, "hardware.cameras.front.fps"), Ok(30));
+    assert_eq!(get_int(config, "hardware.cameras.rear.height"), Ok(720));
+    // The profile overrides one aliased copy without touching the other.
+    assert_eq!(get_int(config, "hardware.cameras.rear.fps"), Ok(15));
+    assert_eq!(get_float(config, "hardware.obstacle-detector.threshold"), Ok(0.6));
+    unsafe { via_config_free(config) };
+}
+
+#[test]
+fn undefined_anchor_is_reported_in_strict_mode() {
+    let dir = scratch_dir("undefined_anchor");
+    let system = write_file(&dir, "system.yaml", "{}");
+    let hardware = write_file(&dir, "hardware.yaml", "camera:\n  fps: *missing\n");
+    let profile = write_file(&dir, "profile.yaml", "{}");
+    let config = unsafe {
+        via_config_load_with_flags(system.as_ptr(), hardware.as_ptr(), profile.as_ptr(), VIA_CONFIG_FLAG_RESOLVE_ANCHORS_STRICT)
+    };
+    assert!(config.is_null());
+    assert!(last_error().contains("'*missing'"), "{}", last_error());
+}
+
+#[test]
+fn profile_with_bom_and_crlf_line_endings_loads() {
+    let dir = scratch_dir("bom_crlf");
+    let system = write_file(&dir, "system.yaml", "camera:\n  device-id: 0\n  fps: 30\n");
+    let hardware = write_file(&dir, "hardware.yaml", "{}");
+    let profile = fixture("profile_bom_crlf.yaml");
+    assert!(std::fs::read(profile.to_str().unwrap()).unwrap().starts_with("\u{FEFF}".as_bytes()));
+
+    let config = unsafe { via_config_load(system.as_ptr(), hardware.as_ptr(), profile.as_ptr()) };
+    assert!(!config.is_null(), "{}", last_error());
+    // The first key follows the BOM directly, so it must not absorb it.
+    assert_eq!(get_int(config, "camera.device-id"), Ok(2));
+    assert_eq!(get_int(config, "camera.fps"), Ok(24));
+    unsafe { via_config_free(config) };
+}
+
+const MERGE_KEYS: &str = "\
+templates:
+  camera: &camera
+    width: 1280
+    height: 720
+    fps: 30
+  low-light: &low-light
+    exposure: long
+    fps: 15
+hardware:
+  front:
+    <<: *camera
+    fps: 60
+  night:
+    <<: [*low-light, *camera]
+    height: 1080
+";
+
+#[test]
+fn merge_keys_are_expanded() {
+    let config = load_strings("{}", MERGE_KEYS, "{}");
+    assert!(!config.is_null(), "{}", last_error());
+    // A single alias, with the local key taking precedence.
+    assert_eq!(get_int(config, "hardware.front.width"), Ok(1280));
+    assert_eq!(get_int(config, "hardware.front.fps"), Ok(60));
+    // A sequence of aliases, where earlier entries win over later ones.
+    assert_eq!(get_str(config, "hardware.night.exposure").as_deref(), Ok("long"));
+    assert_eq!(get_int(config, "hardware.night.fps"), Ok(15));
+    assert_eq!(get_int(config, "hardware.night.width"), Ok(1280));
+    assert_eq!(get_int(config, "hardware.night.height"), Ok(1080));
+    // The `<<` key itself is gone.
+    assert_eq!(get_int(config, "hardware.front.<<"), Err(ViaConfigStatus::KeyNotFound));
+    assert_eq!(get_int(config, "hardware.night.<<"), Err(ViaConfigStatus::KeyNotFound));
+    assert!(!dump(config).contains("<<"));
+    unsafe { via_config_free(config) };
+}
+
+#[test]
+fn empty_profile_file_is_a_no_op_overlay() {
+    let dir = scratch_dir("empty_profile");
+    let system = write_file(&dir, "system.yaml", "camera:\n  fps: 30\n  width: 1280\nlogging:\n  level: info\n");
+    let hardware = write_file(&dir, "hardware.yaml", "camera:\n  width: 1920\n");
+    let profile = write_file(&dir, "profile.yaml", "");
+
+    unsafe {
+        let with_empty = via_config_load(system.as_ptr(), hardware.as_ptr(), profile.as_ptr());
+        assert!(!with_empty.is_null(), "{}", last_error());
+        let without = via_config_load_with_flags(system.as_ptr(), hardware.as_ptr(), std::ptr::null(), VIA_CONFIG_FLAG_SKIP_PROFILE);
+        assert!(!without.is_null(), "{}", last_error());
+
+        let mut equal = false;
+        assert_eq!(via_config_equals(with_empty, without, &mut equal), ViaConfigStatus::Ok);
+        assert!(equal, "{}\n---\n{}", dump(with_empty), dump(without));
+        assert_eq!(get_int(with_empty, "camera.width"), Ok(1920));
+        assert_eq!(get_int(with_empty, "camera.fps"), Ok(30));
+        via_config_free(with_empty);
+        via_config_free(without);
+    }
+}
+
+#[test]
+fn legacy_flat_keys_are_expanded() {
+    let config = unsafe { via_config_load_legacy(fixture("legacy_flat.yaml").as_ptr()) };
+    assert!(!config.is_null(), "{}", last_error());
+    assert_eq!(get_str(config, "log-level").as_deref(), Ok("info"));
+    assert_eq!(get_int(config, "threads.perception"), Ok(2));
+    assert_eq!(get_int(config, "camera.device-id"), Ok(0));
+    assert_eq!(get_int(config, "camera.resolution.width"), Ok(1280));
+    assert_eq!(get_int(config, "camera.resolution.height"), Ok(720));
+    assert_eq!(get_int(config, "microphone.sample-rate"), Ok(16000));
+    assert_eq!(get_bool(config, "microphone.noise-filter.enabled"), Ok(true));
+    // Entries of a free-form table keep their key verbatim.
+    assert_eq!(get_str(config, "perception.model-paths.yolo_v8").as_deref(), Ok("/opt/models/yolo.onnx"));
+    assert_eq!(get_int(config, "reasoning.llm.context-size"), Ok(4096));
+    assert_eq!(get_str(config, "user-name").as_deref(), Ok("Ana"));
+    assert_eq!(get_bool(config, "alert-preferences.play-sounds"), Ok(false));
+    assert_eq!(get_int(config, "custom_plugin_flag"), Ok(1));
+    // The flat spellings are gone.
+    assert_eq!(get_int(config, "camera_device_id"), Err(ViaConfigStatus::KeyNotFound));
+    unsafe { via_config_free(config) };
+}
+
+#[test]
+fn optional_profile_may_be_missing() {
+    let dir = scratch_dir("optional_profile");
+    let system = write_file(&dir, "system.yaml", "camera:\n  fps: 30\n");
+    let hardware = write_file(&dir, "hardware.yaml", "camera:\n  width: 1920\n");
+    let profile = c_path(&dir.join("not-provisioned-yet.yaml"));
+
+    unsafe {
+        let config = via_config_load_with_flags(system.as_ptr(), hardware.as_ptr(), profile.as_ptr(), VIA_CONFIG_FLAG_PROFILE_OPTIONAL);
+        assert!(!config.is_null(), "{}", last_error());
+        assert_eq!(get_int(config, "camera.fps"), Ok(30));
+        assert_eq!(get_int(config, "camera.width"), Ok(1920));
+        via_config_free(config);
+
+        // Without the flag the profile is required.
+        assert!(via_config_load(system.as_ptr(), hardware.as_ptr(), profile.as_ptr()).is_null());
+        assert_eq!(via_config_last_error_status(), ViaConfigStatus::FileNotFound);
+    }
+}
+
+#[test]
+fn optional_profile_does_not_make_system_or_hardware_optional() {
+    let dir = scratch_dir("optional_profile_required_layers");
+    let present = write_file(&dir, "present.yaml", "camera:\n  fps: 30\n");
+    let missing = c_path(&dir.join("missing.yaml"));
+
+    unsafe {
+        for (system, hardware) in [(&missing, &present), (&present, &missing)] {
+            let config = via_config_load_with_flags(system.as_ptr(), hardware.as_ptr(), present.as_ptr(), VIA_CONFIG_FLAG_PROFILE_OPTIONAL);
+            assert!(config.is_null());
+            assert_eq!(via_config_last_error_status(), ViaConfigStatus::FileNotFound);
+        }
+    }
+}
+
+const TWO_DOCUMENTS: &str = "camera:\n  fps: 30\n  width: 1280\n---\ncamera:\n  fps: 15\nlogging:\n  level: debug\n";
+
+#[test]
+fn multiple_documents_are_rejected_by_default() {
+    let config = load_files("two_documents", TWO_DOCUMENTS, "{}", "{}", 0);
+    assert!(config.is_null());
+    assert_eq!(via_config_last_error_status(), ViaConfigStatus::ParseError);
+    assert!(last_error().contains("contains 2 YAML documents"), "{}", last_error());
+}
+
+#[test]
+fn multiple_documents_are_merged_in_order_with_the_flag() {
+    let config = load_files("two_documents_merged", TWO_DOCUMENTS, "{}", "{}", VIA_CONFIG_FLAG_MERGE_DOCUMENTS);
+    assert!(!config.is_null(), "{}", last_error());
+    assert_eq!(get_int(config, "camera.fps"), Ok(15));
+    assert_eq!(get_int(config, "camera.width"), Ok(1280));
+    assert_eq!(get_str(config, "logging.level").as_deref(), Ok("debug"));
+    unsafe { via_config_free(config) };
+}
+
+/// Paths of the system, hardware and default profile files shipped in `config/`.
+fn shipped_files() -> [std::ffi::CString; 3] {
+    let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../config");
+    [
+        c_path(&root.join("system.default.yml")),
+        c_path(&root.join("hardware.default.yml")),
+        c_path(&root.join("profiles/default.yml")),
+    ]
+}
+
+#[test]
+fn shipped_files_pass_the_top_level_check() {
+    let [system, hardware, profile] = shipped_files();
+    let config = unsafe {
+        via_config_load_with_flags(system.as_ptr(), hardware.as_ptr(), profile.as_ptr(), VIA_CONFIG_FLAG_STRICT_TOP_LEVEL)
+    };
+    assert!(!config.is_null(), "{}", last_error());
+    assert!(get_str(config, "log-level").is_ok());
+    unsafe { via_config_free(config) };
+}
+
+#[test]
+fn misspelled_top_level_key_is_named() {
+    let system = "log-level: info\nthreds:\n  perception: 2\n";
+    let config = load_files("top_level_typo", system, "camera:\n  device-id: 0\n", "{}", VIA_CONFIG_FLAG_STRICT_TOP_LEVEL);
+    assert!(config.is_null());
+    assert_eq!(via_config_last_error_status(), ViaConfigStatus::ValidationFailed);
+    let message = last_error();
+    assert!(message.contains("'threds' (source 0)"), "{}", message);
+    assert!(!message.contains("'log-level'") && !message.contains("'camera'"), "{}", message);
+
+    // Without the flag the same files load.
+    let config = load_files("top_level_typo_lax", system, "camera:\n  device-id: 0\n", "{}", 0);
+    assert!(!config.is_null(), "{}", last_error());
+    unsafe { via_config_free(config) };
+}
+
+const COMPLETE_PROFILE: &str = "\
+user-name: Ana
+known-faces-db-path: data/faces.db
+alert-preferences:
+  dangerous-objects: [knife, stairs]
+  play-sounds: true
+";
+
+#[test]
+fn complete_configuration_passes_the_schema_check() {
+    let [system, hardware, _] = shipped_files();
+    let dir = scratch_dir("schema_complete");
+    let profile = write_file(&dir, "profile.yaml", COMPLETE_PROFILE);
+    let config = unsafe {
+        via_config_load_with_flags(
+            system.as_ptr(),
+            hardware.as_ptr(),
+            profile.as_ptr(),
+            VIA_CONFIG_FLAG_STRICT_SCHEMA | VIA_CONFIG_FLAG_STRICT_TOP_LEVEL,
+        )
+    };
+    assert!(!config.is_null(), "{}", last_error());
+    assert_eq!(get_str(config, "user-name").as_deref(), Ok("Ana"));
+    unsafe { via_config_free(config) };
+}
+
+#[test]
+fn missing_required_setting_fails_the_schema_check() {
+    let [system, hardware, _] = shipped_files();
+    let dir = scratch_dir("schema_missing");
+    let profile = write_file(&dir, "profile.yaml", &COMPLETE_PROFILE.replace("known-faces-db-path: data/faces.db\n", ""));
+    let config = unsafe {
+        via_config_load_with_flags(system.as_ptr(), hardware.as_ptr(), profile.as_ptr(), VIA_CONFIG_FLAG_STRICT_SCHEMA)
+    };
+    assert!(config.is_null());
+    assert_eq!(via_config_last_error_status(), ViaConfigStatus::ValidationFailed);
+    let message = last_error();
+    assert!(message.contains("does not match the schema"), "{}", message);
+    assert!(message.contains("known-faces-db-path"), "{}", message);
+
+    // Partial configurations still load without the flag.
+    let config = unsafe { via_config_load_with_flags(system.as_ptr(), hardware.as_ptr(), profile.as_ptr(), 0) };
+    assert!(!config.is_null(), "{}", last_error());
+    unsafe { via_config_free(config) };
+}
+
+/// Loads `profile.yaml` from a scratch directory holding `files`, between an
+/// empty system and hardware file.
+fn load_profile_chain(test: &str, files: &[(&str, &str)]) -> *mut ViaConfig {
+    let dir = scratch_dir(test);
+    for (name, contents) in files {
+        write_file(&dir, name, contents);
+    }
+    let empty = write_file(&dir, "empty.yaml", "{}");
+    let profile = c_path(&dir.join("profile.yaml"));
+    unsafe { via_config_load(empty.as_ptr(), empty.as_ptr(), profile.as_ptr()) }
+}
+
+#[test]
+fn extends_chain_applies_the_nearest_profile_last() {
+    let config = load_profile_chain(
+        "extends_chain",
+        &[
+            ("base.yaml", "alert-preferences:\n  play-sounds: false\n  volume: 3\nuser-name: base\nlanguage: en\n"),
+            ("family.yaml", "extends: base.yaml\nalert-preferences:\n  volume: 5\nuser-name: family\n"),
+            ("profile.yaml", "extends: family.yaml\nuser-name: ana\n"),
+        ],
+    );
+    assert!(!config.is_null(), "{}", last_error());
+    assert_eq!(get_str(config, "user-name").as_deref(), Ok("ana"));
+    assert_eq!(get_int(config, "alert-preferences.volume"), Ok(5));
+    assert_eq!(get_bool(config, "alert-preferences.play-sounds"), Ok(false));
+    assert_eq!(get_str(config, "language").as_deref(), Ok("en"));
+    assert_eq!(get_str(config, "extends"), Err(ViaConfigStatus::KeyNotFound));
+    unsafe { via_config_free(config) };
+}
+
+#[test]
+fn extends_cycle_is_reported() {
+    let config = load_profile_chain(
+        "extends_cycle",
+        &[("profile.yaml", "extends: other.yaml\nuser-name: a\n"), ("other.yaml", "extends: profile.yaml\nuser-name: b\n")],
+    );
+    assert!(config.is_null());
+    assert_eq!(via_config_last_error_status(), ViaConfigStatus::ParseError);
+    let message = last_error();
+    assert!(message.contains("cycle"), "{}", message);
+    assert!(message.contains("other.yaml\" -> ") && message.ends_with("profile.yaml\""), "{}", message);
+
+    let config = load_profile_chain("extends_self", &[("profile.yaml", "extends: ./profile.yaml\n")]);
+    assert!(config.is_null());
+    assert!(last_error().contains("cycle"), "{}", last_error());
+}
+
+const SCALAR_CAMERA: &str = "camera:\n  resolution: 720p\n  fps: 30\n";
+const MAPPING_CAMERA: &str = "camera:\n  resolution:\n    width: 1280\n    height: 720\n";
+
+#[test]
+fn strict_merge_rejects_a_scalar_replaced_by_a_mapping() {
+    let config = load_files("strict_merge_conflict", SCALAR_CAMERA, MAPPING_CAMERA, "{}", VIA_CONFIG_FLAG_STRICT_MERGE);
+    assert!(config.is_null());
+    assert_eq!(via_config_last_error_status(), ViaConfigStatus::TypeError);
+    assert_eq!(last_error(), "Key 'camera.resolution' is a scalar in an earlier source but a mapping in source 1");
+
+    // Values of the same kind still override each other.
+    let config = load_files("strict_merge_same_kind", SCALAR_CAMERA, "camera:\n  fps: 60\n", "{}", VIA_CONFIG_FLAG_STRICT_MERGE);
+    assert!(!config.is_null(), "{}", last_error());
+    assert_eq!(get_int(config, "camera.fps"), Ok(60));
+    unsafe { via_config_free(config) };
+}
+
+#[test]
+fn lenient_merge_lets_a_mapping_replace_a_scalar() {
+    let config = load_files("lenient_merge", SCALAR_CAMERA, MAPPING_CAMERA, "{}", 0);
+    assert!(!config.is_null(), "{}", last_error());
+    assert_eq!(get_int(config, "camera.resolution.width"), Ok(1280));
+    assert_eq!(get_int(config, "camera.fps"), Ok(30));
+    unsafe { via_config_free(config) };
+}
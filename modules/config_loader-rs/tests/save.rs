@@ -0,0 +1,62 @@
+//! Editing configuration files on disk.
+
+mod support;
+
+use support::*;
+use via_config::*;
+
+#[test]
+fn save_file_rewrites_only_the_value_and_leaves_no_temporary_file() {
+    let dir = scratch_dir("save_file");
+    let original = "# Tuned for the Orange Pi.\nreasoning:\n  llm:\n    context-size: 2048  # tokens\n";
+    let hardware = write_file(&dir, "hardware.yaml", original);
+    let system = write_file(&dir, "system.yaml", "{}");
+    let profile = write_file(&dir, "profile.yaml", "{}");
+
+    unsafe {
+        let config = via_config_load(system.as_ptr(), hardware.as_ptr(), profile.as_ptr());
+        assert!(!config.is_null(), "{}", last_error());
+        let status = via_config_save_file(config, hardware.as_ptr(), c("reasoning.llm.context-size").as_ptr(), c("4096").as_ptr());
+        assert_eq!(status, ViaConfigStatus::Ok);
+        assert_eq!(get_int(config, "reasoning.llm.context-size"), Ok(4096));
+        via_config_free(config);
+    }
+    let saved = std::fs::read_to_string(dir.join("hardware.yaml")).unwrap();
+    assert_eq!(saved, original.replace("2048", "4096"));
+    let entries: Vec<_> = std::fs::read_dir(&dir).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+    assert_eq!(entries.len(), 3, "{:?}", entries);
+}
+
+#[test]
+fn effective_configuration_reloads_unchanged() {
+    let dir = scratch_dir("write_effective");
+    let system = "log-level: info\nthreads:\n  audio: 1\n";
+    let hardware = "camera:\n  device-id: 0\ncloud:\n  api-key: s3cr3t\n";
+    let config = load_files("write_effective_sources", system, hardware, "{}", 0);
+    assert!(!config.is_null(), "{}", last_error());
+    let out = dir.join("effective.yaml");
+    let out_c = c_path(&out);
+    let empty = write_file(&dir, "empty.yaml", "{}");
+    unsafe {
+        assert_eq!(via_config_add_secret_key(config, c("cloud.api-key").as_ptr()), ViaConfigStatus::Ok);
+        assert_eq!(via_config_set_integer(config, c("threads.audio").as_ptr(), 2), ViaConfigStatus::Ok);
+        assert_eq!(via_config_write_effective(config, out_c.as_ptr()), ViaConfigStatus::Ok);
+
+        let reloaded = via_config_load(out_c.as_ptr(), empty.as_ptr(), empty.as_ptr());
+        assert!(!reloaded.is_null(), "{}", last_error());
+        let mut equal = false;
+        assert_eq!(via_config_equals(config, reloaded, &mut equal), ViaConfigStatus::Ok);
+        assert!(equal, "{}", dump(reloaded));
+        assert_eq!(get_str(reloaded, "cloud.api-key").as_deref(), Ok("s3cr3t"));
+        assert_eq!(get_int(reloaded, "threads.audio"), Ok(2));
+        via_config_free(reloaded);
+        via_config_free(config);
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&out).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600, "{:o}", mode);
+    }
+}
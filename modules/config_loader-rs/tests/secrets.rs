@@ -0,0 +1,89 @@
+//! Values read with `$secret_file` never appear in reports or dumps.
+
+mod support;
+
+use std::ptr;
+use support::*;
+use via_config::*;
+
+const SECRET: &str = "s3cr3t-token";
+
+#[test]
+fn secret_file_value_is_redacted_in_diff() {
+    let dir = scratch_dir("secret_diff");
+    write_file(&dir, "api-key", &format!("{}\n", SECRET));
+    let system_a = write_file(&dir, "system_a.yaml", "cloud:\n  api-key: old-token\n  region: eu\n");
+    let system_b = write_file(&dir, "system_b.yaml", "cloud:\n  api-key: { $secret_file: api-key }\n  region: us\n");
+    let hardware = write_file(&dir, "hardware.yaml", "{}");
+    let profile = write_file(&dir, "profile.yaml", "{}");
+
+    let mut report = ptr::null_mut();
+    let status = unsafe {
+        via_config_diff_files(
+            system_a.as_ptr(),
+            hardware.as_ptr(),
+            profile.as_ptr(),
+            system_b.as_ptr(),
+            hardware.as_ptr(),
+            profile.as_ptr(),
+            &mut report,
+        )
+    };
+    assert_eq!(status, ViaConfigStatus::Ok);
+    let report = take_string(report);
+    assert!(report.contains("cloud.api-key: *** -> ***"), "{}", report);
+    assert!(report.contains("cloud.region: eu -> us"), "{}", report);
+    assert!(!report.contains(SECRET), "{}", report);
+    assert!(!report.contains("old-token"), "{}", report);
+}
+
+#[test]
+fn secret_file_value_is_redacted_in_dump() {
+    let dir = scratch_dir("secret_dump");
+    write_file(&dir, "api-key", SECRET);
+    let system = write_file(&dir, "system.yaml", "cloud:\n  api-key: { $secret_file: api-key }\n  region: eu\n");
+    let hardware = write_file(&dir, "hardware.yaml", "{}");
+    let profile = write_file(&dir, "profile.yaml", "{}");
+
+    let config = unsafe { via_config_load(system.as_ptr(), hardware.as_ptr(), profile.as_ptr()) };
+    assert!(!config.is_null());
+    // The value itself is available to the modules that need it.
+    assert_eq!(get_str(config, "cloud.api-key").as_deref(), Ok(SECRET));
+    let yaml = dump(config);
+    assert!(yaml.contains("api-key: '***'"), "{}", yaml);
+    assert!(!yaml.contains(SECRET), "{}", yaml);
+    unsafe { via_config_free(config) };
+}
+
+#[test]
+fn secret_file_value_is_redacted_in_reports() {
+    let dir = scratch_dir("secret_reports");
+    write_file(&dir, "api-key", SECRET);
+    let system = write_file(&dir, "system.yaml", "cloud:\n  api-key: { $secret_file: api-key }\n");
+    let hardware = write_file(&dir, "hardware.yaml", "{}");
+    let profile = write_file(&dir, "profile.yaml", "{}");
+
+    extern "C" fn reject(_: *const std::ffi::c_char) -> bool {
+        false
+    }
+
+    let config = unsafe { via_config_load(system.as_ptr(), hardware.as_ptr(), profile.as_ptr()) };
+    assert!(!config.is_null());
+    unsafe {
+        assert_eq!(via_config_register_validator(config, c("cloud.api-key").as_ptr(), Some(reject)), ViaConfigStatus::Ok);
+        let mut failures = ptr::null_mut();
+        assert_eq!(via_config_run_validators(config, &mut failures), ViaConfigStatus::ValidationFailed);
+        let failures = take_string(failures);
+        assert!(!failures.contains(SECRET), "{}", failures);
+
+        let mut explanation = ptr::null_mut();
+        assert_eq!(via_config_explain_key(config, c("cloud.api-key").as_ptr(), &mut explanation), ViaConfigStatus::Ok);
+        let explanation = take_string(explanation);
+        assert!(!explanation.contains(SECRET), "{}", explanation);
+
+        let patch = c(r#"[{"op": "test", "path": "/cloud/api-key", "value": "guess"}]"#);
+        assert_eq!(via_config_apply_json_patch(config, patch.as_ptr()), ViaConfigStatus::ValidationFailed);
+        assert!(!last_error().contains(SECRET), "{}", last_error());
+        via_config_free(config);
+    }
+}
@@ -0,0 +1,105 @@
+//! Helpers shared by the integration tests, which drive the library through
+//! its C ABI exactly as the C++ side does.
+
+#![allow(dead_code)]
+
+use std::ffi::{c_char, CStr, CString};
+use std::path::{Path, PathBuf};
+use via_config::*;
+
+/// Creates an empty scratch directory unique to the test and process.
+pub fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("via_config_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Writes `contents` to `name` in `dir` and returns the file's path.
+pub fn write_file(dir: &Path, name: &str, contents: &str) -> CString {
+    let path = dir.join(name);
+    std::fs::write(&path, contents).unwrap();
+    c_path(&path)
+}
+
+pub fn c_path(path: &Path) -> CString {
+    CString::new(path.to_str().unwrap()).unwrap()
+}
+
+pub fn c(s: &str) -> CString {
+    CString::new(s).unwrap()
+}
+
+/// Returns the path of a file in `tests/fixtures`.
+pub fn fixture(name: &str) -> CString {
+    c_path(&Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name))
+}
+
+/// Loads three in-memory documents.
+pub fn load_strings(system: &str, hardware: &str, profile: &str) -> *mut ViaConfig {
+    unsafe { via_config_load_from_strings(c(system).as_ptr(), c(hardware).as_ptr(), c(profile).as_ptr()) }
+}
+
+/// Writes three documents to files in a scratch directory named after `test`
+/// and loads them with `flags`.
+pub fn load_files(test: &str, system: &str, hardware: &str, profile: &str, flags: u32) -> *mut ViaConfig {
+    let dir = scratch_dir(test);
+    let system = write_file(&dir, "system.yaml", system);
+    let hardware = write_file(&dir, "hardware.yaml", hardware);
+    let profile = write_file(&dir, "profile.yaml", profile);
+    unsafe { via_config_load_with_flags(system.as_ptr(), hardware.as_ptr(), profile.as_ptr(), flags) }
+}
+
+/// Takes ownership of a string returned by the library.
+pub fn take_string(s: *mut c_char) -> String {
+    assert!(!s.is_null());
+    unsafe {
+        let owned = CStr::from_ptr(s).to_str().unwrap().to_string();
+        via_config_free_string(s);
+        owned
+    }
+}
+
+pub fn last_error() -> String {
+    unsafe { CStr::from_ptr(via_config_last_error_message()).to_str().unwrap().to_string() }
+}
+
+pub fn get_int(config: *const ViaConfig, key: &str) -> Result<i64, ViaConfigStatus> {
+    let mut value = 0;
+    match unsafe { via_config_get_integer(config, c(key).as_ptr(), &mut value) } {
+        ViaConfigStatus::Ok => Ok(value),
+        status => Err(status),
+    }
+}
+
+pub fn get_float(config: *const ViaConfig, key: &str) -> Result<f64, ViaConfigStatus> {
+    let mut value = 0.0;
+    match unsafe { via_config_get_float(config, c(key).as_ptr(), &mut value) } {
+        ViaConfigStatus::Ok => Ok(value),
+        status => Err(status),
+    }
+}
+
+pub fn get_bool(config: *const ViaConfig, key: &str) -> Result<bool, ViaConfigStatus> {
+    let mut value = false;
+    match unsafe { via_config_get_boolean(config, c(key).as_ptr(), &mut value) } {
+        ViaConfigStatus::Ok => Ok(value),
+        status => Err(status),
+    }
+}
+
+pub fn get_str(config: *const ViaConfig, key: &str) -> Result<String, ViaConfigStatus> {
+    let mut buf = [0 as c_char; 256];
+    let mut needed = 0;
+    match unsafe { via_config_copy_string(config, c(key).as_ptr(), buf.as_mut_ptr(), buf.len(), &mut needed) } {
+        ViaConfigStatus::Ok => Ok(unsafe { CStr::from_ptr(buf.as_ptr()) }.to_str().unwrap().to_string()),
+        status => Err(status),
+    }
+}
+
+/// Dumps the whole configuration as YAML.
+pub fn dump(config: *const ViaConfig) -> String {
+    let mut out = std::ptr::null_mut();
+    assert_eq!(unsafe { via_config_dump_yaml(config, c("").as_ptr(), 0, 0, &mut out) }, ViaConfigStatus::Ok);
+    take_string(out)
+}
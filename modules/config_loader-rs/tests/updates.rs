@@ -0,0 +1,197 @@
+//! Staged updates, resets and runtime overrides on a loaded handle.
+
+mod support;
+
+use support::*;
+use via_config::*;
+
+#[test]
+fn commit_update_into_frozen_handle_is_rejected() {
+    let config = load_strings("camera:\n  fps: 30\n", "{}", "{}");
+    unsafe {
+        assert_eq!(via_config_begin_update(config), ViaConfigStatus::Ok);
+        assert_eq!(via_config_set_integer(config, c("camera.fps").as_ptr(), 15), ViaConfigStatus::Ok);
+        assert_eq!(via_config_freeze(config), ViaConfigStatus::Ok);
+        assert_eq!(via_config_commit_update(config), ViaConfigStatus::Frozen);
+        assert_eq!(get_int(config, "camera.fps"), Ok(30));
+        // The update stays open and can still be discarded.
+        assert_eq!(via_config_abort_update(config), ViaConfigStatus::Ok);
+        assert_eq!(get_int(config, "camera.fps"), Ok(30));
+        via_config_free(config);
+    }
+}
+
+#[test]
+fn remove_inside_an_update_sees_staged_changes() {
+    let config = load_strings("camera:\n  fps: 30\n  width: 1280\n", "{}", "{}");
+    unsafe {
+        assert_eq!(via_config_begin_update(config), ViaConfigStatus::Ok);
+        // A key set earlier in the update can be removed before the commit.
+        assert_eq!(via_config_set_integer(config, c("camera.height").as_ptr(), 720), ViaConfigStatus::Ok);
+        assert_eq!(via_config_remove_key(config, c("camera.height").as_ptr()), ViaConfigStatus::Ok);
+        // A key already removed in the update cannot be removed twice.
+        assert_eq!(via_config_remove_key(config, c("camera.fps").as_ptr()), ViaConfigStatus::Ok);
+        assert_eq!(via_config_remove_key(config, c("camera.fps").as_ptr()), ViaConfigStatus::KeyNotFound);
+        // Readers keep seeing the committed tree until the commit.
+        assert_eq!(get_int(config, "camera.fps"), Ok(30));
+        assert_eq!(get_int(config, "camera.height"), Err(ViaConfigStatus::KeyNotFound));
+
+        assert_eq!(via_config_commit_update(config), ViaConfigStatus::Ok);
+        assert_eq!(get_int(config, "camera.fps"), Err(ViaConfigStatus::KeyNotFound));
+        assert_eq!(get_int(config, "camera.height"), Err(ViaConfigStatus::KeyNotFound));
+        assert_eq!(get_int(config, "camera.width"), Ok(1280));
+        via_config_free(config);
+    }
+}
+
+#[test]
+fn abort_update_discards_staged_changes() {
+    let config = load_strings("camera:\n  fps: 30\n  width: 1280\n", "{}", "{}");
+    unsafe {
+        assert_eq!(via_config_begin_update(config), ViaConfigStatus::Ok);
+        assert_eq!(via_config_set_integer(config, c("camera.fps").as_ptr(), 15), ViaConfigStatus::Ok);
+        assert_eq!(via_config_remove_key(config, c("camera.width").as_ptr()), ViaConfigStatus::Ok);
+        assert_eq!(via_config_abort_update(config), ViaConfigStatus::Ok);
+        assert_eq!(get_int(config, "camera.fps"), Ok(30));
+        assert_eq!(get_int(config, "camera.width"), Ok(1280));
+
+        // The update is closed: a second abort or a commit has nothing to act on,
+        // and later changes apply immediately.
+        assert_eq!(via_config_abort_update(config), ViaConfigStatus::InternalError);
+        assert_eq!(via_config_commit_update(config), ViaConfigStatus::InternalError);
+        assert_eq!(via_config_set_integer(config, c("camera.fps").as_ptr(), 24), ViaConfigStatus::Ok);
+        assert_eq!(get_int(config, "camera.fps"), Ok(24));
+        via_config_free(config);
+    }
+}
+
+const LAYERED_SYSTEM: &str = "camera:\n  fps: 30\n  width: 1280\n";
+const LAYERED_HARDWARE: &str = "camera:\n  fps: 60\n  exposure: auto\n";
+
+fn source_of(config: *const ViaConfig, key: &str) -> i32 {
+    let mut source = -1;
+    assert_eq!(unsafe { via_config_get_source(config, c(key).as_ptr(), &mut source) }, ViaConfigStatus::Ok);
+    source
+}
+
+#[test]
+fn reset_key_is_staged_inside_an_update() {
+    let config = load_strings(LAYERED_SYSTEM, LAYERED_HARDWARE, "{}");
+    assert!(!config.is_null());
+    unsafe {
+        assert_eq!(via_config_begin_update(config), ViaConfigStatus::Ok);
+        assert_eq!(via_config_reset_key(config, c("camera.fps").as_ptr()), ViaConfigStatus::Ok);
+        assert_eq!(via_config_reset_key(config, c("camera.exposure").as_ptr()), ViaConfigStatus::Ok);
+        // Nothing changes until the update is committed.
+        assert_eq!(get_int(config, "camera.fps"), Ok(60));
+        assert_eq!(get_str(config, "camera.exposure").as_deref(), Ok("auto"));
+        assert_eq!(via_config_commit_update(config), ViaConfigStatus::Ok);
+        assert_eq!(get_int(config, "camera.fps"), Ok(30));
+        assert_eq!(source_of(config, "camera.fps"), 0);
+        assert_eq!(get_str(config, "camera.exposure"), Err(ViaConfigStatus::KeyNotFound));
+        via_config_free(config);
+    }
+}
+
+#[test]
+fn reset_key_clears_runtime_origins() {
+    let config = load_strings(LAYERED_SYSTEM, LAYERED_HARDWARE, "{}");
+    assert!(!config.is_null());
+    unsafe {
+        let patch = c(r#"[{"op": "replace", "path": "/camera/fps", "value": 15}]"#);
+        assert_eq!(via_config_apply_json_patch(config, patch.as_ptr()), ViaConfigStatus::Ok);
+        let mut explanation = std::ptr::null_mut();
+        assert_eq!(via_config_explain_key(config, c("camera.fps").as_ptr(), &mut explanation), ViaConfigStatus::Ok);
+        assert!(take_string(explanation).contains("JSON patch"));
+
+        assert_eq!(via_config_reset_key(config, c("camera").as_ptr()), ViaConfigStatus::Ok);
+        assert_eq!(get_int(config, "camera.fps"), Ok(30));
+        let mut explanation = std::ptr::null_mut();
+        assert_eq!(via_config_explain_key(config, c("camera.fps").as_ptr(), &mut explanation), ViaConfigStatus::Ok);
+        let explanation = take_string(explanation);
+        assert!(!explanation.contains("JSON patch"), "{}", explanation);
+        via_config_free(config);
+    }
+}
+
+#[test]
+fn reset_key_prunes_parents_left_empty() {
+    let config = load_strings("camera:\n  fps: 30\n", "audio:\n  filters:\n    noise: on\n", "{}");
+    assert!(!config.is_null());
+    unsafe {
+        assert_eq!(via_config_reset_key(config, c("audio.filters.noise").as_ptr()), ViaConfigStatus::Ok);
+        assert_eq!(get_str(config, "audio.filters.noise"), Err(ViaConfigStatus::KeyNotFound));
+        let yaml = dump(config);
+        assert!(!yaml.contains("audio"), "{}", yaml);
+        via_config_free(config);
+    }
+}
+
+/// The default depth limit of `via_config_set_max_depth`.
+const DEFAULT_DEPTH_LIMIT: usize = 128;
+
+#[test]
+fn overrides_are_applied_all_or_nothing() {
+    let config = load_strings("camera:\n  fps: 30\n", "{}", "{}");
+    assert!(!config.is_null());
+    let too_deep = format!("{}=1", vec!["level"; DEFAULT_DEPTH_LIMIT + 1].join("."));
+    for bad in ["camera.width", too_deep.as_str()] {
+        let args = [c("camera.fps=15"), c(bad)];
+        let ptrs: Vec<_> = args.iter().map(|arg| arg.as_ptr()).collect();
+        assert_ne!(unsafe { via_config_apply_overrides(config, ptrs.as_ptr(), ptrs.len()) }, ViaConfigStatus::Ok);
+        assert_eq!(get_int(config, "camera.fps"), Ok(30), "{}", bad);
+    }
+    unsafe { via_config_free(config) };
+}
+
+fn apply_patch(config: *mut ViaConfig, patch: &str) -> ViaConfigStatus {
+    unsafe { via_config_apply_json_patch(config, c(patch).as_ptr()) }
+}
+
+const PATCHED: &str = "camera:\n  fps: 30\n  width: 1280\nlabels: [person, stairs]\n";
+
+#[test]
+fn failing_patch_operation_leaves_the_handle_unchanged() {
+    let config = load_strings(PATCHED, "{}", "{}");
+    let before = dump(config);
+    let failing_test = r#"[
+        {"op": "replace", "path": "/camera/fps", "value": 15},
+        {"op": "remove", "path": "/camera/width"},
+        {"op": "test", "path": "/labels/0", "value": "car"}
+    ]"#;
+    assert_eq!(apply_patch(config, failing_test), ViaConfigStatus::ValidationFailed);
+    assert!(last_error().contains("#3 (test \"/labels/0\"): the value is person, not car"), "{}", last_error());
+    assert_eq!(dump(config), before);
+
+    let missing_path = r#"[{"op": "add", "path": "/camera/exposure", "value": "auto"}, {"op": "replace", "path": "/microphone/gain", "value": 2}]"#;
+    assert_eq!(apply_patch(config, missing_path), ViaConfigStatus::KeyNotFound);
+    assert_eq!(dump(config), before);
+    unsafe { via_config_free(config) };
+}
+
+#[test]
+fn passing_patch_test_lets_the_patch_apply() {
+    let config = load_strings(PATCHED, "{}", "{}");
+    let patch = r#"[{"op": "test", "path": "/camera/fps", "value": 30.0}, {"op": "replace", "path": "/camera/fps", "value": 15}]"#;
+    assert_eq!(apply_patch(config, patch), ViaConfigStatus::Ok);
+    assert_eq!(get_int(config, "camera.fps"), Ok(15));
+    unsafe { via_config_free(config) };
+}
+
+#[test]
+fn patch_move_renames_a_key() {
+    let config = load_strings(PATCHED, "{}", "{}");
+    let patch = r#"[{"op": "move", "from": "/camera/fps", "path": "/camera/max-fps"}, {"op": "move", "from": "/labels/1", "path": "/labels/0"}]"#;
+    assert_eq!(apply_patch(config, patch), ViaConfigStatus::Ok);
+    assert_eq!(get_int(config, "camera.fps"), Err(ViaConfigStatus::KeyNotFound));
+    assert_eq!(get_int(config, "camera.max-fps"), Ok(30));
+    assert_eq!(get_int(config, "camera.width"), Ok(1280));
+    assert!(dump(config).contains("labels:\n- stairs\n- person\n"), "{}", dump(config));
+
+    let before = dump(config);
+    assert_eq!(apply_patch(config, r#"[{"op": "move", "from": "/camera/fps", "path": "/fps"}]"#), ViaConfigStatus::KeyNotFound);
+    assert!(last_error().contains("the \"from\" path does not exist"), "{}", last_error());
+    assert_eq!(apply_patch(config, r#"[{"op": "move", "from": "/camera", "path": "/camera/inner"}]"#), ViaConfigStatus::ParseError);
+    assert_eq!(dump(config), before);
+    unsafe { via_config_free(config) };
+}